@@ -1,12 +1,705 @@
-#[derive(Debug, PartialEq, Eq)]
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+#[cfg(test)]
+use mockall::automock;
+use thiserror::Error;
+
+use crate::commands;
+use crate::db::DbError;
+use crate::installer::InstallError;
+use crate::logging::{Level, LogFilter};
+use crate::runtime::{Actor, Handle};
+use crate::workflow::WorkflowError;
+
+/// Actor
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CLI {
     Idle,
     RunningCommand(String),
     LaunchingTUI,
 }
 
+/// Message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CLIMsg {
+    /// Parse the process arguments and execute the requested subcommand
+    Start,
+    /// Execute a parsed subcommand
+    Execute(CliCommand),
+    /// The command (or the TUI it launched) has finished
+    Finish,
+}
+
+/// Subcommands understood by the `riptide` binary (FR-CLI-03)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    /// Open the dashboard, optionally stepping through a pipeline
+    Launch {
+        pipeline: Option<String>,
+    },
+    List,
+    Install {
+        source: String,
+        sha256: Option<String>,
+        force: bool,
+    },
+    Uninstall {
+        name: String,
+    },
+    Upgrade {
+        name: String,
+    },
+    Run {
+        module: String,
+        args: Vec<String>,
+    },
+    Logs {
+        filter: LogFilter,
+        follow: bool,
+    },
+    Graph {
+        format: GraphFormat,
+    },
+    History(HistoryCommand),
+}
+
+/// What `riptide history` does with past runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryCommand {
+    List,
+    Show { run: String },
+    Diff { before: String, after: String },
+    Rerun { run: String },
+}
+
+/// Output format of `riptide graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Mermaid,
+    Dot,
+}
+
+/// Errors, each mapped to the process exit code by [`CliError::exit_code`]:
+///
+/// | Code  | Meaning                                                      |
+/// |-------|--------------------------------------------------------------|
+/// | `0`   | Success                                                      |
+/// | `1`   | Any other error, such as an invalid pipeline or install      |
+/// | `2`   | Invalid command line                                         |
+/// | `3`   | The tool or run is unknown, or the tool entry is missing     |
+/// | `4`   | The tool could not be started                                |
+/// | `5`   | The tool exited with a failure or was killed                 |
+/// | `6`   | A pipeline stage did not succeed                             |
+/// | `130` | Interrupted with Ctrl-C                                      |
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("{0}")]
+    Usage(#[from] clap::Error),
+
+    #[error("Cannot run `{requested}` while `{running}` is still running")]
+    Busy { requested: String, running: String },
+
+    #[error("Failed to run `{command}`: {reason}")]
+    Spawn { command: String, reason: String },
+
+    #[error("`{command}` exited with status {code}")]
+    CommandFailed { command: String, code: i32 },
+
+    #[error("Unknown tool `{0}`; see `riptide list`")]
+    UnknownTool(String),
+
+    #[error("Entry `{entry}` of tool `{tool}` was not found")]
+    MissingEntry { tool: String, entry: String },
+
+    #[error("`{0}` was interrupted")]
+    Interrupted(String),
+
+    #[error("Cannot read logs in {0}: {1}")]
+    Logs(PathBuf, std::io::Error),
+
+    #[error(transparent)]
+    Workflow(#[from] WorkflowError),
+
+    /// Boxed as install errors carry TOML parse errors, which are large
+    #[error(transparent)]
+    Install(Box<InstallError>),
+
+    #[error("Pipeline `{pipeline}` failed at: {}", .failed.join(", "))]
+    PipelineFailed {
+        pipeline: String,
+        failed: Vec<String>,
+    },
+
+    /// Boxed as database errors are large
+    #[error(transparent)]
+    Db(Box<DbError>),
+
+    #[error("Unknown run `{0}`; see `riptide history`")]
+    UnknownRun(String),
+
+    #[error("Run `{0}` was recorded without its pipeline and cannot be run again")]
+    NotRerunnable(String),
+}
+
+/// Implementation
 impl CLI {
-    pub fn start(self: &mut CLI) {
-        todo!();
+    /// Initialize a new CLI in its Idle state
+    pub fn new() -> Self {
+        Self::Idle
+    }
+
+    /// Parse the process arguments and execute the requested subcommand
+    pub fn start(&mut self) -> Result<(), CliError> {
+        self.start_from(std::env::args_os())
+    }
+
+    /// Parse `args` (including the binary name) and execute the requested subcommand.
+    ///
+    /// Running without a subcommand prints the help text and leaves the CLI Idle.
+    pub fn start_from<I, T>(&mut self, args: I) -> Result<(), CliError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        match Self::parse(args)? {
+            Some(command) => self.send(CLIMsg::Execute(command)),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse `args` (including the binary name) without executing anything.
+    ///
+    /// Returns `None` once help or version information has been printed.
+    pub fn parse<I, T>(args: I) -> Result<Option<CliCommand>, CliError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let matches = match Self::command().try_get_matches_from(args) {
+            Ok(matches) => matches,
+            Err(e) if e.kind() == clap::error::ErrorKind::DisplayHelp => {
+                let _ = e.print();
+                return Ok(None);
+            }
+            Err(e) if e.kind() == clap::error::ErrorKind::DisplayVersion => {
+                let _ = e.print();
+                return Ok(None);
+            }
+            Err(e) => return Err(CliError::Usage(e)),
+        };
+
+        let command = CliCommand::from_matches(&matches);
+        if command.is_none() {
+            let _ = Self::command().print_help();
+        }
+        Ok(command)
+    }
+
+    /// Main entry point to handle messages to the enum
+    pub fn send(&mut self, msg: CLIMsg) -> Result<(), CliError> {
+        match msg {
+            CLIMsg::Start => self.start(),
+            CLIMsg::Execute(command) => {
+                if let CLI::RunningCommand(running) = self {
+                    return Err(CliError::Busy {
+                        requested: command.name().into(),
+                        running: running.clone(),
+                    });
+                }
+
+                *self = CLI::RunningCommand(command.name().into());
+
+                // Launching hands control to the TUI, which sends Finish once it closes
+                if let CliCommand::Launch { .. } = command {
+                    *self = CLI::LaunchingTUI;
+                    return Ok(());
+                }
+
+                let result = commands::execute(&command);
+                *self = CLI::Idle;
+                result
+            }
+            CLIMsg::Finish => {
+                *self = CLI::Idle;
+                Ok(())
+            }
+        }
+    }
+
+    /// The clap definition of the `riptide` command line
+    pub fn command() -> Command {
+        Command::new("riptide")
+            .version(env!("CARGO_PKG_VERSION"))
+            .about("A terminal toolkit for orchestrating end-to-end MLOps workflows")
+            .subcommand(
+                Command::new("launch")
+                    .about("Open the riptide TUI dashboard")
+                    .arg(
+                        Arg::new("pipeline")
+                            .help("A YAML pipeline to step through, pausing before each stage")
+                            .value_name("PIPELINE"),
+                    ),
+            )
+            .subcommand(Command::new("list").about("List the available tools"))
+            .subcommand(
+                Command::new("install")
+                    .about("Install a tool into ~/.riptide/tools")
+                    .arg(
+                        Arg::new("source")
+                            .help(
+                                "Directory, git URL or .tar.gz archive holding a riptide-tool.toml",
+                            )
+                            .required(true)
+                            .value_name("SOURCE"),
+                    )
+                    .arg(
+                        Arg::new("sha256")
+                            .long("sha256")
+                            .help("Refuse to install unless the source has this SHA-256")
+                            .value_name("HEX"),
+                    )
+                    .arg(
+                        Arg::new("force")
+                            .long("force")
+                            .help("Replace the tool if it is already installed")
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new("uninstall")
+                    .about("Remove an installed tool")
+                    .arg(
+                        Arg::new("name")
+                            .help("Name of the tool")
+                            .required(true)
+                            .value_name("NAME"),
+                    ),
+            )
+            .subcommand(
+                Command::new("upgrade")
+                    .about("Reinstall a tool from its source if it changed")
+                    .arg(
+                        Arg::new("name")
+                            .help("Name of the tool")
+                            .required(true)
+                            .value_name("NAME"),
+                    ),
+            )
+            .subcommand(
+                Command::new("run")
+                    .about("Run a submodule or a YAML pipeline directly without the TUI")
+                    .arg(
+                        Arg::new("module")
+                            .help("The submodule (e.g. sail, dock) or pipeline file to run")
+                            .required(true)
+                            .value_name("MODULE"),
+                    )
+                    .arg(
+                        Arg::new("args")
+                            .help("Arguments forwarded to the submodule")
+                            .value_name("ARGS")
+                            .num_args(0..)
+                            .last(true)
+                            .action(ArgAction::Append),
+                    ),
+            )
+            .subcommand(
+                Command::new("logs")
+                    .about("View logs in plain mode")
+                    .arg(
+                        Arg::new("run")
+                            .long("run")
+                            .help("Only show the run with this id")
+                            .value_name("ID"),
+                    )
+                    .arg(
+                        Arg::new("stage")
+                            .long("stage")
+                            .help("Only show this stage or tool")
+                            .value_name("NAME"),
+                    )
+                    .arg(
+                        Arg::new("grep")
+                            .long("grep")
+                            .help("Only show records mentioning a keyword, ignoring case")
+                            .value_name("KEYWORD"),
+                    )
+                    .arg(
+                        Arg::new("level")
+                            .long("level")
+                            .help("Only show records at this level or above")
+                            .value_parser(["debug", "info", "warn", "error"]),
+                    )
+                    .arg(
+                        Arg::new("follow")
+                            .long("follow")
+                            .short('f')
+                            .help("Keep printing records as they are written")
+                            .action(ArgAction::SetTrue),
+                    ),
+            )
+            .subcommand(
+                Command::new("history")
+                    .about("List past pipeline runs, compare them or run one again")
+                    .subcommand(
+                        Command::new("show")
+                            .about("Show the stages, metrics and outputs of a run")
+                            .arg(run_arg()),
+                    )
+                    .subcommand(
+                        Command::new("diff")
+                            .about("Compare the parameters, metrics and outputs of two runs")
+                            .arg(
+                                Arg::new("before")
+                                    .help("Id of the earlier run")
+                                    .required(true)
+                                    .value_name("RUN"),
+                            )
+                            .arg(
+                                Arg::new("after")
+                                    .help("Id of the later run")
+                                    .required(true)
+                                    .value_name("RUN"),
+                            ),
+                    )
+                    .subcommand(
+                        Command::new("rerun")
+                            .about("Run a past pipeline again with the same configuration")
+                            .arg(run_arg()),
+                    ),
+            )
+            .subcommand(
+                Command::new("graph")
+                    .about("Print the User state machine diagram")
+                    .hide(true)
+                    .arg(
+                        Arg::new("format")
+                            .long("format")
+                            .value_parser(["mermaid", "dot"])
+                            .default_value("mermaid"),
+                    ),
+            )
+    }
+}
+
+/// The run id argument of `riptide history` subcommands
+fn run_arg() -> Arg {
+    Arg::new("run")
+        .help("Id of the run, as listed by `riptide history`")
+        .required(true)
+        .value_name("RUN")
+}
+
+impl Default for CLI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Actor for CLI {
+    type Msg = CLIMsg;
+    type Error = CliError;
+
+    fn handle(&mut self, msg: CLIMsg) -> Result<(), CliError> {
+        self.send(msg)
+    }
+}
+
+/// What other actors need from the CLI; mocked in tests
+#[cfg_attr(test, automock)]
+pub trait CliPort {
+    fn start(&mut self);
+    fn get_state(&self) -> CLI;
+}
+
+impl CliPort for Handle<CLI> {
+    fn start(&mut self) {
+        let _ = self.send(CLIMsg::Start);
+    }
+
+    fn get_state(&self) -> CLI {
+        self.state().clone()
+    }
+}
+
+impl CliError {
+    /// Exit code of the `riptide` process for this error
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::UnknownTool(_) | CliError::MissingEntry { .. } | CliError::UnknownRun(_) => 3,
+            CliError::Spawn { .. } => 4,
+            CliError::CommandFailed { .. } => 5,
+            CliError::PipelineFailed { .. } => 6,
+            CliError::Interrupted(_) => 130,
+            CliError::Busy { .. }
+            | CliError::Workflow(_)
+            | CliError::Install(_)
+            | CliError::Logs(..)
+            | CliError::Db(_)
+            | CliError::NotRerunnable(_) => 1,
+        }
+    }
+}
+
+impl From<InstallError> for CliError {
+    fn from(error: InstallError) -> Self {
+        CliError::Install(Box::new(error))
+    }
+}
+
+impl From<DbError> for CliError {
+    fn from(error: DbError) -> Self {
+        CliError::Db(Box::new(error))
+    }
+}
+
+impl CliCommand {
+    /// Build a command from parsed matches, `None` when no subcommand was given
+    pub fn from_matches(matches: &ArgMatches) -> Option<Self> {
+        let command = match matches.subcommand()? {
+            ("launch", sub) => CliCommand::Launch {
+                pipeline: sub.get_one::<String>("pipeline").cloned(),
+            },
+            ("list", _) => CliCommand::List,
+            ("install", sub) => CliCommand::Install {
+                source: sub.get_one::<String>("source")?.clone(),
+                sha256: sub.get_one::<String>("sha256").cloned(),
+                force: sub.get_flag("force"),
+            },
+            ("uninstall", sub) => CliCommand::Uninstall {
+                name: sub.get_one::<String>("name")?.clone(),
+            },
+            ("upgrade", sub) => CliCommand::Upgrade {
+                name: sub.get_one::<String>("name")?.clone(),
+            },
+            ("run", sub) => CliCommand::Run {
+                module: sub.get_one::<String>("module")?.clone(),
+                args: sub
+                    .get_many::<String>("args")
+                    .map(|args| args.cloned().collect())
+                    .unwrap_or_default(),
+            },
+            ("logs", sub) => CliCommand::Logs {
+                filter: LogFilter {
+                    run: sub.get_one::<String>("run").cloned(),
+                    stage: sub.get_one::<String>("stage").cloned(),
+                    grep: sub.get_one::<String>("grep").cloned(),
+                    min_level: sub
+                        .get_one::<String>("level")
+                        .and_then(|level| level.parse::<Level>().ok()),
+                },
+                follow: sub.get_flag("follow"),
+            },
+            ("graph", sub) => CliCommand::Graph {
+                format: match sub.get_one::<String>("format")?.as_str() {
+                    "dot" => GraphFormat::Dot,
+                    _ => GraphFormat::Mermaid,
+                },
+            },
+            ("history", sub) => CliCommand::History(match sub.subcommand() {
+                Some(("show", show)) => HistoryCommand::Show {
+                    run: show.get_one::<String>("run")?.clone(),
+                },
+                Some(("diff", diff)) => HistoryCommand::Diff {
+                    before: diff.get_one::<String>("before")?.clone(),
+                    after: diff.get_one::<String>("after")?.clone(),
+                },
+                Some(("rerun", rerun)) => HistoryCommand::Rerun {
+                    run: rerun.get_one::<String>("run")?.clone(),
+                },
+                _ => HistoryCommand::List,
+            }),
+            _ => return None,
+        };
+
+        Some(command)
+    }
+
+    /// The subcommand name as typed on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            CliCommand::Launch { .. } => "launch",
+            CliCommand::List => "list",
+            CliCommand::Install { .. } => "install",
+            CliCommand::Uninstall { .. } => "uninstall",
+            CliCommand::Upgrade { .. } => "upgrade",
+            CliCommand::Run { .. } => "run",
+            CliCommand::Logs { .. } => "logs",
+            CliCommand::Graph { .. } => "graph",
+            CliCommand::History(_) => "history",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Option<CliCommand> {
+        let matches = CLI::command()
+            .try_get_matches_from(args)
+            .expect("arguments should parse");
+        CliCommand::from_matches(&matches)
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        assert_eq!(
+            parse(&["riptide", "launch"]),
+            Some(CliCommand::Launch { pipeline: None })
+        );
+        assert_eq!(
+            parse(&["riptide", "launch", "churn.yaml"]),
+            Some(CliCommand::Launch {
+                pipeline: Some("churn.yaml".into())
+            })
+        );
+        assert_eq!(parse(&["riptide", "list"]), Some(CliCommand::List));
+        assert_eq!(
+            parse(&["riptide", "logs"]),
+            Some(CliCommand::Logs {
+                filter: LogFilter::default(),
+                follow: false,
+            })
+        );
+        assert_eq!(
+            parse(&[
+                "riptide", "logs", "--run", "42", "--stage", "fit", "--grep", "nan", "-f"
+            ]),
+            Some(CliCommand::Logs {
+                filter: LogFilter {
+                    run: Some("42".into()),
+                    stage: Some("fit".into()),
+                    grep: Some("nan".into()),
+                    min_level: None,
+                },
+                follow: true,
+            })
+        );
+        assert_eq!(
+            parse(&["riptide", "install", "./sail"]),
+            Some(CliCommand::Install {
+                source: "./sail".into(),
+                sha256: None,
+                force: false,
+            })
+        );
+        assert_eq!(
+            parse(&[
+                "riptide", "install", "sail.tgz", "--sha256", "ab12", "--force"
+            ]),
+            Some(CliCommand::Install {
+                source: "sail.tgz".into(),
+                sha256: Some("ab12".into()),
+                force: true,
+            })
+        );
+        assert_eq!(
+            parse(&["riptide", "upgrade", "sail"]),
+            Some(CliCommand::Upgrade {
+                name: "sail".into()
+            })
+        );
+        assert_eq!(
+            parse(&["riptide", "graph", "--format", "dot"]),
+            Some(CliCommand::Graph {
+                format: GraphFormat::Dot
+            })
+        );
+        assert_eq!(parse(&["riptide"]), None);
+    }
+
+    #[test]
+    fn test_parse_run_forwards_args() {
+        assert_eq!(
+            parse(&["riptide", "run", "sail", "--", "data.csv", "--verbose"]),
+            Some(CliCommand::Run {
+                module: "sail".into(),
+                args: vec!["data.csv".into(), "--verbose".into()],
+            })
+        );
+        assert_eq!(
+            parse(&["riptide", "run", "dock"]),
+            Some(CliCommand::Run {
+                module: "dock".into(),
+                args: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_history() {
+        assert_eq!(
+            parse(&["riptide", "history"]),
+            Some(CliCommand::History(HistoryCommand::List))
+        );
+        assert_eq!(
+            parse(&["riptide", "history", "show", "17"]),
+            Some(CliCommand::History(HistoryCommand::Show {
+                run: "17".into()
+            }))
+        );
+        assert_eq!(
+            parse(&["riptide", "history", "diff", "17", "18"]),
+            Some(CliCommand::History(HistoryCommand::Diff {
+                before: "17".into(),
+                after: "18".into(),
+            }))
+        );
+        assert_eq!(
+            parse(&["riptide", "history", "rerun", "17"]),
+            Some(CliCommand::History(HistoryCommand::Rerun {
+                run: "17".into()
+            }))
+        );
+        assert!(
+            CLI::command()
+                .try_get_matches_from(["riptide", "history", "diff", "17"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_usage_error() {
+        let mut cli = CLI::new();
+        let result = cli.start_from(["riptide", "sink"]);
+        assert!(matches!(result, Err(CliError::Usage(_))));
+        assert_eq!(cli, CLI::Idle);
+    }
+
+    #[test]
+    fn test_launch_transitions_to_launching_tui() {
+        let mut cli = CLI::new();
+        cli.start_from(["riptide", "launch"]).unwrap();
+        assert_eq!(cli, CLI::LaunchingTUI);
+
+        cli.send(CLIMsg::Finish).unwrap();
+        assert_eq!(cli, CLI::Idle);
+    }
+
+    #[test]
+    fn test_command_returns_to_idle() {
+        let mut cli = CLI::new();
+        cli.send(CLIMsg::Execute(CliCommand::List)).unwrap();
+        assert_eq!(cli, CLI::Idle);
+
+        // Failing commands still release the CLI
+        let result = cli.send(CLIMsg::Execute(CliCommand::Run {
+            module: "riptide-no-such-module".into(),
+            args: vec![],
+        }));
+        assert!(matches!(result, Err(CliError::UnknownTool(_))));
+        assert_eq!(cli, CLI::Idle);
+    }
+
+    #[test]
+    fn test_reject_while_running() {
+        let mut cli = CLI::RunningCommand("install".into());
+        let result = cli.send(CLIMsg::Execute(CliCommand::List));
+        assert!(matches!(result, Err(CliError::Busy { .. })));
+        assert_eq!(cli, CLI::RunningCommand("install".into()));
     }
 }
@@ -1,7 +1,9 @@
 pub mod cli;
 pub mod tui;
+pub mod user;
 pub mod view;
 
 pub use cli::CLI;
 pub use tui::TUI;
+pub use user::User;
 pub use view::View;
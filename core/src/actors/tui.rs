@@ -1,11 +1,136 @@
+#[cfg(test)]
+use mockall::automock;
+use thiserror::Error;
+
+use crate::actors::View;
+use crate::runtime::{Actor, Handle};
+
+/// Actor
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TUI {
     Idle,
+    Open(View),
     RunningCommand(String),
     LaunchingCLI,
 }
 
+/// Message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TUIMsg {
+    Open,
+    SetView(View),
+    Close,
+}
+
+/// Errors
+#[derive(Debug, Error)]
+pub enum TuiError {
+    #[error("The TUI is not open")]
+    NotOpen,
+}
+
 impl TUI {
+    /// Initialize a new TUI in its Idle state
+    pub fn new() -> Self {
+        Self::Idle
+    }
+
+    /// Open the dashboard on the default view
     pub fn open(self: &mut TUI) {
-        todo!();
+        if *self == TUI::Idle {
+            *self = TUI::Open(View::default());
+        }
+    }
+
+    /// Main entry point to handle messages to the enum
+    pub fn send(&mut self, msg: TUIMsg) -> Result<(), TuiError> {
+        match msg {
+            TUIMsg::Open => {
+                self.open();
+                Ok(())
+            }
+            TUIMsg::SetView(view) => match self {
+                TUI::Open(current) => {
+                    *current = view;
+                    Ok(())
+                }
+                _ => Err(TuiError::NotOpen),
+            },
+            TUIMsg::Close => {
+                *self = TUI::Idle;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Default for TUI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Actor for TUI {
+    type Msg = TUIMsg;
+    type Error = TuiError;
+
+    fn handle(&mut self, msg: TUIMsg) -> Result<(), TuiError> {
+        self.send(msg)
+    }
+}
+
+/// What other actors need from the TUI; mocked in tests
+#[cfg_attr(test, automock)]
+pub trait TuiPort {
+    fn open(&mut self);
+    fn set_view(&mut self, view: View);
+    fn close(&mut self);
+}
+
+impl TuiPort for TUI {
+    fn open(&mut self) {
+        TUI::open(self);
+    }
+
+    fn set_view(&mut self, view: View) {
+        let _ = self.send(TUIMsg::SetView(view));
+    }
+
+    fn close(&mut self) {
+        let _ = self.send(TUIMsg::Close);
+    }
+}
+
+impl TuiPort for Handle<TUI> {
+    fn open(&mut self) {
+        let _ = self.send(TUIMsg::Open);
+    }
+
+    fn set_view(&mut self, view: View) {
+        let _ = self.send(TUIMsg::SetView(view));
+    }
+
+    fn close(&mut self) {
+        let _ = self.send(TUIMsg::Close);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_view_requires_open() {
+        let mut tui = TUI::new();
+        assert!(tui.send(TUIMsg::SetView(View::Logs)).is_err());
+
+        tui.send(TUIMsg::Open).unwrap();
+        assert_eq!(tui, TUI::Open(View::Workflow));
+
+        tui.send(TUIMsg::SetView(View::Logs)).unwrap();
+        assert_eq!(tui, TUI::Open(View::Logs));
+
+        tui.send(TUIMsg::Close).unwrap();
+        assert_eq!(tui, TUI::Idle);
     }
 }
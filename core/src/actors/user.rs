@@ -1,66 +1,288 @@
-use crate::actors::{CLI, TUI, View};
+use std::fmt::Write;
 
-#[derive(Debug, PartialEq, Eq)]
+use thiserror::Error;
+
+use crate::actors::cli::CliPort;
+use crate::actors::tui::TuiPort;
+use crate::actors::{CLI, View};
+use crate::runtime::Actor;
+
+/// Actor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum User {
     Idle,
     InCLI,
     InTUI,
     DefiningWorkflow,
-    RunningWorkflow,
+    RunningWorkflow(Progress),
     ReviewingLogs,
 }
 
+/// Whether a running workflow is executing stages or waiting for the user between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    Running,
+    Paused,
+}
+
+/// Message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserMsg {
+    LaunchCli,
+    LaunchTui,
+    DefineWorkflow,
+    RunWorkflow,
+    /// Run a workflow one stage at a time, pausing before each
+    StepWorkflow,
+    PauseWorkflow,
+    ResumeWorkflow,
+    FinishWorkflow,
+    ViewLogs,
+    /// Leave the logs for the view the dashboard opens on
+    CloseLogs,
+    QuitTui,
+}
+
+/// A legal move of the User state machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    pub from: User,
+    pub event: UserMsg,
+    pub to: User,
+    /// Condition on the other actors that must also hold, if any
+    pub guard: Option<&'static str>,
+}
+
+const fn transition(from: User, event: UserMsg, to: User) -> Transition {
+    Transition {
+        from,
+        event,
+        to,
+        guard: None,
+    }
+}
+
+/// Every legal transition; anything not listed is rejected with a `TransitionError`
+pub const TRANSITIONS: [Transition; 13] = [
+    transition(User::Idle, UserMsg::LaunchCli, User::InCLI),
+    Transition {
+        guard: Some(CLI_RUNNING),
+        ..transition(User::InCLI, UserMsg::LaunchTui, User::InTUI)
+    },
+    transition(User::InTUI, UserMsg::DefineWorkflow, User::DefiningWorkflow),
+    transition(User::DefiningWorkflow, UserMsg::RunWorkflow, RUNNING),
+    transition(User::DefiningWorkflow, UserMsg::StepWorkflow, PAUSED),
+    transition(RUNNING, UserMsg::PauseWorkflow, PAUSED),
+    transition(PAUSED, UserMsg::ResumeWorkflow, RUNNING),
+    transition(RUNNING, UserMsg::FinishWorkflow, User::InTUI),
+    transition(PAUSED, UserMsg::FinishWorkflow, User::InTUI),
+    transition(User::InTUI, UserMsg::ViewLogs, User::ReviewingLogs),
+    transition(
+        User::DefiningWorkflow,
+        UserMsg::ViewLogs,
+        User::ReviewingLogs,
+    ),
+    transition(User::ReviewingLogs, UserMsg::CloseLogs, User::InTUI),
+    transition(User::InTUI, UserMsg::QuitTui, User::Idle),
+];
+
+const CLI_RUNNING: &str = "CLI is running a command";
+const RUNNING: User = User::RunningWorkflow(Progress::Running);
+const PAUSED: User = User::RunningWorkflow(Progress::Paused);
+
+/// Errors
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TransitionError {
+    #[error("Cannot {event:?} from {from:?}")]
+    Illegal { from: User, event: UserMsg },
+
+    #[error("Cannot {event:?} from {from:?}: requires {guard}")]
+    GuardFailed {
+        from: User,
+        event: UserMsg,
+        guard: &'static str,
+    },
+}
+
 impl User {
-    pub fn launch_cli(&mut self, cli: &mut CLI) {
-        todo!();
+    /// Look up the state `event` leads to from the current state
+    pub fn next(&self, event: UserMsg) -> Result<&'static Transition, TransitionError> {
+        TRANSITIONS
+            .iter()
+            .find(|t| t.from == *self && t.event == event)
+            .ok_or(TransitionError::Illegal { from: *self, event })
     }
 
-    pub fn launch_tui(&mut self, cli: &CLI, tui: &mut TUI) {
-        todo!();
+    pub fn launch_cli(&mut self, cli: &mut impl CliPort) -> Result<(), TransitionError> {
+        let transition = self.next(UserMsg::LaunchCli)?;
+        cli.start();
+        *self = transition.to;
+        Ok(())
     }
-    pub fn define_workflow(&mut self, tui: &mut TUI) {
-        todo!();
+
+    /// The TUI only opens from the CLI while it is running a command
+    pub fn launch_tui(
+        &mut self,
+        cli: &impl CliPort,
+        tui: &mut impl TuiPort,
+    ) -> Result<(), TransitionError> {
+        let transition = self.next(UserMsg::LaunchTui)?;
+        if !matches!(cli.get_state(), CLI::RunningCommand(_)) {
+            return Err(TransitionError::GuardFailed {
+                from: *self,
+                event: UserMsg::LaunchTui,
+                guard: CLI_RUNNING,
+            });
+        }
+        tui.open();
+        *self = transition.to;
+        Ok(())
     }
 
-    pub fn run_workflow(&mut self) {
-        todo!();
+    pub fn define_workflow(&mut self, tui: &mut impl TuiPort) -> Result<(), TransitionError> {
+        let transition = self.next(UserMsg::DefineWorkflow)?;
+        tui.set_view(View::Workflow);
+        *self = transition.to;
+        Ok(())
     }
-    pub fn view_logs(&mut self, tui: &mut TUI) {
-        todo!();
+
+    pub fn run_workflow(&mut self) -> Result<(), TransitionError> {
+        *self = self.next(UserMsg::RunWorkflow)?.to;
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use mockall::{automock, predicate::*};
+    pub fn step_workflow(&mut self) -> Result<(), TransitionError> {
+        *self = self.next(UserMsg::StepWorkflow)?.to;
+        Ok(())
+    }
+
+    /// Wait for the user before the next stage
+    pub fn pause_workflow(&mut self) -> Result<(), TransitionError> {
+        *self = self.next(UserMsg::PauseWorkflow)?.to;
+        Ok(())
+    }
+
+    pub fn resume_workflow(&mut self) -> Result<(), TransitionError> {
+        *self = self.next(UserMsg::ResumeWorkflow)?.to;
+        Ok(())
+    }
+
+    /// The run completed or was aborted; back to the dashboard
+    pub fn finish_workflow(&mut self) -> Result<(), TransitionError> {
+        *self = self.next(UserMsg::FinishWorkflow)?.to;
+        Ok(())
+    }
+
+    pub fn view_logs(&mut self, tui: &mut impl TuiPort) -> Result<(), TransitionError> {
+        let transition = self.next(UserMsg::ViewLogs)?;
+        tui.set_view(View::Logs);
+        *self = transition.to;
+        Ok(())
+    }
+
+    pub fn close_logs(&mut self, tui: &mut impl TuiPort) -> Result<(), TransitionError> {
+        let transition = self.next(UserMsg::CloseLogs)?;
+        tui.set_view(View::default());
+        *self = transition.to;
+        Ok(())
+    }
 
-    // Traits only for mocking in tests
-    #[automock]
-    trait CLI {
-        fn start(&mut self);
-        fn get_state(&self) -> UserCLIState;
+    /// The dashboard closes along with the command that opened it
+    pub fn quit_tui(&mut self, tui: &mut impl TuiPort) -> Result<(), TransitionError> {
+        let transition = self.next(UserMsg::QuitTui)?;
+        tui.close();
+        *self = transition.to;
+        Ok(())
     }
 
-    #[automock]
-    trait TUI {
-        fn open(&mut self);
-        fn set_view(&mut self, view: View);
+    /// Identifier of the state in diagrams, e.g. `RunningWorkflow_Paused`
+    pub fn id(&self) -> String {
+        match self {
+            User::RunningWorkflow(progress) => format!("RunningWorkflow_{progress:?}"),
+            state => format!("{state:?}"),
+        }
     }
 
-    // Helper enum for CLI state (mirrors CLI enum in prod)
-    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-    enum UserCLIState {
-        Idle,
-        RunningCommand,
+    /// Render the transition table as a Mermaid state diagram
+    pub fn to_mermaid() -> String {
+        let mut out = String::from("stateDiagram-v2\n");
+        let _ = writeln!(out, "    [*] --> {}", User::Idle.id());
+        for t in TRANSITIONS.iter() {
+            let _ = write!(out, "    {} --> {}: {:?}", t.from.id(), t.to.id(), t.event);
+            if let Some(guard) = t.guard {
+                let _ = write!(out, " [{guard}]");
+            }
+            out.push('\n');
+        }
+        out
     }
 
-    // Mock enum for Views (simplified)
-    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-    enum View {
-        Workflow,
-        Logs,
+    /// Render the transition table as a Graphviz DOT digraph
+    pub fn to_dot() -> String {
+        let mut out = String::from("digraph User {\n");
+        for t in TRANSITIONS.iter() {
+            let label = match t.guard {
+                Some(guard) => format!("{:?} [{guard}]", t.event),
+                None => format!("{:?}", t.event),
+            };
+            let _ = writeln!(
+                out,
+                "    {} -> {} [label=\"{label}\"];",
+                t.from.id(),
+                t.to.id()
+            );
+        }
+        out.push_str("}\n");
+        out
     }
+}
+
+/// Runtime wrapper pairing the User state machine with the actors it drives
+pub struct UserActor<C: CliPort, T: TuiPort> {
+    pub state: User,
+    cli: C,
+    tui: T,
+}
+
+impl<C: CliPort, T: TuiPort> UserActor<C, T> {
+    pub fn new(cli: C, tui: T) -> Self {
+        Self {
+            state: User::Idle,
+            cli,
+            tui,
+        }
+    }
+}
+
+impl<C: CliPort + 'static, T: TuiPort + 'static> Actor for UserActor<C, T> {
+    type Msg = UserMsg;
+    type Error = TransitionError;
+
+    fn handle(&mut self, msg: UserMsg) -> Result<(), TransitionError> {
+        match msg {
+            UserMsg::LaunchCli => self.state.launch_cli(&mut self.cli),
+            UserMsg::LaunchTui => self.state.launch_tui(&self.cli, &mut self.tui),
+            UserMsg::DefineWorkflow => self.state.define_workflow(&mut self.tui),
+            UserMsg::RunWorkflow => self.state.run_workflow(),
+            UserMsg::StepWorkflow => self.state.step_workflow(),
+            UserMsg::PauseWorkflow => self.state.pause_workflow(),
+            UserMsg::ResumeWorkflow => self.state.resume_workflow(),
+            UserMsg::FinishWorkflow => self.state.finish_workflow(),
+            UserMsg::ViewLogs => self.state.view_logs(&mut self.tui),
+            UserMsg::CloseLogs => self.state.close_logs(&mut self.tui),
+            UserMsg::QuitTui => self.state.quit_tui(&mut self.tui),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::TUI;
+    use crate::actors::cli::MockCliPort as MockCLI;
+    use crate::actors::tui::MockTuiPort as MockTUI;
+    use crate::runtime::Dispatcher;
+    use mockall::predicate::*;
 
     #[test]
     fn test_launch_cli() {
@@ -68,7 +290,7 @@ mod tests {
 
         let mut mock_cli = MockCLI::new();
         mock_cli.expect_start().times(1).return_const(());
-        user.launch_cli(&mut mock_cli);
+        user.launch_cli(&mut mock_cli).unwrap();
 
         assert_eq!(user, User::InCLI);
     }
@@ -82,27 +304,37 @@ mod tests {
         // Should not open TUI if user not InCLI
         mock_tui.expect_open().times(0);
         mock_cli.expect_get_state().times(0);
-        user.launch_tui_mock(&mock_cli, &mut mock_tui);
+        assert_eq!(
+            user.launch_tui(&mock_cli, &mut mock_tui),
+            Err(TransitionError::Illegal {
+                from: User::Idle,
+                event: UserMsg::LaunchTui
+            })
+        );
         assert_eq!(user, User::Idle);
+        mock_cli.checkpoint();
+        mock_tui.checkpoint();
 
         // Set user to InCLI but CLI is idle, TUI open should NOT be called
         user = User::InCLI;
-        mock_cli
-            .expect_get_state()
-            .times(1)
-            .return_const(UserCLIState::Idle);
+        mock_cli.expect_get_state().times(1).return_const(CLI::Idle);
         mock_tui.expect_open().times(0);
-        user.launch_tui_mock(&mock_cli, &mut mock_tui);
+        assert!(matches!(
+            user.launch_tui(&mock_cli, &mut mock_tui),
+            Err(TransitionError::GuardFailed { .. })
+        ));
         assert_eq!(user, User::InCLI);
+        mock_cli.checkpoint();
+        mock_tui.checkpoint();
 
         // CLI running, TUI should open
         user = User::InCLI;
         mock_cli
             .expect_get_state()
             .times(1)
-            .return_const(UserCLIState::RunningCommand);
+            .return_const(CLI::RunningCommand("launch".into()));
         mock_tui.expect_open().times(1).return_const(());
-        user.launch_tui_mock(&mock_cli, &mut mock_tui);
+        user.launch_tui(&mock_cli, &mut mock_tui).unwrap();
         assert_eq!(user, User::InTUI);
     }
 
@@ -116,19 +348,38 @@ mod tests {
             .with(eq(View::Workflow))
             .times(1)
             .return_const(());
-        user.define_workflow_mock(&mut mock_tui);
+        user.define_workflow(&mut mock_tui).unwrap();
         assert_eq!(user, User::DefiningWorkflow);
     }
 
     #[test]
     fn test_run_workflow() {
         let mut user = User::DefiningWorkflow;
-        user.run_workflow_mock();
-        assert_eq!(user, User::RunningWorkflow);
+        user.run_workflow().unwrap();
+        assert_eq!(user, RUNNING);
 
         let mut user2 = User::Idle;
-        user2.run_workflow_mock();
-        assert_ne!(user2, User::RunningWorkflow);
+        assert!(user2.run_workflow().is_err());
+        assert_ne!(user2, RUNNING);
+    }
+
+    #[test]
+    fn test_step_workflow() {
+        let mut user = User::DefiningWorkflow;
+        user.step_workflow().unwrap();
+        assert_eq!(user, PAUSED);
+
+        // Running a stage, then pausing before the next one
+        user.resume_workflow().unwrap();
+        assert_eq!(user, RUNNING);
+        assert!(user.resume_workflow().is_err());
+        user.pause_workflow().unwrap();
+        assert_eq!(user, PAUSED);
+
+        // Aborting from the pause returns to the dashboard
+        user.finish_workflow().unwrap();
+        assert_eq!(user, User::InTUI);
+        assert!(user.pause_workflow().is_err());
     }
 
     #[test]
@@ -142,15 +393,112 @@ mod tests {
             .return_const(());
 
         let mut user = User::InTUI;
-        user.view_logs_mock(&mut mock_tui);
+        user.view_logs(&mut mock_tui).unwrap();
         assert_eq!(user, User::ReviewingLogs);
 
         let mut user2 = User::DefiningWorkflow;
-        user2.view_logs_mock(&mut mock_tui);
+        user2.view_logs(&mut mock_tui).unwrap();
         assert_eq!(user2, User::ReviewingLogs);
 
         let mut user3 = User::Idle;
-        user3.view_logs_mock(&mut mock_tui);
+        assert!(user3.view_logs(&mut mock_tui).is_err());
         assert_ne!(user3, User::ReviewingLogs);
     }
+
+    #[test]
+    fn test_close_logs_and_quit_tui() {
+        let mut mock_tui = MockTUI::new();
+        mock_tui
+            .expect_set_view()
+            .with(eq(View::default()))
+            .times(1)
+            .return_const(());
+        mock_tui.expect_close().times(1).return_const(());
+
+        let mut user = User::ReviewingLogs;
+        assert!(user.quit_tui(&mut mock_tui).is_err());
+        user.close_logs(&mut mock_tui).unwrap();
+        assert_eq!(user, User::InTUI);
+
+        assert!(user.close_logs(&mut mock_tui).is_err());
+        user.quit_tui(&mut mock_tui).unwrap();
+        assert_eq!(user, User::Idle);
+    }
+
+    #[test]
+    fn test_user_drives_actors_through_dispatcher() {
+        let mut dispatcher = Dispatcher::new();
+        let cli = dispatcher.spawn("cli", CLI::RunningCommand("launch".into()));
+        let tui = dispatcher.spawn("tui", TUI::new());
+        let user = dispatcher.spawn(
+            "user",
+            UserActor {
+                state: User::InCLI,
+                cli: cli.clone(),
+                tui: tui.clone(),
+            },
+        );
+
+        user.send(UserMsg::LaunchTui).unwrap();
+        user.send(UserMsg::ViewLogs).unwrap();
+        dispatcher.run_until_idle().unwrap();
+
+        assert_eq!(user.state().state, User::ReviewingLogs);
+        assert_eq!(*tui.state(), TUI::Open(View::Logs));
+
+        user.send(UserMsg::CloseLogs).unwrap();
+        user.send(UserMsg::QuitTui).unwrap();
+        dispatcher.run_until_idle().unwrap();
+
+        assert_eq!(user.state().state, User::Idle);
+        assert_eq!(*tui.state(), TUI::Idle);
+    }
+
+    #[test]
+    fn test_every_state_is_reachable() {
+        let states = [
+            User::Idle,
+            User::InCLI,
+            User::InTUI,
+            User::DefiningWorkflow,
+            RUNNING,
+            PAUSED,
+            User::ReviewingLogs,
+        ];
+        for state in states {
+            assert!(
+                state == User::Idle || TRANSITIONS.iter().any(|t| t.to == state),
+                "{state:?} is unreachable"
+            );
+            assert!(
+                TRANSITIONS.iter().any(|t| t.from == state),
+                "{state:?} is a dead end"
+            );
+        }
+    }
+
+    #[test]
+    fn test_graph_dumps() {
+        let dot = User::to_dot();
+        assert!(dot.starts_with("digraph User {"));
+        assert!(dot.contains("InCLI -> InTUI [label=\"LaunchTui [CLI is running a command]\"];"));
+        assert_eq!(dot.matches("->").count(), TRANSITIONS.len());
+
+        let mermaid = User::to_mermaid();
+        assert!(mermaid.contains("DefiningWorkflow --> RunningWorkflow_Running: RunWorkflow"));
+        assert!(
+            mermaid.contains("RunningWorkflow_Paused --> RunningWorkflow_Running: ResumeWorkflow")
+        );
+    }
+
+    /// The diagram in core/README.md is generated from the transition table
+    #[test]
+    fn test_readme_diagram_in_sync() {
+        let readme = include_str!("../../README.md");
+        let expected = format!("```mermaid\n{}```", User::to_mermaid());
+        assert!(
+            readme.contains(&expected),
+            "core/README.md is out of date, regenerate it with `riptide graph`"
+        );
+    }
 }
@@ -1,5 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum View {
+    Registry,
+    #[default]
     Workflow,
     Logs,
+    Metrics,
+    History,
+    Data,
     Help,
 }
+
+impl View {
+    /// Every view in tab order
+    pub const ALL: [View; 7] = [
+        View::Registry,
+        View::Workflow,
+        View::Logs,
+        View::Metrics,
+        View::History,
+        View::Data,
+        View::Help,
+    ];
+
+    /// Tab title shown in the dashboard header
+    pub fn title(&self) -> &'static str {
+        match self {
+            View::Registry => "Tool Registry",
+            View::Workflow => "Workflow",
+            View::Logs => "Logs",
+            View::Metrics => "Metrics",
+            View::History => "History",
+            View::Data => "Data",
+            View::Help => "Help",
+        }
+    }
+
+    /// Position of the view in the tab bar
+    pub fn index(&self) -> usize {
+        View::ALL.iter().position(|v| v == self).unwrap_or(0)
+    }
+
+    pub fn next(&self) -> View {
+        View::ALL[(self.index() + 1) % View::ALL.len()]
+    }
+
+    pub fn previous(&self) -> View {
+        View::ALL[(self.index() + View::ALL.len() - 1) % View::ALL.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_cycling_wraps() {
+        assert_eq!(View::Registry.previous(), View::Help);
+        assert_eq!(View::Help.next(), View::Registry);
+        for view in View::ALL {
+            assert_eq!(view.next().previous(), view);
+        }
+    }
+}
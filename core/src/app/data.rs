@@ -0,0 +1,249 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Borders, Cell, HighlightSpacing, Paragraph, Row, Table, TableState, Wrap},
+};
+use theme::TableColors;
+
+use crate::catalog::{CatalogError, DataCatalog, DataEntry};
+
+/// The files under the data root, with the shape of every table.
+///
+/// The catalog comes from the `dock` tool and is read when the view is first shown and on
+/// refresh, as profiling reads every table. `dock` runs on a worker thread so a large data
+/// root does not freeze the dashboard. Below the table is the schema of the selected file,
+/// or why it could not be read.
+pub struct DataView {
+    /// The `dock` binary, `None` when it is not installed
+    dock: Option<PathBuf>,
+    catalog: Option<DataCatalog>,
+    loaded: bool,
+    /// Outcome of the scan in progress, if any
+    worker: Option<Receiver<Result<DataCatalog, CatalogError>>>,
+    selected: usize,
+    /// Last problem cataloging the data root
+    notice: Option<String>,
+}
+
+impl DataView {
+    pub fn new(dock: Option<PathBuf>) -> Self {
+        Self {
+            dock,
+            catalog: None,
+            loaded: false,
+            worker: None,
+            selected: 0,
+            notice: None,
+        }
+    }
+
+    /// True once a scan has been started
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// True while `dock` is cataloging the data root
+    pub fn is_scanning(&self) -> bool {
+        self.worker.is_some()
+    }
+
+    /// Start cataloging the data root again; `poll` picks up the result
+    pub fn refresh(&mut self) {
+        if self.is_scanning() {
+            return;
+        }
+        self.loaded = true;
+        let Some(dock) = self.dock.clone() else {
+            self.finish(Err(CatalogError::NotInstalled));
+            return;
+        };
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(DataCatalog::load(&dock, None));
+        });
+        self.worker = Some(rx);
+    }
+
+    /// Take the catalog once the scan in progress finishes
+    pub fn poll(&mut self) {
+        let Some(worker) = &self.worker else {
+            return;
+        };
+        let result = match worker.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                Err(CatalogError::Failed("the catalog's worker stopped".into()))
+            }
+        };
+        self.worker = None;
+        self.finish(result);
+    }
+
+    /// Show a new catalog, keeping the same file selected
+    fn finish(&mut self, result: Result<DataCatalog, CatalogError>) {
+        let selected = self.selected().map(|entry| entry.path.clone());
+        match result {
+            Ok(catalog) => {
+                self.catalog = Some(catalog);
+                self.notice = None;
+            }
+            Err(e) => self.notice = Some(e.to_string()),
+        }
+        self.selected = selected
+            .and_then(|path| self.entries().iter().position(|entry| entry.path == path))
+            .unwrap_or(0);
+    }
+
+    fn entries(&self) -> &[DataEntry] {
+        self.catalog
+            .as_ref()
+            .map(|catalog| catalog.entries.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn selected(&self) -> Option<&DataEntry> {
+        self.entries().get(self.selected)
+    }
+
+    /// Select the next (`delta` > 0) or previous file, wrapping around
+    pub fn select(&mut self, delta: isize) {
+        let count = self.entries().len();
+        if count > 0 {
+            self.selected = (self.selected as isize + delta).rem_euclid(count as isize) as usize;
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, block: Block, colors: &TableColors) {
+        let inner = block.inner(area);
+        frame.render_widget(block.bg(colors.buffer_bg), area);
+        let style = Style::new().fg(colors.row_fg).bg(colors.buffer_bg);
+
+        let Some(catalog) = self.catalog.as_ref().filter(|c| !c.entries.is_empty()) else {
+            let message = match &self.notice {
+                Some(notice) => notice.as_str(),
+                None if self.is_scanning() => "Cataloging the data root...",
+                None => "No files under the data root.",
+            };
+            frame.render_widget(
+                Paragraph::new(message)
+                    .style(style)
+                    .wrap(Wrap { trim: false }),
+                inner,
+            );
+            return;
+        };
+
+        let rows = (catalog.entries.len() as u16 + 1).min(inner.height / 2);
+        let rects = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(rows),
+            Constraint::Min(3),
+        ])
+        .split(inner);
+
+        let tables = catalog
+            .entries
+            .iter()
+            .filter(|entry| entry.category == "table")
+            .count();
+        let mut status = format!(
+            "{} files, {tables} tables in {}",
+            catalog.entries.len(),
+            catalog.root.display()
+        );
+        if self.is_scanning() {
+            status.push_str(" · scanning again...");
+        }
+        if let Some(notice) = &self.notice {
+            status.push_str(&format!(" · {notice}"));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(status).bold()).style(style),
+            rects[0],
+        );
+
+        self.render_table(frame, rects[1], &catalog.entries, colors);
+
+        let details = Paragraph::new(self.selected().map(details).unwrap_or_default())
+            .block(Block::default().borders(Borders::TOP).title("Schema"))
+            .style(style)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details, rects[2]);
+    }
+
+    fn render_table(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        entries: &[DataEntry],
+        colors: &TableColors,
+    ) {
+        let header = ["Path", "Type", "Bytes", "Modified", "Rows", "Columns"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(Style::new().fg(colors.header_fg).bg(colors.header_bg));
+
+        let count = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_else(|| "-".into());
+        let rows = entries.iter().map(|entry| {
+            Row::new([
+                entry.path.display().to_string(),
+                entry.category.clone(),
+                entry.bytes.to_string(),
+                entry
+                    .modified
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default(),
+                count(entry.rows),
+                count(entry.columns),
+            ])
+            .style(Style::new().fg(colors.row_fg))
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(6),
+                Constraint::Length(10),
+                Constraint::Length(16),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(
+            Style::new()
+                .add_modifier(Modifier::REVERSED)
+                .fg(colors.selected_row_style_fg),
+        )
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always)
+        .bg(colors.buffer_bg);
+
+        let mut state = TableState::default().with_selected(self.selected);
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+}
+
+/// The columns of a table, one per line, or why it could not be read
+fn details(entry: &DataEntry) -> String {
+    if let Some(error) = &entry.error {
+        return format!("Cannot read {}: {error}", entry.path.display());
+    }
+    match &entry.schema {
+        Some(schema) => schema
+            .iter()
+            .map(|field| format!("{:<24} {}", field.name, field.dtype))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => format!("{} is not a table dock can read.", entry.path.display()),
+    }
+}
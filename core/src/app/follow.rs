@@ -0,0 +1,62 @@
+use std::io;
+
+/// Run a live view shows: the most recent one in its store, unless a workflow pins its own
+#[derive(Debug, Default)]
+pub struct FollowedRun {
+    /// `None` until the store has a run
+    run: Option<String>,
+    /// Set when a workflow chose the run, so newer runs do not replace it
+    pinned: bool,
+}
+
+impl FollowedRun {
+    pub fn get(&self) -> Option<&str> {
+        self.run.as_deref()
+    }
+
+    /// Show `run` from now on, e.g. the run of the workflow being stepped through
+    pub fn pin(&mut self, run: &str) {
+        self.pinned = true;
+        self.run = Some(run.to_string());
+    }
+
+    /// Move to the newest of `runs`, listed oldest first, unless pinned. Returns whether the
+    /// run shown changed; `runs` is not read at all when pinned.
+    pub fn follow(&mut self, runs: impl FnOnce() -> io::Result<Vec<String>>) -> io::Result<bool> {
+        if self.pinned {
+            return Ok(false);
+        }
+        match runs()?.pop() {
+            Some(latest) if self.run.as_ref() != Some(&latest) => {
+                self.run = Some(latest);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follows_the_newest_run_until_pinned() {
+        let mut run = FollowedRun::default();
+        assert!(!run.follow(|| Ok(Vec::new())).unwrap());
+        assert_eq!(run.get(), None);
+
+        assert!(run.follow(|| Ok(vec!["a".into(), "b".into()])).unwrap());
+        assert_eq!(run.get(), Some("b"));
+        assert!(!run.follow(|| Ok(vec!["a".into(), "b".into()])).unwrap());
+        assert!(run.follow(|| Err(io::ErrorKind::NotFound.into())).is_err());
+
+        run.pin("a");
+        assert_eq!(run.get(), Some("a"));
+        assert!(
+            !run.follow(|| panic!("a pinned run reads no store"))
+                .unwrap()
+        );
+        assert_eq!(run.get(), Some("a"));
+    }
+}
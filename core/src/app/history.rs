@@ -0,0 +1,230 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Borders, Cell, HighlightSpacing, Paragraph, Row, Table, TableState, Wrap},
+};
+use theme::TableColors;
+
+use crate::history::{RunDiff, RunHistory, RunSummary};
+use crate::workflow::Pipeline;
+
+/// Headline metrics shown per run in the table
+const HEADLINE_METRICS: usize = 3;
+
+/// Past pipeline runs, newest first.
+///
+/// The history is read when the view is first shown and on refresh rather than every tick,
+/// as it lives in the database. Below the table is the selected run in detail, or how it
+/// differs from the run marked for comparison.
+pub struct HistoryView {
+    history: RunHistory,
+    runs: Vec<RunSummary>,
+    loaded: bool,
+    selected: usize,
+    /// Run the selected one is compared against
+    marked: Option<String>,
+    diff: Option<RunDiff>,
+    /// Last problem reading the history or acting on a run
+    notice: Option<String>,
+}
+
+impl HistoryView {
+    pub fn new(history: RunHistory) -> Self {
+        Self {
+            history,
+            runs: Vec::new(),
+            loaded: false,
+            selected: 0,
+            marked: None,
+            diff: None,
+            notice: None,
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// Read the history again, keeping the same run selected
+    pub fn refresh(&mut self) {
+        let selected = self.selected().map(|run| run.run_id().to_string());
+        match self.history.list() {
+            Ok(runs) => {
+                self.runs = runs;
+                self.notice = None;
+            }
+            Err(e) => self.notice = Some(format!("Cannot read the run history: {e}")),
+        }
+        self.loaded = true;
+        self.selected = selected
+            .and_then(|id| self.runs.iter().position(|run| run.run_id() == id))
+            .unwrap_or(0);
+    }
+
+    pub fn selected(&self) -> Option<&RunSummary> {
+        self.runs.get(self.selected)
+    }
+
+    pub fn marked(&self) -> Option<&str> {
+        self.marked.as_deref()
+    }
+
+    /// Select the next (`delta` > 0) or previous run, wrapping around
+    pub fn select(&mut self, delta: isize) {
+        let count = self.runs.len();
+        if count > 0 {
+            self.selected = (self.selected as isize + delta).rem_euclid(count as isize) as usize;
+            self.diff = None;
+        }
+    }
+
+    /// Mark the selected run to compare others against, or unmark it
+    pub fn mark(&mut self) {
+        let Some(id) = self.selected().map(|run| run.run_id().to_string()) else {
+            return;
+        };
+        self.marked = if self.marked.as_ref() == Some(&id) {
+            None
+        } else {
+            Some(id)
+        };
+        self.diff = None;
+    }
+
+    /// Compare the marked run with the selected one
+    pub fn diff(&mut self) {
+        let marked = self
+            .marked
+            .as_ref()
+            .and_then(|id| self.runs.iter().find(|run| run.run_id() == id));
+        match (marked, self.selected()) {
+            (Some(before), Some(after)) if before.run_id() != after.run_id() => {
+                self.diff = Some(RunDiff::between(before, after));
+                self.notice = None;
+            }
+            _ => {
+                self.notice = Some("Mark a run, then select another to compare".to_string());
+            }
+        }
+    }
+
+    pub fn diff_shown(&self) -> Option<&RunDiff> {
+        self.diff.as_ref()
+    }
+
+    /// The pipeline of the selected run, configured exactly as it ran
+    pub fn rerun(&mut self) -> Option<Pipeline> {
+        let run = self.selected()?;
+        let result = run.pipeline();
+        let id = run.run_id().to_string();
+        match result {
+            Ok(Some(pipeline)) => return Some(pipeline),
+            Ok(None) => {
+                self.notice = Some(format!("Run {id} was recorded without its pipeline"));
+            }
+            Err(e) => self.notice = Some(format!("Cannot rerun {id}: {e}")),
+        }
+        None
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, block: Block, colors: &TableColors) {
+        let inner = block.inner(area);
+        frame.render_widget(block.bg(colors.buffer_bg), area);
+        let style = Style::new().fg(colors.row_fg).bg(colors.buffer_bg);
+
+        if self.runs.is_empty() {
+            let message = self.notice.as_deref().unwrap_or("No runs recorded yet.");
+            frame.render_widget(Paragraph::new(message).style(style), inner);
+            return;
+        }
+
+        let rows = (self.runs.len() as u16 + 1).min(inner.height / 2);
+        let rects = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(rows),
+            Constraint::Min(3),
+        ])
+        .split(inner);
+
+        let mut status = format!("{} runs", self.runs.len());
+        if let Some(marked) = &self.marked {
+            status.push_str(&format!(" · comparing against {marked}"));
+        }
+        if let Some(notice) = &self.notice {
+            status.push_str(&format!(" · {notice}"));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(status).bold()).style(style),
+            rects[0],
+        );
+
+        self.render_table(frame, rects[1], colors);
+
+        let (title, text) = match (&self.diff, self.selected()) {
+            (Some(diff), _) => ("Comparison", diff.to_string()),
+            (None, Some(run)) => ("Details", run.to_string()),
+            (None, None) => ("Details", String::new()),
+        };
+        let details = Paragraph::new(text)
+            .block(Block::default().borders(Borders::TOP).title(title))
+            .style(style)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details, rects[2]);
+    }
+
+    fn render_table(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let header = [
+            "", "Run", "Pipeline", "Status", "Started", "Duration", "Metrics",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(Style::new().fg(colors.header_fg).bg(colors.header_bg));
+
+        let rows = self.runs.iter().map(|summary| {
+            let run = &summary.run;
+            let marked = if self.marked.as_deref() == Some(summary.run_id()) {
+                "*"
+            } else {
+                ""
+            };
+            Row::new([
+                marked.to_string(),
+                run.run_id.clone(),
+                run.pipeline.clone(),
+                run.status.clone(),
+                run.started.format("%Y-%m-%d %H:%M").to_string(),
+                summary.duration(),
+                summary.headline(HEADLINE_METRICS),
+            ])
+            .style(Style::new().fg(colors.row_fg))
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(1),
+                Constraint::Length(14),
+                Constraint::Length(14),
+                Constraint::Length(10),
+                Constraint::Length(16),
+                Constraint::Length(9),
+                Constraint::Min(12),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(
+            Style::new()
+                .add_modifier(Modifier::REVERSED)
+                .fg(colors.selected_row_style_fg),
+        )
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always)
+        .bg(colors.buffer_bg);
+
+        let mut state = TableState::default().with_selected(self.selected);
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+}
@@ -0,0 +1,275 @@
+use ::keymap::Bindable;
+
+use crate::actors::View;
+
+pub use ::keymap::{KeyBinding, KeymapError};
+
+/// Bindings used when the user has no keymap file, in the same format users write
+pub const DEFAULT_KEYMAP: &str = include_str!("keymap.toml");
+
+/// Key bindings for every view, loaded from TOML (FR-TUI-02)
+pub type Keymap = ::keymap::Keymap<Action>;
+
+::keymap::actions!(
+    /// Actions available from every view
+    GlobalAction, "global", {
+        Quit => "quit", "quit";
+        NextView => "next_view", "next view";
+        PreviousView => "previous_view", "previous view";
+        ShowHelp => "show_help", "show this help";
+        ShowRegistry => "show_registry", "show the tool registry";
+        ShowWorkflow => "show_workflow", "show the workflow";
+        ShowLogs => "show_logs", "show the logs";
+        ShowMetrics => "show_metrics", "show the metrics";
+        ShowHistory => "show_history", "show the history";
+        ShowData => "show_data", "show the datasets";
+        SplitVertical => "split_vertical", "split pane side by side";
+        SplitHorizontal => "split_horizontal", "split pane top and bottom";
+        ClosePane => "close_pane", "close pane";
+        GrowPane => "grow_pane", "grow pane";
+        ShrinkPane => "shrink_pane", "shrink pane";
+        FocusNext => "focus_next", "focus next pane";
+        FocusPrevious => "focus_previous", "focus previous pane";
+        ZoomPane => "zoom_pane", "zoom pane";
+    }
+);
+
+::keymap::actions!(
+    /// Actions of the Tool Registry view
+    RegistryAction, "registry", {
+        Up => "up", "move up";
+        Down => "down", "move down";
+    }
+);
+
+::keymap::actions!(
+    /// Decisions while stepping through a workflow
+    WorkflowAction, "workflow", {
+        RunStage => "run_stage", "run the next stage";
+        SkipStage => "skip_stage", "skip the next stage";
+        RetryStage => "retry_stage", "retry the last stage";
+        EditParams => "edit_params", "edit the next stage's parameters";
+        Abort => "abort", "abort the run";
+    }
+);
+
+::keymap::actions!(
+    /// Actions of the Logs view
+    LogsAction, "logs", {
+        Up => "up", "scroll up";
+        Down => "down", "scroll down";
+        PageUp => "page_up", "scroll a page up";
+        PageDown => "page_down", "scroll a page down";
+        ToggleFollow => "toggle_follow", "pause or resume auto-scroll";
+        Search => "search", "search as you type (regex)";
+        NextStage => "next_stage", "filter by the next stage";
+        NextLevel => "next_level", "raise the minimum level";
+        FirstError => "first_error", "jump to the run's first error";
+        ClearFilters => "clear_filters", "clear filters and search";
+    }
+);
+
+::keymap::actions!(
+    /// Actions of the Metrics view
+    MetricsAction, "metrics", {
+        Up => "up", "select the previous metric";
+        Down => "down", "select the next metric";
+    }
+);
+
+::keymap::actions!(
+    /// Actions of the History view
+    HistoryAction, "history", {
+        Up => "up", "select the previous run";
+        Down => "down", "select the next run";
+        Mark => "mark", "mark the run to compare against";
+        Diff => "diff", "compare the marked run with this one";
+        Rerun => "rerun", "run the pipeline again as it ran";
+        Refresh => "refresh", "reload the history";
+    }
+);
+
+::keymap::actions!(
+    /// Actions of the Data view
+    DataAction, "data", {
+        Up => "up", "select the previous file";
+        Down => "down", "select the next file";
+        Refresh => "refresh", "catalog the data root again";
+    }
+);
+
+/// Any bindable action, tagged with the view it belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Global(GlobalAction),
+    Registry(RegistryAction),
+    Workflow(WorkflowAction),
+    Logs(LogsAction),
+    Metrics(MetricsAction),
+    History(HistoryAction),
+    Data(DataAction),
+}
+
+impl Bindable for Action {
+    type View = View;
+
+    const DEFAULTS: &'static str = DEFAULT_KEYMAP;
+
+    fn all() -> Vec<Action> {
+        let global = GlobalAction::ALL.iter().map(|a| Action::Global(*a));
+        let registry = RegistryAction::ALL.iter().map(|a| Action::Registry(*a));
+        let workflow = WorkflowAction::ALL.iter().map(|a| Action::Workflow(*a));
+        let logs = LogsAction::ALL.iter().map(|a| Action::Logs(*a));
+        let metrics = MetricsAction::ALL.iter().map(|a| Action::Metrics(*a));
+        let history = HistoryAction::ALL.iter().map(|a| Action::History(*a));
+        let data = DataAction::ALL.iter().map(|a| Action::Data(*a));
+        global
+            .chain(registry)
+            .chain(workflow)
+            .chain(logs)
+            .chain(metrics)
+            .chain(history)
+            .chain(data)
+            .collect()
+    }
+
+    fn scope(&self) -> &'static str {
+        match self {
+            Action::Global(_) => GlobalAction::SCOPE,
+            Action::Registry(_) => RegistryAction::SCOPE,
+            Action::Workflow(_) => WorkflowAction::SCOPE,
+            Action::Logs(_) => LogsAction::SCOPE,
+            Action::Metrics(_) => MetricsAction::SCOPE,
+            Action::History(_) => HistoryAction::SCOPE,
+            Action::Data(_) => DataAction::SCOPE,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Action::Global(a) => a.name(),
+            Action::Registry(a) => a.name(),
+            Action::Workflow(a) => a.name(),
+            Action::Logs(a) => a.name(),
+            Action::Metrics(a) => a.name(),
+            Action::History(a) => a.name(),
+            Action::Data(a) => a.name(),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Action::Global(a) => a.description(),
+            Action::Registry(a) => a.description(),
+            Action::Workflow(a) => a.description(),
+            Action::Logs(a) => a.description(),
+            Action::Metrics(a) => a.description(),
+            Action::History(a) => a.description(),
+            Action::Data(a) => a.description(),
+        }
+    }
+
+    fn view(&self) -> Option<View> {
+        match self {
+            Action::Global(_) => None,
+            Action::Registry(_) => Some(View::Registry),
+            Action::Workflow(_) => Some(View::Workflow),
+            Action::Logs(_) => Some(View::Logs),
+            Action::Metrics(_) => Some(View::Metrics),
+            Action::History(_) => Some(View::History),
+            Action::Data(_) => Some(View::Data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_defaults_cover_every_action() {
+        let keymap = Keymap::default();
+        for (action, keys) in keymap.bindings() {
+            assert!(!keys.is_empty(), "{} has no default key", action.name());
+        }
+        assert_eq!(
+            keymap.bindings().len(),
+            GlobalAction::ALL.len()
+                + RegistryAction::ALL.len()
+                + WorkflowAction::ALL.len()
+                + LogsAction::ALL.len()
+                + MetricsAction::ALL.len()
+                + HistoryAction::ALL.len()
+                + DataAction::ALL.len()
+        );
+    }
+
+    #[test]
+    fn test_view_actions_only_fire_in_their_view() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action(View::Registry, key(KeyCode::Char('j'))),
+            Some(Action::Registry(RegistryAction::Down))
+        );
+        assert_eq!(keymap.action(View::Help, key(KeyCode::Char('j'))), None);
+        assert_eq!(
+            keymap.action(View::Logs, key(KeyCode::Char('j'))),
+            Some(Action::Logs(LogsAction::Down))
+        );
+        assert_eq!(
+            keymap.action(View::Logs, key(KeyCode::Char('q'))),
+            Some(Action::Global(GlobalAction::Quit))
+        );
+    }
+
+    #[test]
+    fn test_user_overrides_replace_defaults() {
+        let keymap = Keymap::from_toml("[global]\nquit = [\"ctrl+c\"]\n").unwrap();
+        assert_eq!(keymap.action(View::Help, key(KeyCode::Char('q'))), None);
+        assert_eq!(
+            keymap.action(
+                View::Help,
+                KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
+            ),
+            Some(Action::Global(GlobalAction::Quit))
+        );
+    }
+
+    #[test]
+    fn test_conflicts_are_rejected() {
+        let err = Keymap::from_toml("[global]\nquit = [\"tab\"]\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Key `tab` is bound to both `global.quit` and `global.next_view`"
+        );
+
+        // A view action may not shadow a global key
+        let err = Keymap::from_toml("[registry]\nup = [\"w\"]\n").unwrap_err();
+        assert!(matches!(err, KeymapError::Conflict { .. }));
+    }
+
+    #[test]
+    fn test_unknown_actions_are_rejected() {
+        assert!(matches!(
+            Keymap::from_toml("[global]\nfly = [\"f\"]\n"),
+            Err(KeymapError::UnknownAction { .. })
+        ));
+        assert!(matches!(
+            Keymap::from_toml("[ocean]\nswim = [\"s\"]\n"),
+            Err(KeymapError::UnknownAction { .. })
+        ));
+    }
+
+    #[test]
+    fn test_help_lists_active_bindings() {
+        let keymap = Keymap::from_toml("[registry]\ndown = [\"n\", \"down\"]\n").unwrap();
+        let help = keymap.help(Some(View::Registry));
+        assert!(help.contains(&("n/down".to_string(), "move down")));
+        assert!(keymap.help(None).contains(&("q/esc".to_string(), "quit")));
+    }
+}
@@ -0,0 +1,365 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use serde::{Deserialize, Serialize};
+
+use crate::actors::View;
+
+/// Smallest share of a split either side may shrink to, in percent
+const MIN_RATIO: u16 = 10;
+
+/// How a split arranges its two children
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SplitDirection {
+    /// Children side by side
+    Vertical,
+    /// Children stacked top to bottom
+    Horizontal,
+}
+
+/// A node of the pane tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pane {
+    Leaf(View),
+    Split {
+        direction: SplitDirection,
+        /// Share of the area given to `first`, in percent
+        ratio: u16,
+        first: Box<Pane>,
+        second: Box<Pane>,
+    },
+}
+
+/// The pane tree of the dashboard plus which pane has focus
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaneLayout {
+    root: Pane,
+    /// Index of the focused leaf in depth-first order
+    focus: usize,
+    zoomed: bool,
+}
+
+impl Pane {
+    fn leaves(&self, out: &mut Vec<View>) {
+        match self {
+            Pane::Leaf(view) => out.push(*view),
+            Pane::Split { first, second, .. } => {
+                first.leaves(out);
+                second.leaves(out);
+            }
+        }
+    }
+
+    fn leaf_count(&self) -> usize {
+        match self {
+            Pane::Leaf(_) => 1,
+            Pane::Split { first, second, .. } => first.leaf_count() + second.leaf_count(),
+        }
+    }
+
+    /// Path to the `index`th leaf; `false` descends into `first`, `true` into `second`
+    fn path_to(&self, index: usize) -> Option<Vec<bool>> {
+        match self {
+            Pane::Leaf(_) => (index == 0).then(Vec::new),
+            Pane::Split { first, second, .. } => {
+                let left = first.leaf_count();
+                let (side, child, index) = if index < left {
+                    (false, first, index)
+                } else {
+                    (true, second, index - left)
+                };
+                let mut path = child.path_to(index)?;
+                path.insert(0, side);
+                Some(path)
+            }
+        }
+    }
+
+    fn node_mut(&mut self, path: &[bool]) -> &mut Pane {
+        match (self, path.split_first()) {
+            (Pane::Split { first, second, .. }, Some((side, rest))) => {
+                if *side {
+                    second.node_mut(rest)
+                } else {
+                    first.node_mut(rest)
+                }
+            }
+            (node, _) => node,
+        }
+    }
+
+    /// Bring every split's ratio back within `MIN_RATIO..=100 - MIN_RATIO`
+    fn clamp_ratios(&mut self) {
+        if let Pane::Split {
+            ratio,
+            first,
+            second,
+            ..
+        } = self
+        {
+            *ratio = (*ratio).clamp(MIN_RATIO, 100 - MIN_RATIO);
+            first.clamp_ratios();
+            second.clamp_ratios();
+        }
+    }
+
+    fn areas(&self, area: Rect, out: &mut Vec<Rect>) {
+        match self {
+            Pane::Leaf(_) => out.push(area),
+            Pane::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => {
+                let direction = match direction {
+                    SplitDirection::Vertical => Direction::Horizontal,
+                    SplitDirection::Horizontal => Direction::Vertical,
+                };
+                let rects = Layout::default()
+                    .direction(direction)
+                    .constraints([
+                        Constraint::Percentage(*ratio),
+                        Constraint::Percentage(100 - ratio),
+                    ])
+                    .split(area);
+                first.areas(rects[0], out);
+                second.areas(rects[1], out);
+            }
+        }
+    }
+}
+
+impl PaneLayout {
+    /// A single pane showing `view`
+    pub fn new(view: View) -> Self {
+        Self {
+            root: Pane::Leaf(view),
+            focus: 0,
+            zoomed: false,
+        }
+    }
+
+    /// Views of every pane in depth-first order
+    pub fn views(&self) -> Vec<View> {
+        let mut views = Vec::new();
+        self.root.leaves(&mut views);
+        views
+    }
+
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoomed
+    }
+
+    /// The view hosted by the focused pane
+    pub fn focused_view(&self) -> View {
+        self.views()[self.focus]
+    }
+
+    /// Show `view` in the focused pane
+    pub fn set_view(&mut self, view: View) {
+        if let Some(path) = self.root.path_to(self.focus) {
+            *self.root.node_mut(&path) = Pane::Leaf(view);
+        }
+    }
+
+    /// Split the focused pane in two, both showing its view, and focus the new half
+    pub fn split(&mut self, direction: SplitDirection) {
+        let Some(path) = self.root.path_to(self.focus) else {
+            return;
+        };
+        let node = self.root.node_mut(&path);
+        if let Pane::Leaf(view) = *node {
+            *node = Pane::Split {
+                direction,
+                ratio: 50,
+                first: Box::new(Pane::Leaf(view)),
+                second: Box::new(Pane::Leaf(view)),
+            };
+            self.focus += 1;
+            self.zoomed = false;
+        }
+    }
+
+    /// Close the focused pane, letting its sibling take over the space. The last pane stays.
+    pub fn close(&mut self) {
+        let Some(path) = self.root.path_to(self.focus) else {
+            return;
+        };
+        let Some((side, parent_path)) = path.split_last() else {
+            return;
+        };
+        let parent = self.root.node_mut(parent_path);
+        if let Pane::Split { first, second, .. } =
+            std::mem::replace(parent, Pane::Leaf(View::default()))
+        {
+            *parent = if *side { *first } else { *second };
+        }
+        // A closed second child passes focus to the last pane of its sibling
+        if *side {
+            self.focus -= 1;
+        }
+        self.zoomed = false;
+    }
+
+    /// Grow (positive) or shrink (negative) the focused pane within its split, in percent
+    pub fn resize(&mut self, delta: i16) {
+        let Some(path) = self.root.path_to(self.focus) else {
+            return;
+        };
+        let Some((side, parent_path)) = path.split_last() else {
+            return;
+        };
+        if let Pane::Split { ratio, .. } = self.root.node_mut(parent_path) {
+            // Growing the second child means shrinking the first
+            let delta = if *side { -delta } else { delta };
+            *ratio = (*ratio as i16 + delta).clamp(MIN_RATIO as i16, 100 - MIN_RATIO as i16) as u16;
+        }
+    }
+
+    pub fn focus_next(&mut self) {
+        self.focus = (self.focus + 1) % self.root.leaf_count();
+    }
+
+    pub fn focus_previous(&mut self) {
+        let count = self.root.leaf_count();
+        self.focus = (self.focus + count - 1) % count;
+    }
+
+    /// Toggle showing only the focused pane
+    pub fn toggle_zoom(&mut self) {
+        self.zoomed = !self.zoomed;
+    }
+
+    /// Screen area of each visible pane as `(pane index, view, area)`
+    pub fn areas(&self, area: Rect) -> Vec<(usize, View, Rect)> {
+        if self.zoomed {
+            return vec![(self.focus, self.focused_view(), area)];
+        }
+        let mut rects = Vec::new();
+        self.root.areas(area, &mut rects);
+        self.views()
+            .into_iter()
+            .zip(rects)
+            .enumerate()
+            .map(|(i, (view, rect))| (i, view, rect))
+            .collect()
+    }
+
+    /// Read a layout saved by `save`
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut layout: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        layout.root.clamp_ratios();
+        if layout.focus >= layout.root.leaf_count() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "focused pane does not exist",
+            ));
+        }
+        Ok(layout)
+    }
+
+    /// Persist the layout so the next session starts with the same panes
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self::new(View::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_close() {
+        let mut layout = PaneLayout::new(View::Workflow);
+        layout.split(SplitDirection::Vertical);
+        assert_eq!(layout.views(), vec![View::Workflow, View::Workflow]);
+        assert_eq!(layout.focus(), 1);
+
+        layout.set_view(View::Logs);
+        layout.split(SplitDirection::Horizontal);
+        layout.set_view(View::Help);
+        assert_eq!(layout.views(), vec![View::Workflow, View::Logs, View::Help]);
+        assert_eq!(layout.focused_view(), View::Help);
+
+        // Closing the Help pane hands its space back to Logs
+        layout.close();
+        assert_eq!(layout.views(), vec![View::Workflow, View::Logs]);
+        assert_eq!(layout.focused_view(), View::Logs);
+
+        layout.close();
+        layout.close();
+        assert_eq!(layout.views(), vec![View::Workflow]);
+    }
+
+    #[test]
+    fn test_focus_cycles() {
+        let mut layout = PaneLayout::default();
+        layout.split(SplitDirection::Vertical);
+        layout.split(SplitDirection::Vertical);
+        assert_eq!(layout.focus(), 2);
+
+        layout.focus_next();
+        assert_eq!(layout.focus(), 0);
+        layout.focus_previous();
+        assert_eq!(layout.focus(), 2);
+    }
+
+    #[test]
+    fn test_resize_and_zoom_areas() {
+        let area = Rect::new(0, 0, 100, 40);
+        let mut layout = PaneLayout::new(View::Workflow);
+        layout.split(SplitDirection::Vertical);
+        layout.set_view(View::Logs);
+
+        // Growing the right pane shrinks the left one
+        layout.resize(20);
+        let areas = layout.areas(area);
+        assert_eq!(areas[0].2.width, 30);
+        assert_eq!(areas[1].1, View::Logs);
+        assert_eq!(areas[1].2.width, 70);
+
+        layout.resize(100);
+        assert_eq!(layout.areas(area)[0].2.width, MIN_RATIO);
+
+        layout.toggle_zoom();
+        assert_eq!(layout.areas(area), vec![(1, View::Logs, area)]);
+    }
+
+    #[test]
+    fn test_layout_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layout.json");
+
+        let mut layout = PaneLayout::new(View::Workflow);
+        layout.split(SplitDirection::Horizontal);
+        layout.set_view(View::Logs);
+        layout.resize(-15);
+        layout.save(&path).unwrap();
+
+        assert_eq!(PaneLayout::load(&path).unwrap(), layout);
+
+        // A hand-edited ratio out of range is clamped rather than overflowing
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("\"ratio\": 65"));
+        fs::write(&path, saved.replace("\"ratio\": 65", "\"ratio\": 250")).unwrap();
+        let loaded = PaneLayout::load(&path).unwrap();
+        let areas = loaded.areas(Rect::new(0, 0, 100, 40));
+        assert_eq!(areas[0].2.height, 36);
+    }
+}
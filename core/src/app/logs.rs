@@ -0,0 +1,339 @@
+use std::cell::Cell;
+
+use ratatui::{
+    Frame,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use regex::Regex;
+use theme::TableColors;
+
+use crate::app::follow::FollowedRun;
+use crate::logging::{Level, LogFilter, LogRecord, LogStore, LogTail};
+
+/// Records kept in memory for the Logs view
+const MAX_RECORDS: usize = 10_000;
+
+/// Live view of one run's log (FR-TUI-03).
+///
+/// Follows the most recent run in the store unless a workflow pins its own run. Records can
+/// be narrowed by stage, level and a regex typed as a search; matches are highlighted.
+pub struct LogView {
+    store: LogStore,
+    run: FollowedRun,
+    tail: Option<LogTail>,
+    records: Vec<LogRecord>,
+    /// Stage and level criteria; the run is handled by the tail
+    filter: LogFilter,
+    search: String,
+    /// `search` compiled, `None` when empty or invalid
+    pattern: Option<Regex>,
+    /// True while keys go to the search box
+    searching: bool,
+    /// Keep the newest records in view
+    follow: bool,
+    /// First visible record among the filtered ones when not following
+    scroll: usize,
+    /// Rows available at the last render, for paging
+    height: Cell<usize>,
+    /// Last problem reading the store, or why a key did nothing
+    notice: Option<String>,
+}
+
+impl LogView {
+    pub fn new(store: LogStore) -> Self {
+        Self {
+            store,
+            run: FollowedRun::default(),
+            tail: None,
+            records: Vec::new(),
+            filter: LogFilter::default(),
+            search: String::new(),
+            pattern: None,
+            searching: false,
+            follow: true,
+            scroll: 0,
+            height: Cell::new(0),
+            notice: None,
+        }
+    }
+
+    /// Show `run` from now on, e.g. the run of the workflow being stepped through
+    pub fn pin_run(&mut self, run: &str) {
+        self.run.pin(run);
+        self.show_run();
+    }
+
+    pub fn run(&self) -> Option<&str> {
+        self.run.get()
+    }
+
+    pub fn is_following(&self) -> bool {
+        self.follow
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.searching
+    }
+
+    /// Start over at the run now followed
+    fn show_run(&mut self) {
+        self.tail = Some(self.store.tail(LogFilter {
+            run: self.run.get().map(str::to_string),
+            ..LogFilter::default()
+        }));
+        self.records.clear();
+        self.filter.stage = None;
+        self.scroll = 0;
+        self.follow = true;
+    }
+
+    /// Read records written since the last poll, switching to a newer run when not pinned
+    pub fn poll(&mut self) {
+        match self.run.follow(|| self.store.runs()) {
+            Ok(true) => self.show_run(),
+            Ok(false) => {}
+            Err(e) => self.notice = Some(format!("Cannot read logs: {e}")),
+        }
+        let Some(tail) = &mut self.tail else {
+            return;
+        };
+        match tail.poll() {
+            Ok(records) => self.records.extend(records),
+            Err(e) => self.notice = Some(format!("Cannot read logs: {e}")),
+        }
+        let excess = self.records.len().saturating_sub(MAX_RECORDS);
+        if excess > 0 {
+            self.records.drain(..excess);
+            self.scroll = self.scroll.saturating_sub(excess);
+        }
+    }
+
+    /// Records passing the stage, level and search filters
+    fn visible(&self) -> Vec<&LogRecord> {
+        self.records
+            .iter()
+            .filter(|record| self.filter.matches(record))
+            .filter(|record| {
+                self.pattern
+                    .as_ref()
+                    .is_none_or(|pattern| pattern.is_match(&line_text(record)))
+            })
+            .collect()
+    }
+
+    /// Index of the first record shown
+    fn top(&self, count: usize) -> usize {
+        let last_page = count.saturating_sub(self.height.get().max(1));
+        if self.follow {
+            last_page
+        } else {
+            self.scroll.min(last_page)
+        }
+    }
+
+    /// Scroll by `delta` records; scrolling pauses auto-scroll until the end is reached
+    pub fn scroll(&mut self, delta: isize) {
+        let count = self.visible().len();
+        let top = self.top(count);
+        let last_page = count.saturating_sub(self.height.get().max(1));
+        self.scroll = top.saturating_add_signed(delta).min(last_page);
+        self.follow = delta > 0 && self.scroll == last_page;
+    }
+
+    pub fn page(&mut self, pages: isize) {
+        let rows = self.height.get().max(1) as isize;
+        self.scroll(pages * rows);
+    }
+
+    pub fn toggle_follow(&mut self) {
+        if self.follow {
+            self.scroll = self.top(self.visible().len());
+        }
+        self.follow = !self.follow;
+    }
+
+    /// Show the run's first error at the top and pause auto-scroll
+    pub fn first_error(&mut self) {
+        match self
+            .visible()
+            .iter()
+            .position(|record| record.level == Level::Error)
+        {
+            Some(index) => {
+                self.scroll = index;
+                self.follow = false;
+                self.notice = None;
+            }
+            None => self.notice = Some("No errors in this run".into()),
+        }
+    }
+
+    /// Cycle the stage filter through every stage seen in the run
+    pub fn next_stage(&mut self) {
+        let mut stages: Vec<&str> = Vec::new();
+        for stage in self.records.iter().filter_map(|r| r.stage.as_deref()) {
+            if !stages.contains(&stage) {
+                stages.push(stage);
+            }
+        }
+        let next = match &self.filter.stage {
+            None => stages.first(),
+            Some(current) => stages
+                .iter()
+                .position(|s| s == current)
+                .and_then(|i| stages.get(i + 1)),
+        };
+        self.filter.stage = next.map(|s| s.to_string());
+    }
+
+    /// Cycle the minimum level: all, info, warn, error
+    pub fn next_level(&mut self) {
+        self.filter.min_level = match self.filter.min_level {
+            None | Some(Level::Debug) => Some(Level::Info),
+            Some(Level::Info) => Some(Level::Warn),
+            Some(Level::Warn) => Some(Level::Error),
+            Some(Level::Error) => None,
+        };
+    }
+
+    pub fn clear_filters(&mut self) {
+        self.filter.stage = None;
+        self.filter.min_level = None;
+        self.set_search(String::new());
+        self.notice = None;
+    }
+
+    pub fn start_search(&mut self) {
+        self.searching = true;
+    }
+
+    fn set_search(&mut self, search: String) {
+        self.pattern = if search.is_empty() {
+            None
+        } else {
+            // Case-insensitive like `riptide logs --grep`
+            Regex::new(&format!("(?i){search}")).ok()
+        };
+        self.search = search;
+    }
+
+    /// Feed a key to the search box, filtering as the user types; `false` when not searching
+    pub fn search_key(&mut self, key: KeyEvent) -> bool {
+        if !self.searching {
+            return false;
+        }
+        let mut search = self.search.clone();
+        match key.code {
+            KeyCode::Enter => self.searching = false,
+            KeyCode::Esc => {
+                self.searching = false;
+                search.clear();
+            }
+            KeyCode::Backspace => {
+                search.pop();
+            }
+            KeyCode::Char(c) => search.push(c),
+            _ => {}
+        }
+        self.set_search(search);
+        true
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, block: Block, colors: &TableColors) {
+        let inner = block.inner(area);
+        frame.render_widget(block.bg(colors.buffer_bg), area);
+        let rects = Layout::vertical([Constraint::Length(2), Constraint::Min(1)]).split(inner);
+
+        self.render_status(frame, rects[0], colors);
+
+        let body = rects[1];
+        self.height.set(body.height as usize);
+        let style = Style::new().fg(colors.row_fg).bg(colors.buffer_bg);
+        let visible = self.visible();
+        if visible.is_empty() {
+            let message = match (self.run.get(), self.records.is_empty()) {
+                (None, _) => "No logs recorded yet.",
+                (Some(_), true) => "Waiting for log records…",
+                (Some(_), false) => "No records match the filters.",
+            };
+            frame.render_widget(Paragraph::new(message).style(style), body);
+            return;
+        }
+
+        let top = self.top(visible.len());
+        let lines: Vec<Line> = visible[top..]
+            .iter()
+            .take(body.height as usize)
+            .map(|record| self.record_line(record))
+            .collect();
+        frame.render_widget(Paragraph::new(lines).style(style), body);
+    }
+
+    fn render_status(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let run = self.run.get().unwrap_or("none");
+        let stage = self.filter.stage.as_deref().unwrap_or("all");
+        let level = self.filter.min_level.map_or("all", |level| level.name());
+        let mode = if self.follow { "following" } else { "paused" };
+        let mut status = format!("Run {run} · stage: {stage} · level: {level} · {mode}");
+
+        let search = if self.searching || !self.search.is_empty() {
+            let cursor = if self.searching { "█" } else { "" };
+            let invalid = if !self.search.is_empty() && self.pattern.is_none() {
+                " (invalid pattern)"
+            } else {
+                ""
+            };
+            format!("Search: /{}{cursor}{invalid}", self.search)
+        } else {
+            String::new()
+        };
+        if let Some(notice) = &self.notice {
+            status.push_str(&format!(" · {notice}"));
+        }
+
+        let header = Paragraph::new(vec![Line::from(status).bold(), Line::from(search)])
+            .style(Style::new().fg(colors.row_fg).bg(colors.buffer_bg))
+            .block(Block::default().borders(Borders::NONE));
+        frame.render_widget(header, area);
+    }
+
+    /// A record as a line, colored by level, with search matches highlighted
+    fn record_line(&self, record: &LogRecord) -> Line<'static> {
+        let text = line_text(record);
+        let style = match record.level {
+            Level::Error => Style::new().fg(Color::Red),
+            Level::Warn => Style::new().fg(Color::Yellow),
+            Level::Info => Style::new(),
+            Level::Debug => Style::new().add_modifier(Modifier::DIM),
+        };
+        let Some(pattern) = &self.pattern else {
+            return Line::styled(text, style);
+        };
+
+        let highlight = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+        let mut spans = Vec::new();
+        let mut end = 0;
+        for found in pattern.find_iter(&text).filter(|m| !m.is_empty()) {
+            spans.push(Span::styled(text[end..found.start()].to_string(), style));
+            spans.push(Span::styled(found.as_str().to_string(), highlight));
+            end = found.end();
+        }
+        spans.push(Span::styled(text[end..].to_string(), style));
+        Line::from(spans)
+    }
+}
+
+/// A record as shown in the view; the run is in the status line, so it is left out
+fn line_text(record: &LogRecord) -> String {
+    let mut text = format!("{} {:<5} ", record.ts.format("%H:%M:%S%.3f"), record.level);
+    if let Some(stage) = &record.stage {
+        text.push_str(&format!("[{stage}] "));
+    }
+    text.push_str(&record.message);
+    text.push_str(&record.fields_text());
+    text
+}
@@ -0,0 +1,232 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    symbols::Marker,
+    text::Line,
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Dataset, GraphType, HighlightSpacing, Paragraph, Row,
+        Table, TableState,
+    },
+};
+use theme::TableColors;
+
+use crate::app::follow::FollowedRun;
+use crate::metrics::{Goal, MetricStore, MetricTail, RunMetrics, format_value};
+
+/// Width of the Trend column, in points
+const TREND_WIDTH: usize = 20;
+
+/// Live metrics of one run (FR-MON-02).
+///
+/// Like the Logs view it follows the most recent run unless a workflow pins its own run. A
+/// table lists the latest and best value of every metric with a sparkline of its trend; the
+/// selected metric is drawn as a line chart below.
+pub struct MetricsView {
+    store: MetricStore,
+    run: FollowedRun,
+    tail: Option<MetricTail>,
+    metrics: RunMetrics,
+    /// Index of the charted metric, in name order
+    selected: usize,
+    /// Last problem reading the store
+    notice: Option<String>,
+}
+
+impl MetricsView {
+    pub fn new(store: MetricStore) -> Self {
+        Self {
+            store,
+            run: FollowedRun::default(),
+            tail: None,
+            metrics: RunMetrics::default(),
+            selected: 0,
+            notice: None,
+        }
+    }
+
+    /// Show `run` from now on, e.g. the run of the workflow being stepped through
+    pub fn pin_run(&mut self, run: &str) {
+        self.run.pin(run);
+        self.show_run();
+    }
+
+    pub fn run(&self) -> Option<&str> {
+        self.run.get()
+    }
+
+    /// Name of the charted metric
+    pub fn selected(&self) -> Option<&str> {
+        self.metrics.names().nth(self.selected)
+    }
+
+    /// Start over at the run now followed
+    fn show_run(&mut self) {
+        self.tail = self.run.get().map(|run| self.store.tail(run));
+        self.metrics = RunMetrics::default();
+        self.selected = 0;
+    }
+
+    /// Read points written since the last poll, switching to a newer run when not pinned
+    pub fn poll(&mut self) {
+        match self.run.follow(|| self.store.runs()) {
+            Ok(true) => self.show_run(),
+            Ok(false) => {}
+            Err(e) => self.notice = Some(format!("Cannot read metrics: {e}")),
+        }
+        // Keep the same metric selected when new names sort before it
+        let selected = self.selected().map(str::to_string);
+        let Some(tail) = &mut self.tail else {
+            return;
+        };
+        match tail.poll() {
+            Ok(points) => points.into_iter().for_each(|point| self.metrics.add(point)),
+            Err(e) => self.notice = Some(format!("Cannot read metrics: {e}")),
+        }
+        if let Some(name) = selected {
+            self.selected = self.metrics.names().position(|n| n == name).unwrap_or(0);
+        }
+    }
+
+    /// Chart the next (`delta` > 0) or previous metric, wrapping around
+    pub fn select(&mut self, delta: isize) {
+        let count = self.metrics.series.len();
+        if count > 0 {
+            self.selected = (self.selected as isize + delta).rem_euclid(count as isize) as usize;
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, block: Block, colors: &TableColors) {
+        let inner = block.inner(area);
+        frame.render_widget(block.bg(colors.buffer_bg), area);
+        let style = Style::new().fg(colors.row_fg).bg(colors.buffer_bg);
+
+        if self.metrics.is_empty() {
+            let message = match self.run.get() {
+                None => "No metrics recorded yet.".to_string(),
+                Some(run) => format!("Waiting for metrics of run {run}…"),
+            };
+            frame.render_widget(Paragraph::new(message).style(style), inner);
+            return;
+        }
+
+        let rows = (self.metrics.series.len() as u16 + 1).min(inner.height / 2);
+        let rects = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(rows),
+            Constraint::Min(3),
+        ])
+        .split(inner);
+
+        let mut status = format!(
+            "Run {} · {} metrics",
+            self.run.get().unwrap_or("none"),
+            self.metrics.series.len()
+        );
+        if let Some(notice) = &self.notice {
+            status.push_str(&format!(" · {notice}"));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(status).bold()).style(style),
+            rects[0],
+        );
+
+        self.render_table(frame, rects[1], colors);
+        self.render_chart(frame, rects[2], colors);
+    }
+
+    /// Latest and best value of every metric, with its recent trend
+    fn render_table(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let header = ["Metric", "Latest", "Best", "Steps", "Trend"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(Style::new().fg(colors.header_fg).bg(colors.header_bg));
+
+        let rows = self.metrics.series.iter().map(|(name, series)| {
+            let goal = Goal::of(name);
+            let arrow = match goal {
+                Goal::Minimize => "↓",
+                Goal::Maximize => "↑",
+            };
+            Row::new([
+                name.clone(),
+                series.latest().map(format_value).unwrap_or_default(),
+                series
+                    .best(goal)
+                    .map(|best| format!("{} {arrow}", format_value(best)))
+                    .unwrap_or_default(),
+                series.points.len().to_string(),
+                series.sparkline(TREND_WIDTH),
+            ])
+            .style(Style::new().fg(colors.row_fg))
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(12),
+                Constraint::Length(12),
+                Constraint::Length(14),
+                Constraint::Length(6),
+                Constraint::Length(TREND_WIDTH as u16),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(
+            Style::new()
+                .add_modifier(Modifier::REVERSED)
+                .fg(colors.selected_row_style_fg),
+        )
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always)
+        .bg(colors.buffer_bg);
+
+        let mut state = TableState::default().with_selected(self.selected);
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    /// Line chart of the selected metric over its steps
+    fn render_chart(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let Some((name, series)) = self.metrics.series.iter().nth(self.selected) else {
+            return;
+        };
+        let data: Vec<(f64, f64)> = series
+            .points
+            .iter()
+            .map(|(step, value)| (*step as f64, *value))
+            .collect();
+        let (first, last) = data.iter().fold((f64::MAX, f64::MIN), |(lo, hi), (x, _)| {
+            (lo.min(*x), hi.max(*x))
+        });
+        let (low, high) = series.bounds().unwrap_or((0.0, 1.0));
+        // Pad flat series so the line is drawn mid-chart instead of on an edge
+        let (low, high) = if high > low {
+            (low, high)
+        } else {
+            (low - 1.0, high + 1.0)
+        };
+
+        let dataset = Dataset::default()
+            .name(name.clone())
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().fg(Color::Cyan))
+            .data(&data);
+        let chart = Chart::new(vec![dataset])
+            .block(Block::default().borders(Borders::TOP).title(name.clone()))
+            .style(Style::new().fg(colors.row_fg).bg(colors.buffer_bg))
+            .x_axis(
+                Axis::default()
+                    .title("step")
+                    .bounds([first, last.max(first + 1.0)])
+                    .labels([first.to_string(), last.to_string()]),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([low, high])
+                    .labels([format_value(low), format_value(high)]),
+            );
+        frame.render_widget(chart, area);
+    }
+}
@@ -0,0 +1,1019 @@
+pub mod data;
+pub mod follow;
+pub mod history;
+pub mod keymap;
+pub mod layout;
+pub mod logs;
+pub mod metrics;
+pub mod workflow;
+
+use std::time::Duration;
+
+// Ratatui imports
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyEvent, KeyEventKind},
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Text},
+    widgets::{
+        Block, BorderType, Borders, Cell, HighlightSpacing, Paragraph, Row, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Table, TableState, Tabs, Wrap,
+    },
+};
+use theme::TableColors;
+
+// Internal imports
+use crate::actors::tui::TUIMsg;
+use crate::actors::{TUI, User, View};
+use crate::catalog;
+use crate::config;
+use crate::history::RunHistory;
+use crate::logging::LogStore;
+use crate::metrics::MetricStore;
+use crate::registry::ToolRegistry;
+use crate::workflow::{Pipeline, RunContext};
+use data::DataView;
+use history::HistoryView;
+use keymap::{
+    Action, DataAction, GlobalAction, HistoryAction, Keymap, KeymapError, LogsAction,
+    MetricsAction, RegistryAction, WorkflowAction,
+};
+use layout::{PaneLayout, SplitDirection};
+use logs::LogView;
+use metrics::MetricsView;
+use workflow::WorkflowSession;
+
+/// Percent a pane grows or shrinks per keypress
+const RESIZE_STEP: i16 = 5;
+
+/// How long to wait for input before checking on a running stage
+const TICK: Duration = Duration::from_millis(100);
+
+/// A row of the Tool Registry table
+struct ToolRow {
+    name: String,
+    version: String,
+    description: String,
+    status: String,
+}
+
+/// The riptide dashboard
+pub struct App {
+    user: User,
+    tui: TUI,
+    layout: PaneLayout,
+    keymap: Keymap,
+    /// Pipeline being stepped through in the Workflow view
+    workflow: Option<WorkflowSession>,
+    logs: LogView,
+    metrics: MetricsView,
+    history: HistoryView,
+    data: DataView,
+    tools: Vec<ToolRow>,
+    state: TableState,
+    scroll_state: ScrollbarState,
+    colors: TableColors,
+    quit: bool,
+}
+
+impl App {
+    /// Open the dashboard with the user's keymap, the pane layout of the previous session,
+    /// the tools on the search path, the log and metric stores and the run history
+    pub fn new() -> Result<Self, KeymapError> {
+        let keymap = Keymap::load(&config::keymap_path())?;
+        let layout = PaneLayout::load(&config::layout_path()).unwrap_or_default();
+        Ok(Self::with_config(
+            layout,
+            keymap,
+            &ToolRegistry::load(),
+            LogStore::default(),
+            MetricStore::default(),
+            RunHistory::default(),
+        ))
+    }
+
+    pub fn with_config(
+        layout: PaneLayout,
+        keymap: Keymap,
+        registry: &ToolRegistry,
+        logs: LogStore,
+        metrics: MetricStore,
+        history: RunHistory,
+    ) -> Self {
+        let tools: Vec<ToolRow> = registry
+            .tools()
+            .iter()
+            .map(|tool| ToolRow {
+                name: tool.name().to_string(),
+                version: tool.manifest.version.clone(),
+                description: tool.manifest.description.clone(),
+                status: match tool.entry_path() {
+                    Some(_) => "installed".into(),
+                    None => "not installed".into(),
+                },
+            })
+            .collect();
+
+        let mut tui = TUI::new();
+        tui.open();
+        let _ = tui.send(TUIMsg::SetView(layout.focused_view()));
+
+        Self {
+            // The dashboard is launched from the CLI
+            user: User::InTUI,
+            tui,
+            layout,
+            keymap,
+            workflow: None,
+            logs: LogView::new(logs),
+            metrics: MetricsView::new(metrics),
+            history: HistoryView::new(history),
+            data: DataView::new(catalog::catalog_tool(registry)),
+            scroll_state: ScrollbarState::new(tools.len().saturating_sub(1)),
+            tools,
+            state: TableState::default().with_selected(0),
+            colors: TableColors::new_from_pywal(),
+            quit: false,
+        }
+    }
+
+    /// The view shown in the focused pane
+    pub fn view(&self) -> View {
+        match self.tui {
+            TUI::Open(view) => view,
+            _ => View::default(),
+        }
+    }
+
+    pub fn user(&self) -> User {
+        self.user
+    }
+
+    /// Step through `pipeline` in the Workflow view, pausing before its first stage
+    pub fn load_pipeline(&mut self, pipeline: Pipeline, context: RunContext) {
+        let _ = self.user.define_workflow(&mut self.tui);
+        self.layout.set_view(View::Workflow);
+        let _ = self.user.step_workflow();
+        if context.log.is_some() {
+            self.logs.pin_run(&context.run_id);
+        }
+        if context.metrics.is_some() {
+            self.metrics.pin_run(&context.run_id);
+        }
+        self.workflow = Some(WorkflowSession::new(pipeline, context));
+    }
+
+    fn set_view(&mut self, view: View) {
+        self.layout.set_view(view);
+        let _ = self.tui.send(TUIMsg::SetView(view));
+    }
+
+    /// Keep the TUI actor's view in step with the focused pane
+    fn sync_focus(&mut self) {
+        let _ = self.tui.send(TUIMsg::SetView(self.layout.focused_view()));
+    }
+
+    fn next_row(&mut self) {
+        if self.tools.is_empty() {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(i) if i + 1 < self.tools.len() => i + 1,
+            _ => 0,
+        };
+        self.state.select(Some(i));
+        self.scroll_state = self.scroll_state.position(i);
+    }
+
+    fn previous_row(&mut self) {
+        if self.tools.is_empty() {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(0) | None => self.tools.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.state.select(Some(i));
+        self.scroll_state = self.scroll_state.position(i);
+    }
+
+    pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<(), Box<dyn std::error::Error>> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(TICK)?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key);
+            }
+            self.tick();
+        }
+        let _ = self.tui.send(TUIMsg::Close);
+        self.layout.save(&config::layout_path())?;
+        Ok(())
+    }
+
+    /// Pick up progress of a running stage, new log records and metrics, and read the
+    /// run history and the data catalog once they are first shown
+    fn tick(&mut self) {
+        if let Some(session) = &mut self.workflow {
+            session.poll(&mut self.user);
+        }
+        self.logs.poll();
+        self.metrics.poll();
+        self.data.poll();
+        if !self.history.is_loaded() && self.layout.views().contains(&View::History) {
+            self.history.refresh();
+        }
+        if !self.data.is_loaded() && self.layout.views().contains(&View::Data) {
+            self.data.refresh();
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        // The parameter editor takes every key until it is closed
+        if self.view() == View::Workflow
+            && let Some(session) = &mut self.workflow
+            && session.edit_key(key)
+        {
+            return;
+        }
+        // So does the log search box
+        if self.view() == View::Logs && self.logs.search_key(key) {
+            return;
+        }
+        let Some(action) = self.keymap.action(self.view(), key) else {
+            return;
+        };
+        match action {
+            Action::Global(action) => match action {
+                GlobalAction::Quit => self.quit = true,
+                GlobalAction::NextView => self.set_view(self.view().next()),
+                GlobalAction::PreviousView => self.set_view(self.view().previous()),
+                GlobalAction::ShowRegistry => self.set_view(View::Registry),
+                GlobalAction::ShowWorkflow => self.set_view(View::Workflow),
+                GlobalAction::ShowLogs => self.set_view(View::Logs),
+                GlobalAction::ShowMetrics => self.set_view(View::Metrics),
+                GlobalAction::ShowHistory => self.set_view(View::History),
+                GlobalAction::ShowData => self.set_view(View::Data),
+                GlobalAction::ShowHelp => self.set_view(View::Help),
+                GlobalAction::SplitVertical => self.layout.split(SplitDirection::Vertical),
+                GlobalAction::SplitHorizontal => self.layout.split(SplitDirection::Horizontal),
+                GlobalAction::ClosePane => {
+                    self.layout.close();
+                    self.sync_focus();
+                }
+                GlobalAction::GrowPane => self.layout.resize(RESIZE_STEP),
+                GlobalAction::ShrinkPane => self.layout.resize(-RESIZE_STEP),
+                GlobalAction::FocusNext => {
+                    self.layout.focus_next();
+                    self.sync_focus();
+                }
+                GlobalAction::FocusPrevious => {
+                    self.layout.focus_previous();
+                    self.sync_focus();
+                }
+                GlobalAction::ZoomPane => self.layout.toggle_zoom(),
+            },
+            Action::Registry(action) => match action {
+                RegistryAction::Up => self.previous_row(),
+                RegistryAction::Down => self.next_row(),
+            },
+            Action::Workflow(action) => {
+                let Some(session) = &mut self.workflow else {
+                    return;
+                };
+                match action {
+                    WorkflowAction::RunStage => session.run_next(&mut self.user),
+                    WorkflowAction::SkipStage => session.skip(&mut self.user),
+                    WorkflowAction::RetryStage => session.retry(&mut self.user, &mut self.tui),
+                    WorkflowAction::EditParams => session.edit(),
+                    WorkflowAction::Abort => session.abort(&mut self.user),
+                }
+            }
+            Action::Logs(action) => match action {
+                LogsAction::Up => self.logs.scroll(-1),
+                LogsAction::Down => self.logs.scroll(1),
+                LogsAction::PageUp => self.logs.page(-1),
+                LogsAction::PageDown => self.logs.page(1),
+                LogsAction::ToggleFollow => self.logs.toggle_follow(),
+                LogsAction::Search => self.logs.start_search(),
+                LogsAction::NextStage => self.logs.next_stage(),
+                LogsAction::NextLevel => self.logs.next_level(),
+                LogsAction::FirstError => self.logs.first_error(),
+                LogsAction::ClearFilters => self.logs.clear_filters(),
+            },
+            Action::Metrics(action) => match action {
+                MetricsAction::Up => self.metrics.select(-1),
+                MetricsAction::Down => self.metrics.select(1),
+            },
+            Action::History(action) => match action {
+                HistoryAction::Up => self.history.select(-1),
+                HistoryAction::Down => self.history.select(1),
+                HistoryAction::Mark => self.history.mark(),
+                HistoryAction::Diff => self.history.diff(),
+                HistoryAction::Rerun => {
+                    // Refuse while a workflow is stepping, as loading would replace it
+                    if matches!(self.user, User::RunningWorkflow(_)) {
+                        return;
+                    }
+                    if let Some(pipeline) = self.history.rerun() {
+                        self.load_pipeline(pipeline, RunContext::recorded());
+                    }
+                }
+                HistoryAction::Refresh => self.history.refresh(),
+            },
+            Action::Data(action) => match action {
+                DataAction::Up => self.data.select(-1),
+                DataAction::Down => self.data.select(1),
+                DataAction::Refresh => self.data.refresh(),
+            },
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let vertical = &Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(5),
+        ]);
+        let rects = vertical.split(frame.area());
+
+        self.render_tabs(frame, rects[0]);
+        for (index, view, area) in self.layout.areas(rects[1]) {
+            let block = self.pane_block(view, index == self.layout.focus());
+            match view {
+                View::Registry => {
+                    self.render_registry(frame, area, block);
+                    self.render_scrollbar(frame, area);
+                }
+                View::Workflow => match &self.workflow {
+                    Some(session) => session.render(frame, area, block, &self.colors),
+                    None => self.render_placeholder(
+                        frame,
+                        area,
+                        block,
+                        "No workflow loaded. Step through one with `riptide launch <pipeline.yaml>`.",
+                    ),
+                },
+                View::Logs => self.logs.render(frame, area, block, &self.colors),
+                View::Metrics => self.metrics.render(frame, area, block, &self.colors),
+                View::History => self.history.render(frame, area, block, &self.colors),
+                View::Data => self.data.render(frame, area, block, &self.colors),
+                View::Help => self.render_help(frame, area, block),
+            }
+        }
+        self.render_footer(frame, rects[2]);
+    }
+
+    /// Border of a pane, highlighted when it has focus
+    fn pane_block(&self, view: View, focused: bool) -> Block<'static> {
+        let mut title = view.title().to_string();
+        if focused && self.layout.is_zoomed() {
+            title.push_str(" (zoomed)");
+        }
+        let border_style = if focused {
+            Style::new().fg(self.colors.footer_border_color)
+        } else {
+            Style::new().fg(self.colors.row_fg)
+        };
+        let border_type = if focused {
+            BorderType::Thick
+        } else {
+            BorderType::Plain
+        };
+        Block::bordered()
+            .title(title)
+            .border_type(border_type)
+            .border_style(border_style)
+    }
+
+    fn render_tabs(&self, frame: &mut Frame, area: Rect) {
+        let titles = View::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, view)| Line::from(format!(" {} {} ", i + 1, view.title())));
+
+        let tabs = Tabs::new(titles)
+            .select(self.view().index())
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .highlight_style(
+                Style::new()
+                    .fg(self.colors.header_fg)
+                    .bg(self.colors.header_bg)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::new().fg(self.colors.footer_border_color))
+                    .title(" riptide "),
+            );
+        frame.render_widget(tabs, area);
+    }
+
+    fn render_registry(&mut self, frame: &mut Frame, area: Rect, block: Block) {
+        let header_style = Style::default()
+            .fg(self.colors.header_fg)
+            .bg(self.colors.header_bg);
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(self.colors.selected_row_style_fg);
+
+        let header = ["Name", "Version", "Description", "Status"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(header_style)
+            .height(1);
+
+        let rows = self.tools.iter().enumerate().map(|(i, tool)| {
+            let color = if i % 2 == 0 {
+                self.colors.normal_row_color
+            } else {
+                self.colors.alt_row_color
+            };
+            [&tool.name, &tool.version, &tool.description, &tool.status]
+                .into_iter()
+                .map(|content| Cell::from(Text::from(content.as_str())))
+                .collect::<Row>()
+                .style(Style::new().fg(self.colors.row_fg).bg(color))
+        });
+
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Min(20),
+                Constraint::Length(16),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(selected_row_style)
+        .highlight_symbol(" █ ")
+        .bg(self.colors.buffer_bg)
+        .highlight_spacing(HighlightSpacing::Always)
+        .block(block);
+
+        frame.render_stateful_widget(t, area, &mut self.state);
+    }
+
+    fn render_scrollbar(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            &mut self.scroll_state,
+        );
+    }
+
+    fn render_placeholder(&self, frame: &mut Frame, area: Rect, block: Block, message: &str) {
+        let placeholder = Paragraph::new(message)
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .block(block)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(placeholder, area);
+    }
+
+    /// Help panel listing every binding of the active keymap (NFR-06)
+    fn render_help(&self, frame: &mut Frame, area: Rect, block: Block) {
+        let mut rows = Vec::new();
+        let sections = std::iter::once((None, "Global"))
+            .chain(View::ALL.iter().map(|view| (Some(*view), view.title())));
+        for (view, title) in sections {
+            let bindings = self.keymap.help(view);
+            if bindings.is_empty() {
+                continue;
+            }
+            rows.push(
+                Row::new([Cell::from(title), Cell::from("")])
+                    .style(Style::new().add_modifier(Modifier::BOLD)),
+            );
+            for (keys, description) in bindings {
+                rows.push(Row::new([Cell::from(keys), Cell::from(description)]));
+            }
+        }
+
+        let help = Table::new(rows, [Constraint::Length(20), Constraint::Min(20)])
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .block(block);
+        frame.render_widget(help, area);
+    }
+
+    /// Footer with the bindings of the focused view and the global scope
+    fn footer_text(&self) -> Text<'static> {
+        let line = |bindings: Vec<(String, &str)>| {
+            bindings
+                .into_iter()
+                .map(|(keys, description)| format!("({keys}) {description}"))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        };
+        // The focused view's bindings come first so they survive wrapping
+        let mut lines = Vec::new();
+        let view_bindings = self.keymap.help(Some(self.view()));
+        if !view_bindings.is_empty() {
+            lines.push(line(view_bindings));
+        }
+        lines.push(line(self.keymap.help(None)));
+        Text::from_iter(lines)
+    }
+
+    fn render_footer(&self, frame: &mut Frame, area: Rect) {
+        let info_footer = Paragraph::new(self.footer_text())
+            .style(
+                Style::new()
+                    .fg(self.colors.row_fg)
+                    .bg(self.colors.buffer_bg),
+            )
+            .centered()
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Double)
+                    .border_style(Style::new().fg(self.colors.footer_border_color)),
+            );
+        frame.render_widget(info_footer, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::{KeyCode, KeyModifiers};
+    use ratatui::{Terminal, backend::TestBackend};
+
+    /// A dashboard listing the submodules of this workspace, with no logs, metrics or runs
+    fn app(layout: PaneLayout, keymap: Keymap) -> App {
+        let workspace = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let logs = LogStore::new(workspace.join("target").join("no-logs"));
+        let metrics = MetricStore::new(workspace.join("target").join("no-metrics"));
+        App::with_config(
+            layout,
+            keymap,
+            &ToolRegistry::discover(&[workspace]),
+            logs,
+            metrics,
+            RunHistory::new("mem://"),
+        )
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn render(app: &mut App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
+    #[test]
+    fn test_tabs_switch_views() {
+        let mut app = app(PaneLayout::default(), Keymap::default());
+        assert_eq!(app.view(), View::Workflow);
+
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.view(), View::Logs);
+
+        press(&mut app, KeyCode::BackTab);
+        press(&mut app, KeyCode::BackTab);
+        assert_eq!(app.view(), View::Registry);
+
+        press(&mut app, KeyCode::Char('4'));
+        assert_eq!(app.view(), View::Metrics);
+
+        press(&mut app, KeyCode::Char('5'));
+        assert_eq!(app.view(), View::History);
+
+        press(&mut app, KeyCode::Char('6'));
+        assert_eq!(app.view(), View::Data);
+
+        press(&mut app, KeyCode::Char('7'));
+        assert_eq!(app.view(), View::Help);
+
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn test_registry_lists_submodules() {
+        let mut app = app(PaneLayout::default(), Keymap::default());
+        press(&mut app, KeyCode::Char('1'));
+
+        let screen = render(&mut app);
+        assert!(screen.contains("Tool Registry"));
+        for name in ["dock", "sail", "surf", "buoy"] {
+            assert!(screen.contains(name), "{name} missing from registry");
+        }
+        assert!(screen.contains("Data Organization and Cleaning Kit"));
+
+        press(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.state.selected(), Some(app.tools.len() - 1));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_panes_host_independent_views() {
+        let mut app = app(PaneLayout::new(View::Registry), Keymap::default());
+        press(&mut app, KeyCode::Char('|'));
+        press(&mut app, KeyCode::Char('3'));
+        assert_eq!(app.layout.views(), vec![View::Registry, View::Logs]);
+
+        let screen = render(&mut app);
+        assert!(screen.contains("Tool Registry"));
+        assert!(screen.contains("No logs recorded yet."));
+
+        // Focus follows to the other pane and the tab bar reflects it
+        press(&mut app, KeyCode::Char('w'));
+        assert_eq!(app.view(), View::Registry);
+
+        press(&mut app, KeyCode::Char('z'));
+        let screen = render(&mut app);
+        assert!(screen.contains("(zoomed)"));
+        assert!(!screen.contains("No logs recorded yet."));
+    }
+
+    #[test]
+    fn test_help_and_footer_follow_keymap() {
+        let keymap = Keymap::from_toml("[global]\nshow_help = [\"h\"]\n").unwrap();
+        let mut app = app(PaneLayout::new(View::Registry), keymap);
+
+        let screen = render(&mut app);
+        assert!(screen.contains("(h) show this help"));
+        assert!(screen.contains("(k/up) move up"));
+
+        press(&mut app, KeyCode::Char('h'));
+        assert_eq!(app.view(), View::Help);
+        let screen = render(&mut app);
+        assert!(screen.contains("split pane side by side"));
+    }
+
+    #[test]
+    fn test_logs_view_tails_filters_and_searches() {
+        use crate::logging::{Level, LogRecord};
+        use ratatui::style::Modifier;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = LogStore::new(dir.path());
+        let workspace = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut app = App::with_config(
+            PaneLayout::new(View::Logs),
+            Keymap::default(),
+            &ToolRegistry::discover(&[workspace]),
+            store.clone(),
+            MetricStore::new(dir.path().join("metrics")),
+            RunHistory::new("mem://"),
+        );
+        app.tick();
+        assert!(render(&mut app).contains("No logs recorded yet."));
+
+        let log = |stage: &str, level, message: &str| {
+            store
+                .append(&LogRecord::new("7", Some(stage), level, message))
+                .unwrap()
+        };
+        log("load", Level::Info, "reading data.csv");
+        log("fit", Level::Info, "epoch 1 loss=0.9");
+        log("fit", Level::Error, "loss is NaN");
+        log("fit", Level::Info, "epoch 2 loss=0.8");
+        app.tick();
+        let screen = render(&mut app);
+        assert!(screen.contains("Run 7"));
+        assert!(screen.contains("[load] reading data.csv"));
+        assert!(screen.contains("following"));
+
+        // Records written later show up on the next tick
+        log("report", Level::Warn, "slow disk");
+        app.tick();
+        assert!(render(&mut app).contains("[report] slow disk"));
+
+        press(&mut app, KeyCode::Char('e'));
+        assert!(!app.logs.is_following());
+        let screen = render(&mut app);
+        assert!(screen.contains("paused"));
+
+        // Search as you type; keys go to the search box, so `s` does not filter
+        press(&mut app, KeyCode::Char('/'));
+        for c in "epoch s".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert!(render(&mut app).contains("No records match the filters."));
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Enter);
+        assert!(!app.logs.is_searching());
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(screen.contains("epoch 1") && screen.contains("epoch 2"));
+        assert!(!screen.contains("reading data.csv"));
+        // One cell per character; `find` counts bytes
+        let start = screen[..screen.find("epoch 1").unwrap()].chars().count();
+        let highlighted = |i: usize| buffer.content()[i].modifier.contains(Modifier::REVERSED);
+        assert!(highlighted(start));
+        assert!(!highlighted(start + 6));
+
+        // Stage and level filters
+        press(&mut app, KeyCode::Char('c'));
+        press(&mut app, KeyCode::Char('s'));
+        press(&mut app, KeyCode::Char('s'));
+        press(&mut app, KeyCode::Char('l'));
+        press(&mut app, KeyCode::Char('l'));
+        let screen = render(&mut app);
+        assert!(screen.contains("stage: fit · level: warn"));
+        assert!(screen.contains("loss is NaN"));
+        assert!(!screen.contains("epoch"));
+        assert!(!screen.contains("slow disk"));
+    }
+
+    #[test]
+    fn test_metrics_view_charts_selected_metric() {
+        use crate::metrics::MetricPoint;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = MetricStore::new(dir.path());
+        let workspace = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut app = App::with_config(
+            PaneLayout::new(View::Metrics),
+            Keymap::default(),
+            &ToolRegistry::discover(&[workspace]),
+            LogStore::new(dir.path().join("logs")),
+            store.clone(),
+            RunHistory::new("mem://"),
+        );
+        app.tick();
+        assert!(render(&mut app).contains("No metrics recorded yet."));
+
+        let report = |step, name: &str, value| {
+            store
+                .append(&MetricPoint {
+                    run: "3".into(),
+                    step,
+                    name: name.into(),
+                    value,
+                })
+                .unwrap()
+        };
+        report(1, "loss", 0.9);
+        report(1, "accuracy", 0.5);
+        report(2, "loss", 0.4);
+        app.tick();
+        let screen = render(&mut app);
+        assert!(screen.contains("Run 3 · 2 metrics"));
+        assert!(screen.contains("0.4000"));
+        assert!(screen.contains("0.4000 ↓"));
+        assert!(screen.contains("0.5000 ↑"));
+        assert_eq!(app.metrics.selected(), Some("accuracy"));
+
+        // Points reported later extend the table; the selection moves with the keys
+        report(3, "loss", 0.2);
+        app.tick();
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.metrics.selected(), Some("loss"));
+        let screen = render(&mut app);
+        assert!(screen.contains("0.2000 ↓"));
+        assert!(screen.contains("step"));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.metrics.selected(), Some("accuracy"));
+    }
+
+    #[test]
+    fn test_data_view_reports_missing_dock() {
+        let mut app = app(PaneLayout::default(), Keymap::default());
+        app.data = DataView::new(None);
+        press(&mut app, KeyCode::Char('6'));
+        assert!(!app.data.is_loaded());
+
+        app.tick();
+        assert!(app.data.is_loaded());
+        assert!(app.data.selected().is_none());
+        let screen = render(&mut app);
+        assert!(screen.contains("Data"));
+        assert!(screen.contains("dock tool is not installed"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_data_view_scans_without_blocking() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dock = dir.path().join("dock");
+        let json = r#"{"root":"data","entries":[{"path":"churn.csv","category":"table",
+            "kind":"csv","bytes":21,"modified":"2026-01-02T03:04:05Z","rows":2,"columns":1,
+            "schema":[{"name":"age","dtype":"i64"}],"error":null}]}"#;
+        let script = format!("#!/bin/sh\nsleep 0.3\ncat <<'EOF'\n{json}\nEOF\n");
+        std::fs::write(&dock, script).unwrap();
+        std::fs::set_permissions(&dock, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut app = app(PaneLayout::default(), Keymap::default());
+        app.data = DataView::new(Some(dock));
+        press(&mut app, KeyCode::Char('6'));
+        let started = std::time::Instant::now();
+        app.tick();
+        assert!(
+            started.elapsed() < Duration::from_millis(200),
+            "tick waited for dock"
+        );
+        assert!(app.data.is_scanning());
+        assert!(render(&mut app).contains("Cataloging the data root"));
+
+        while app.data.is_scanning() {
+            assert!(started.elapsed() < Duration::from_secs(10), "dock hung");
+            std::thread::sleep(Duration::from_millis(10));
+            app.tick();
+        }
+        assert_eq!(
+            app.data.selected().map(|entry| entry.path.clone()),
+            Some("churn.csv".into())
+        );
+        assert!(render(&mut app).contains("1 files, 1 tables in data"));
+    }
+
+    #[test]
+    fn test_history_view_compares_runs() {
+        use crate::workflow::{RunReport, StageReport, StageStatus};
+
+        let dir = tempfile::tempdir().unwrap();
+        let history = RunHistory::new(format!(
+            "surrealkv://{}",
+            dir.path().join("riptide.db").display()
+        ));
+        let mut pipeline = Pipeline::from_yaml(
+            "name: fit\nstages:\n  - name: train\n    run: echo 1\n",
+            dir.path(),
+        )
+        .unwrap();
+        let report = RunReport {
+            stages: vec![StageReport {
+                name: "train".into(),
+                status: StageStatus::Succeeded,
+                duration: Duration::from_secs(1),
+            }],
+        };
+        let started = chrono::Utc::now();
+        for (run, offset) in [("1", 0), ("2", 60)] {
+            let context = RunContext {
+                run_id: run.into(),
+                artifact_dir: dir.path().join("artifacts"),
+                log: None,
+                metrics: None,
+                history: None,
+            };
+            let started = started + chrono::Duration::seconds(offset);
+            history
+                .record(&pipeline, &report, &context, started)
+                .unwrap();
+            pipeline.def.stages[0].set_params("echo 2").unwrap();
+        }
+
+        let workspace = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut app = App::with_config(
+            PaneLayout::new(View::History),
+            Keymap::default(),
+            &ToolRegistry::discover(&[workspace]),
+            LogStore::new(dir.path().join("logs")),
+            MetricStore::new(dir.path().join("metrics")),
+            history,
+        );
+        assert!(!app.history.is_loaded());
+        app.tick();
+        let screen = render(&mut app);
+        assert!(screen.contains("2 runs"));
+        assert!(screen.contains("succeeded"));
+        assert_eq!(app.history.selected().unwrap().run_id(), "2");
+
+        // Compare the newest run against the older one
+        press(&mut app, KeyCode::Char('j'));
+        press(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.history.marked(), Some("1"));
+        press(&mut app, KeyCode::Char('k'));
+        press(&mut app, KeyCode::Char('d'));
+        let diff = app.history.diff_shown().unwrap();
+        assert_eq!(diff.params[0].before.as_deref(), Some("echo 1"));
+        assert_eq!(diff.params[0].after.as_deref(), Some("echo 2"));
+        let screen = render(&mut app);
+        assert!(screen.contains("Comparison"));
+        assert!(screen.contains("echo 1 → echo 2"));
+
+        // A rerun repeats the pipeline as the selected run executed it
+        let rerun = app.history.rerun().unwrap();
+        assert_eq!(rerun.stage("train").unwrap().params(), "echo 2");
+    }
+
+    /// Let the running stage finish
+    #[cfg(unix)]
+    fn wait_for_stage(app: &mut App) {
+        let started = std::time::Instant::now();
+        while app
+            .workflow
+            .as_ref()
+            .is_some_and(WorkflowSession::is_running)
+        {
+            assert!(started.elapsed() < Duration::from_secs(10), "stage hung");
+            std::thread::sleep(Duration::from_millis(10));
+            app.tick();
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_step_through_workflow() {
+        use crate::actors::user::Progress;
+
+        let pipeline = Pipeline::from_yaml(
+            r#"
+name: steps
+stages:
+  - name: greet
+    run: echo hello
+  - name: broken
+    run: exit 3
+    needs: [greet]
+  - name: report
+    run: echo done
+    needs: [broken]
+"#,
+            std::path::Path::new("."),
+        )
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let context = RunContext {
+            run_id: "test".into(),
+            artifact_dir: dir.path().join("artifacts"),
+            log: None,
+            metrics: None,
+            history: None,
+        };
+        let mut app = app(PaneLayout::new(View::Registry), Keymap::default());
+        app.load_pipeline(pipeline, context);
+        assert_eq!(app.view(), View::Workflow);
+        assert_eq!(app.user(), User::RunningWorkflow(Progress::Paused));
+        let screen = render(&mut app);
+        assert!(screen.contains("Paused before `greet`"));
+        assert!(screen.contains("Command: sh -c echo hello"));
+
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.user(), User::RunningWorkflow(Progress::Running));
+        wait_for_stage(&mut app);
+        assert_eq!(app.user(), User::RunningWorkflow(Progress::Paused));
+        let screen = render(&mut app);
+        assert!(screen.contains("[greet] hello"));
+        assert!(screen.contains("Paused before `broken`"));
+
+        // Fix the broken stage before running it; keys go to the editor meanwhile
+        press(&mut app, KeyCode::Char('e'));
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Char('0'));
+        assert!(render(&mut app).contains("Parameters: exit 0"));
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('r'));
+        wait_for_stage(&mut app);
+
+        // Abort before the last stage, then retry: the last stage that ran runs again and
+        // the aborted one follows it
+        press(&mut app, KeyCode::Char('a'));
+        assert_eq!(app.user(), User::InTUI);
+        assert!(render(&mut app).contains("Pipeline `steps` did not succeed"));
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT));
+        wait_for_stage(&mut app);
+        assert_eq!(app.user(), User::RunningWorkflow(Progress::Paused));
+        assert!(render(&mut app).contains("Paused before `report`"));
+        press(&mut app, KeyCode::Enter);
+        wait_for_stage(&mut app);
+        assert_eq!(app.user(), User::InTUI);
+        let report = app.workflow.as_ref().unwrap().executor().report();
+        assert!(report.succeeded(), "{report}");
+        assert!(render(&mut app).contains("Pipeline `steps` succeeded."));
+    }
+}
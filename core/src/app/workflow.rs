@@ -0,0 +1,345 @@
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    Frame,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+};
+use theme::TableColors;
+
+use crate::actors::User;
+use crate::actors::tui::TuiPort;
+use crate::workflow::{Executor, Pipeline, RunContext, StageStatus};
+
+/// Lines of stage output kept for the Workflow view
+const OUTPUT_LINES: usize = 500;
+
+/// A pipeline being stepped through from the Workflow view (FR-ORCH-03).
+///
+/// The session pauses before every stage; the user decides whether to run, skip, retry,
+/// edit or abort. Stages run on a worker thread so the dashboard keeps redrawing.
+pub struct WorkflowSession {
+    executor: Executor<'static>,
+    /// Stage and hook output of this session, oldest first
+    output: Vec<String>,
+    /// Lines from the worker thread and from the executor's own hooks
+    lines: Receiver<String>,
+    line_sender: Sender<String>,
+    /// Outcome of the stage currently running, if any
+    worker: Option<Receiver<(StageStatus, Duration)>>,
+    /// Parameters of the next stage while the user edits them
+    editing: Option<String>,
+}
+
+impl WorkflowSession {
+    pub fn new(pipeline: Pipeline, context: RunContext) -> Self {
+        let (line_sender, lines) = mpsc::channel();
+        let hook_lines = line_sender.clone();
+        let executor = Executor::with_output(pipeline, move |out| {
+            let _ = hook_lines.send(out.to_string());
+        })
+        .with_context(context);
+
+        let mut output = vec![format!("Run {}", executor.context().run_id)];
+        if let Err(e) = executor.context().create_dirs() {
+            output.push(format!("Cannot create the artifact directory: {e}"));
+        }
+        Self {
+            executor,
+            output,
+            lines,
+            line_sender,
+            worker: None,
+            editing: None,
+        }
+    }
+
+    pub fn executor(&self) -> &Executor<'static> {
+        &self.executor
+    }
+
+    /// True while a stage is executing
+    pub fn is_running(&self) -> bool {
+        self.worker.is_some()
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing.is_some()
+    }
+
+    /// Run the next stage, or record it as skipped when its condition does not hold
+    pub fn run_next(&mut self, user: &mut User) {
+        if self.is_running() {
+            return;
+        }
+        let Some(plan) = self.executor.plan() else {
+            return;
+        };
+        let _ = user.resume_workflow();
+        if !plan.runnable {
+            self.executor.skip();
+            self.stage_done(user);
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let lines = self.line_sender.clone();
+        thread::spawn(move || {
+            let started = Instant::now();
+            let status = plan.execute(|out| {
+                let _ = lines.send(out.to_string());
+            });
+            let _ = tx.send((status, started.elapsed()));
+        });
+        self.worker = Some(rx);
+    }
+
+    /// Collect new output and record the running stage once it finishes
+    pub fn poll(&mut self, user: &mut User) {
+        self.drain_output();
+        let Some(worker) = &self.worker else {
+            return;
+        };
+        let (status, duration) = match worker.try_recv() {
+            Ok(outcome) => outcome,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => (
+                StageStatus::Error("the stage's worker stopped".into()),
+                Duration::ZERO,
+            ),
+        };
+        // The worker sends its last lines before the outcome
+        self.drain_output();
+        self.finish_stage(status, duration, user);
+        self.drain_output();
+    }
+
+    pub fn skip(&mut self, user: &mut User) {
+        if self.is_running() || self.executor.skip().is_none() {
+            return;
+        }
+        if self.executor.is_finished() {
+            let _ = user.finish_workflow();
+        }
+    }
+
+    /// Run the last stage that ran again, e.g. after it failed or the run was aborted; the
+    /// stages skipped or aborted after it follow it again
+    pub fn retry(&mut self, user: &mut User, tui: &mut impl TuiPort) {
+        if self.is_running() {
+            return;
+        }
+        let finished = self.executor.is_finished();
+        if !self.executor.rewind() {
+            return;
+        }
+        if finished {
+            // The run had ended with that stage; resume stepping through it
+            let _ = user.define_workflow(tui);
+            let _ = user.step_workflow();
+        }
+        self.run_next(user);
+    }
+
+    /// Start editing the next stage's parameters
+    pub fn edit(&mut self) {
+        if self.is_running() {
+            return;
+        }
+        let Some(plan) = self.executor.plan() else {
+            return;
+        };
+        self.editing = self
+            .executor
+            .pipeline()
+            .stage(&plan.stage)
+            .map(|stage| stage.params());
+    }
+
+    pub fn abort(&mut self, user: &mut User) {
+        if self.is_running() || self.executor.is_finished() {
+            return;
+        }
+        self.executor.abort();
+        let _ = user.finish_workflow();
+    }
+
+    /// Feed a key to the parameter editor; `false` when not editing
+    pub fn edit_key(&mut self, key: KeyEvent) -> bool {
+        let Some(buffer) = &mut self.editing else {
+            return false;
+        };
+        match key.code {
+            // An unclosed quote keeps the editor open to fix it
+            KeyCode::Enter if self.executor.edit(buffer).is_ok() => self.editing = None,
+            KeyCode::Esc => self.editing = None,
+            KeyCode::Backspace => {
+                buffer.pop();
+            }
+            KeyCode::Char(c) => buffer.push(c),
+            _ => {}
+        }
+        true
+    }
+
+    fn finish_stage(&mut self, status: StageStatus, duration: Duration, user: &mut User) {
+        self.worker = None;
+        self.executor.record(status, duration);
+        self.stage_done(user);
+    }
+
+    /// Pause before the next stage, or end the run after the last one
+    fn stage_done(&mut self, user: &mut User) {
+        let _ = if self.executor.is_finished() {
+            user.finish_workflow()
+        } else {
+            user.pause_workflow()
+        };
+    }
+
+    fn drain_output(&mut self) {
+        self.output.extend(self.lines.try_iter());
+        let excess = self.output.len().saturating_sub(OUTPUT_LINES);
+        self.output.drain(..excess);
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, block: Block, colors: &TableColors) {
+        let inner = block.inner(area);
+        frame.render_widget(block.bg(colors.buffer_bg), area);
+
+        let stage_count = self.executor.pipeline().def.stages.len() as u16;
+        let rects = Layout::vertical([
+            Constraint::Length(stage_count + 1),
+            Constraint::Length(4),
+            Constraint::Min(3),
+        ])
+        .split(inner);
+
+        self.render_stages(frame, rects[0], colors);
+        self.render_next(frame, rects[1], colors);
+        self.render_output(frame, rects[2], colors);
+    }
+
+    fn render_stages(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let header = ["", "Stage", "Command", "Status"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(Style::new().fg(colors.header_fg).bg(colors.header_bg));
+
+        let report = self.executor.report();
+        let next = self.executor.plan().map(|plan| plan.stage);
+        let rows = self.executor.pipeline().stages().map(|stage| {
+            let is_next = next.as_deref() == Some(stage.name.as_str());
+            let status = match report.status_of(&stage.name) {
+                Some(status) => status.to_string(),
+                None if is_next && self.is_running() => "running".into(),
+                None if is_next => "paused".into(),
+                None => "pending".into(),
+            };
+            let (program, args) = stage.command_line();
+            let marker = if is_next { "▶" } else { "" };
+            let row = Row::new([
+                Cell::from(marker),
+                Cell::from(stage.name.clone()),
+                Cell::from(format!("{program} {}", args.join(" "))),
+                Cell::from(status),
+            ]);
+            if is_next {
+                row.style(Style::new().add_modifier(Modifier::BOLD))
+            } else {
+                row
+            }
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Length(16),
+                Constraint::Min(20),
+                Constraint::Length(20),
+            ],
+        )
+        .header(header)
+        .style(Style::new().fg(colors.row_fg).bg(colors.buffer_bg));
+        frame.render_widget(table, area);
+    }
+
+    /// What happens next: the paused stage with its command line and inputs
+    fn render_next(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let lines = match self.executor.plan() {
+            None => {
+                let report = self.executor.report();
+                let outcome = if report.succeeded() {
+                    "succeeded".to_string()
+                } else {
+                    format!("did not succeed: {}", report.failed().join(", "))
+                };
+                vec![Line::from(format!(
+                    "Pipeline `{}` {outcome}.",
+                    self.executor.pipeline().name()
+                ))]
+            }
+            Some(plan) => {
+                let state = if self.is_running() {
+                    format!("Running `{}`…", plan.stage)
+                } else if plan.runnable {
+                    format!("Paused before `{}`", plan.stage)
+                } else {
+                    format!(
+                        "Paused before `{}` (its condition does not hold)",
+                        plan.stage
+                    )
+                };
+                let command = match &self.editing {
+                    Some(buffer) => format!("Parameters: {buffer}█  (enter apply, esc cancel)"),
+                    None => format!("Command: {}", plan.command_line()),
+                };
+                let inputs = plan
+                    .inputs
+                    .iter()
+                    .map(|input| {
+                        let mark = if input.exists() { "✓" } else { "✗" };
+                        format!("{} {mark}", input.display())
+                    })
+                    .collect::<Vec<_>>();
+                let inputs = if inputs.is_empty() {
+                    "Inputs: none".to_string()
+                } else {
+                    format!("Inputs: {}", inputs.join(", "))
+                };
+                vec![
+                    Line::from(state).bold(),
+                    Line::from(command),
+                    Line::from(inputs),
+                ]
+            }
+        };
+
+        let next = Paragraph::new(lines)
+            .style(Style::new().fg(colors.row_fg).bg(colors.buffer_bg))
+            .block(Block::default().borders(Borders::TOP))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(next, area);
+    }
+
+    fn render_output(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let visible = area.height.saturating_sub(1) as usize;
+        let start = self.output.len().saturating_sub(visible);
+        let output = Paragraph::new(
+            self.output[start..]
+                .iter()
+                .map(|line| Line::from(line.as_str()))
+                .collect::<Vec<_>>(),
+        )
+        .style(Style::new().fg(colors.row_fg).bg(colors.buffer_bg))
+        .block(Block::default().borders(Borders::TOP).title("Output"));
+        frame.render_widget(output, area);
+    }
+}
@@ -0,0 +1,132 @@
+//! Datasets under the data root, as catalogued by the `dock` tool (FR-DOCK-01)
+//!
+//! riptide does not read datasets itself: it runs the registered `dock` entry with
+//! `ls --json` and deserializes what it prints. The data root is `$DOCK_DATA_DIR`, or the
+//! directory riptide was started in.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::registry::ToolRegistry;
+
+/// Name of the tool that catalogs datasets
+pub const CATALOG_TOOL: &str = "dock";
+
+/// A column of a table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    pub dtype: String,
+}
+
+/// One file under the data root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataEntry {
+    /// Relative to the data root
+    pub path: PathBuf,
+    /// `table`, `media`, `graph` or `other`
+    pub category: String,
+    pub kind: String,
+    pub bytes: u64,
+    pub modified: Option<DateTime<Utc>>,
+    /// Shape and schema, for tables dock can read
+    pub rows: Option<usize>,
+    pub columns: Option<usize>,
+    pub schema: Option<Vec<Field>>,
+    /// Why a table could not be profiled
+    pub error: Option<String>,
+}
+
+/// What `dock ls --json` prints
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataCatalog {
+    pub root: PathBuf,
+    /// Sorted by path
+    pub entries: Vec<DataEntry>,
+}
+
+/// Errors
+#[derive(Debug, Error)]
+pub enum CatalogError {
+    #[error("The {CATALOG_TOOL} tool is not installed; install it with `riptide install`")]
+    NotInstalled,
+
+    #[error("Cannot run {0}: {1}")]
+    Spawn(PathBuf, io::Error),
+
+    #[error("{CATALOG_TOOL} ls failed: {0}")]
+    Failed(String),
+
+    #[error("Unexpected output of {CATALOG_TOOL} ls: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+impl DataCatalog {
+    /// Catalog `root`, or dock's default data root, with the `dock` binary at `entry`
+    pub fn load(entry: &Path, root: Option<&Path>) -> Result<Self, CatalogError> {
+        let mut cmd = Command::new(entry);
+        cmd.args(["ls", "--json"]);
+        if let Some(root) = root {
+            cmd.arg(root);
+        }
+        let output = cmd
+            .output()
+            .map_err(|e| CatalogError::Spawn(entry.to_path_buf(), e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(CatalogError::Failed(stderr.trim().to_string()));
+        }
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
+}
+
+/// The entry binary of the registered `dock` tool
+pub fn catalog_tool(registry: &ToolRegistry) -> Option<PathBuf> {
+    registry.get(CATALOG_TOOL)?.entry_path()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_catalog_is_read_from_dock() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dock = dir.path().join("dock");
+        let json = r#"{"root":"data","entries":[{"path":"raw/churn.csv","category":"table",
+            "kind":"csv","bytes":21,"modified":"2026-01-02T03:04:05Z","rows":2,"columns":2,
+            "schema":[{"name":"age","dtype":"i64"},{"name":"gender","dtype":"str"}],
+            "error":null}]}"#;
+        let script = format!(
+            "#!/bin/sh\n[ \"$1 $2 $3\" = \"ls --json data\" ] || {{ echo \"bad args: $*\" >&2; exit 2; }}\ncat <<'EOF'\n{json}\nEOF\n"
+        );
+        fs::write(&dock, script).unwrap();
+        fs::set_permissions(&dock, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let catalog = DataCatalog::load(&dock, Some(Path::new("data"))).unwrap();
+        assert_eq!(catalog.root, Path::new("data"));
+        let churn = &catalog.entries[0];
+        assert_eq!(churn.category, "table");
+        assert_eq!((churn.rows, churn.columns), (Some(2), Some(2)));
+        assert_eq!(churn.schema.as_ref().unwrap()[1].dtype, "str");
+
+        let error = DataCatalog::load(&dock, None).unwrap_err();
+        assert!(
+            matches!(&error, CatalogError::Failed(stderr) if stderr == "bad args: ls --json"),
+            "{error}"
+        );
+        assert!(matches!(
+            DataCatalog::load(&dir.path().join("missing"), None),
+            Err(CatalogError::Spawn(..))
+        ));
+    }
+}
@@ -0,0 +1,11 @@
+use crate::actors::User;
+use crate::actors::cli::{CliError, GraphFormat};
+
+/// `riptide graph`: print the User state machine for the docs
+pub fn run(format: GraphFormat) -> Result<(), CliError> {
+    match format {
+        GraphFormat::Mermaid => print!("{}", User::to_mermaid()),
+        GraphFormat::Dot => print!("{}", User::to_dot()),
+    }
+    Ok(())
+}
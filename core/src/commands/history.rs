@@ -0,0 +1,66 @@
+use crate::actors::cli::{CliError, HistoryCommand};
+use crate::commands::run;
+use crate::history::{RunDiff, RunHistory, RunSummary};
+use crate::workflow::RunContext;
+
+/// Headline metrics shown per run by `riptide history`
+const HEADLINE_METRICS: usize = 3;
+
+/// `riptide history`: list, show, compare or rerun recorded pipeline runs
+pub fn run(command: &HistoryCommand) -> Result<(), CliError> {
+    let history = RunHistory::default();
+    match command {
+        HistoryCommand::List => list(&history),
+        HistoryCommand::Show { run } => {
+            print!("{}", get(&history, run)?);
+            Ok(())
+        }
+        HistoryCommand::Diff { before, after } => {
+            let diff = RunDiff::between(&get(&history, before)?, &get(&history, after)?);
+            print!("{diff}");
+            Ok(())
+        }
+        HistoryCommand::Rerun { run } => rerun(&get(&history, run)?),
+    }
+}
+
+fn list(history: &RunHistory) -> Result<(), CliError> {
+    let runs = history.list()?;
+    if runs.is_empty() {
+        println!("No runs recorded yet. Run a pipeline with `riptide run <pipeline.yaml>`.");
+        return Ok(());
+    }
+
+    println!(
+        "{:<14} {:<16} {:<10} {:<20} {:>9}  METRICS",
+        "RUN", "PIPELINE", "STATUS", "STARTED", "DURATION"
+    );
+    for summary in runs {
+        let run = &summary.run;
+        println!(
+            "{:<14} {:<16} {:<10} {:<20} {:>9}  {}",
+            run.run_id,
+            run.pipeline,
+            run.status,
+            run.started.format("%Y-%m-%d %H:%M:%S"),
+            summary.duration(),
+            summary.headline(HEADLINE_METRICS)
+        );
+    }
+    Ok(())
+}
+
+fn get(history: &RunHistory, run: &str) -> Result<RunSummary, CliError> {
+    history
+        .get(run)?
+        .ok_or_else(|| CliError::UnknownRun(run.to_string()))
+}
+
+/// Execute the pipeline of a past run again, as a new run
+fn rerun(summary: &RunSummary) -> Result<(), CliError> {
+    let pipeline = summary
+        .pipeline()?
+        .ok_or_else(|| CliError::NotRerunnable(summary.run_id().to_string()))?;
+    println!("Rerunning run {}", summary.run_id());
+    run::execute_pipeline(pipeline, RunContext::recorded())
+}
@@ -0,0 +1,36 @@
+use crate::actors::cli::CliError;
+use crate::installer::{InstallOptions, Installer, Upgrade};
+
+/// `riptide install <source>`: build or copy a tool into the managed prefix
+pub fn run(source: &str, sha256: Option<String>, force: bool) -> Result<(), CliError> {
+    let installer = Installer::default();
+    let receipt = installer.install(source, &InstallOptions { sha256, force })?;
+    println!(
+        "Installed {} {} into {} (sha256 {})",
+        receipt.name,
+        receipt.version,
+        installer.prefix().join(&receipt.name).display(),
+        receipt.sha256
+    );
+    Ok(())
+}
+
+/// `riptide uninstall <name>`
+pub fn uninstall(name: &str) -> Result<(), CliError> {
+    let receipt = Installer::default().uninstall(name)?;
+    println!("Uninstalled {} {}", receipt.name, receipt.version);
+    Ok(())
+}
+
+/// `riptide upgrade <name>`: reinstall from the recorded source
+pub fn upgrade(name: &str) -> Result<(), CliError> {
+    match Installer::default().upgrade(name)? {
+        Upgrade::UpToDate(receipt) => {
+            println!("{} {} is up to date", receipt.name, receipt.version)
+        }
+        Upgrade::Upgraded { from, to } => {
+            println!("Upgraded {} {} -> {}", to.name, from.version, to.version)
+        }
+    }
+    Ok(())
+}
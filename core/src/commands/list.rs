@@ -0,0 +1,35 @@
+use crate::actors::cli::CliError;
+use crate::registry::ToolRegistry;
+
+/// `riptide list`: print each discovered tool and whether it is installed
+pub fn run() -> Result<(), CliError> {
+    let registry = ToolRegistry::load();
+    for error in registry.errors() {
+        eprintln!("riptide: skipping tool: {error}");
+    }
+    if registry.tools().is_empty() {
+        println!(
+            "No tools found. Add a directory with a riptide-tool.toml to $RIPTIDE_TOOL_PATH \
+             or install one with `riptide install`."
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{:<8} {:<10} {:<44} STATUS",
+        "NAME", "VERSION", "DESCRIPTION"
+    );
+    for tool in registry.tools() {
+        let status = match tool.entry_path() {
+            Some(path) => format!("installed ({})", path.display()),
+            None => "not installed".to_string(),
+        };
+        println!(
+            "{:<8} {:<10} {:<44} {status}",
+            tool.name(),
+            tool.manifest.version,
+            tool.manifest.description
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::actors::cli::CliError;
+use crate::logging::{LogFilter, LogStore, LogTail};
+
+/// How often `--follow` checks for new records
+const POLL: Duration = Duration::from_millis(250);
+
+/// `riptide logs`: print recorded logs in plain mode, optionally following new records
+pub fn run(filter: &LogFilter, follow: bool) -> Result<(), CliError> {
+    let store = LogStore::default();
+    let mut tail = store.tail(filter.clone());
+    let read = |tail: &mut LogTail| {
+        tail.poll()
+            .map_err(|e| CliError::Logs(store.dir().to_path_buf(), e))
+    };
+
+    let records = read(&mut tail)?;
+    if records.is_empty() && !follow {
+        if *filter == LogFilter::default() {
+            println!("No logs recorded yet.");
+        } else {
+            println!("No records match the filters.");
+        }
+    }
+    for record in records {
+        println!("{record}");
+    }
+
+    if !follow {
+        return Ok(());
+    }
+    loop {
+        thread::sleep(POLL);
+        for record in read(&mut tail)? {
+            println!("{record}");
+        }
+    }
+}
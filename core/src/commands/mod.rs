@@ -0,0 +1,41 @@
+//! Implementations of the `riptide` subcommands driven by the `CLI` actor
+
+pub mod graph;
+pub mod history;
+pub mod install;
+pub mod list;
+pub mod logs;
+pub mod run;
+
+use std::env;
+use std::path::PathBuf;
+
+use crate::actors::cli::{CliCommand, CliError};
+
+/// Execute a non-interactive subcommand to completion
+pub fn execute(command: &CliCommand) -> Result<(), CliError> {
+    match command {
+        // The TUI is opened by the caller once the CLI reaches LaunchingTUI
+        CliCommand::Launch { .. } => Ok(()),
+        CliCommand::List => list::run(),
+        CliCommand::Install {
+            source,
+            sha256,
+            force,
+        } => install::run(source, sha256.clone(), *force),
+        CliCommand::Uninstall { name } => install::uninstall(name),
+        CliCommand::Upgrade { name } => install::upgrade(name),
+        CliCommand::Run { module, args } => run::run(module, args),
+        CliCommand::Logs { filter, follow } => logs::run(filter, *follow),
+        CliCommand::Graph { format } => graph::run(*format),
+        CliCommand::History(command) => history::run(command),
+    }
+}
+
+/// Locate an executable by name on `PATH`
+pub fn find_on_path(name: &str) -> Option<PathBuf> {
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
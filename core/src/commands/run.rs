@@ -0,0 +1,496 @@
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
+
+use crate::actors::cli::CliError;
+use crate::logging::{Level, LogRecord};
+use crate::registry::ToolRegistry;
+use crate::util::process::{self, Stream};
+use crate::workflow::{Executor, Pipeline, RunContext, WorkflowError};
+
+/// What the Ctrl-C handler passes an interrupt on to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Foreground {
+    /// No tool is running, so riptide itself stops with exit code 130
+    Riptide,
+    /// A tool is being started; it is interrupted once its process id is known
+    Spawning,
+    /// An interactive tool, which gets Ctrl-C straight from the terminal
+    Terminal,
+    /// A tool running in its own process group, with this process id
+    Child(u32),
+}
+
+static FOREGROUND: Mutex<Foreground> = Mutex::new(Foreground::Riptide);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+fn set_foreground(foreground: Foreground) {
+    *FOREGROUND.lock().unwrap_or_else(|e| e.into_inner()) = foreground;
+}
+
+/// `riptide run <tool> -- <args>`: run a registered tool in the foreground, or execute a
+/// pipeline when given a `.yaml`/`.yml` file
+pub fn run(module: &str, args: &[String]) -> Result<(), CliError> {
+    if is_pipeline(module) {
+        return run_pipeline(Path::new(module));
+    }
+
+    forward_interrupts();
+    let context = RunContext::recorded();
+    run_tool(
+        &ToolRegistry::load(),
+        &context,
+        module,
+        args,
+        |stream, line| match stream {
+            Stream::Stdout => println!("{line}"),
+            Stream::Stderr => eprintln!("{line}"),
+        },
+    )
+}
+
+/// Run `name` from `registry`, handing its output to `on_line` prefixed with `[name]` and
+/// logging it as a stage of the run `context`
+fn run_tool(
+    registry: &ToolRegistry,
+    context: &RunContext,
+    name: &str,
+    args: &[String],
+    mut on_line: impl FnMut(Stream, String),
+) -> Result<(), CliError> {
+    let result = spawn_tool(registry, context, name, args, &mut on_line);
+    let record = match &result {
+        Ok(()) => LogRecord::new(&context.run_id, Some(name), Level::Info, "tool succeeded"),
+        Err(e) => LogRecord::new(&context.run_id, Some(name), Level::Error, e.to_string())
+            .with_field("exit_code", e.exit_code()),
+    };
+    context.log(record);
+    result
+}
+
+fn spawn_tool(
+    registry: &ToolRegistry,
+    context: &RunContext,
+    name: &str,
+    args: &[String],
+    on_line: &mut dyn FnMut(Stream, String),
+) -> Result<(), CliError> {
+    let tool = registry
+        .get(name)
+        .ok_or_else(|| CliError::UnknownTool(name.into()))?;
+    let entry = tool.entry_path().ok_or_else(|| CliError::MissingEntry {
+        tool: name.into(),
+        entry: tool.manifest.entry.clone(),
+    })?;
+
+    context.log(
+        LogRecord::new(&context.run_id, Some(name), Level::Info, "tool started")
+            .with_field("entry", entry.display().to_string())
+            .with_field("args", args.to_vec()),
+    );
+    let mut cmd = Command::new(&entry);
+    cmd.args(args).env("RIPTIDE_RUN_ID", &context.run_id);
+
+    set_foreground(if tool.manifest.interactive {
+        Foreground::Terminal
+    } else {
+        Foreground::Spawning
+    });
+    // An interrupt that came in while riptide got ready stops the tool before it starts
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        set_foreground(Foreground::Riptide);
+        return Err(CliError::Interrupted(name.into()));
+    }
+    let status = if tool.manifest.interactive {
+        // Left in riptide's process group with the terminal as its stdio, so it can read
+        // and draw; Ctrl-C reaches it straight from the terminal and is not forwarded
+        cmd.status()
+    } else {
+        // In its own process group Ctrl-C reaches the tool once, forwarded by riptide. That
+        // group is in the background, where reading the terminal would stop the tool, so
+        // it gets no stdin unless riptide's own comes from a pipe or file.
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+        if io::stdin().is_terminal() {
+            cmd.stdin(Stdio::null());
+        }
+        process::run_streaming_with_pid(
+            &mut cmd,
+            |pid| {
+                let mut foreground = FOREGROUND.lock().unwrap_or_else(|e| e.into_inner());
+                *foreground = Foreground::Child(pid);
+                if INTERRUPTED.load(Ordering::SeqCst) {
+                    let _ = process::interrupt(pid);
+                }
+            },
+            |stream, line| {
+                context.log_line(name, None, stream, &line);
+                on_line(stream, format!("[{name}] {line}"))
+            },
+        )
+    };
+    set_foreground(Foreground::Riptide);
+
+    let status = status.map_err(|e| CliError::Spawn {
+        command: entry.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        return Err(CliError::Interrupted(name.into()));
+    }
+    match status.code() {
+        Some(0) => Ok(()),
+        code => Err(CliError::CommandFailed {
+            command: name.into(),
+            code: code.unwrap_or(-1),
+        }),
+    }
+}
+
+/// Pass Ctrl-C and termination signals on to the running tool instead of exiting, or exit
+/// with code 130 when no tool is running
+fn forward_interrupts() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let _ = ctrlc::set_handler(|| {
+            // Held while deciding, so the tool cannot start or finish in between
+            let foreground = FOREGROUND.lock().unwrap_or_else(|e| e.into_inner());
+            INTERRUPTED.store(true, Ordering::SeqCst);
+            match *foreground {
+                Foreground::Riptide => std::process::exit(130),
+                Foreground::Child(pid) => {
+                    let _ = process::interrupt(pid);
+                }
+                Foreground::Spawning | Foreground::Terminal => {}
+            }
+        });
+    });
+}
+
+fn is_pipeline(target: &str) -> bool {
+    matches!(
+        Path::new(target).extension().and_then(|e| e.to_str()),
+        Some("yaml" | "yml")
+    )
+}
+
+/// Validate and execute every stage of a pipeline, then print per-stage results
+pub fn run_pipeline(path: &Path) -> Result<(), CliError> {
+    run_pipeline_with(path, RunContext::recorded())
+}
+
+fn run_pipeline_with(path: &Path, context: RunContext) -> Result<(), CliError> {
+    execute_pipeline(Pipeline::load(path)?, context)
+}
+
+/// Execute a validated pipeline under `context`, printing per-stage results
+pub fn execute_pipeline(pipeline: Pipeline, context: RunContext) -> Result<(), CliError> {
+    let name = pipeline.name().to_string();
+    context
+        .create_dirs()
+        .map_err(|e| WorkflowError::ArtifactDir(context.artifact_dir.clone(), e))?;
+    println!("Run {} of `{name}`", context.run_id);
+
+    let mut executor = Executor::new(pipeline).with_context(context);
+    let report = executor.run();
+    print!("{report}");
+
+    if report.succeeded() {
+        Ok(())
+    } else {
+        Err(CliError::PipelineFailed {
+            pipeline: name,
+            failed: report.failed(),
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::logging::{LogFilter, LogStore};
+    use crate::metrics::MetricStore;
+    use std::fs;
+
+    fn write_tool(dir: &Path, script: &str) -> ToolRegistry {
+        write_tool_with(dir, script, false)
+    }
+
+    fn write_tool_with(dir: &Path, script: &str, interactive: bool) -> ToolRegistry {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::write(
+            dir.join(crate::registry::MANIFEST),
+            format!(
+                "name = \"trawl\"\nversion = \"0.1.0\"\nentry = \"./trawl.sh\"\ninteractive = {interactive}\n"
+            ),
+        )
+        .unwrap();
+        let entry = dir.join("trawl.sh");
+        fs::write(&entry, format!("#!/bin/sh\n{script}\n")).unwrap();
+        fs::set_permissions(&entry, fs::Permissions::from_mode(0o755)).unwrap();
+        ToolRegistry::discover(&[dir.to_path_buf()])
+    }
+
+    #[test]
+    fn test_run_registered_tool() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = write_tool(
+            dir.path(),
+            "echo \"$1\"; echo oops >&2; echo '{\"step\": 1, \"name\": \"loss\", \"value\": 0.5}'",
+        );
+
+        let mut lines = Vec::new();
+        let context = RunContext {
+            run_id: "run-1".into(),
+            artifact_dir: dir.path().join("artifacts"),
+            log: Some(LogStore::new(dir.path().join("logs"))),
+            metrics: Some(MetricStore::new(dir.path().join("metrics"))),
+            history: None,
+        };
+        run_tool(
+            &registry,
+            &context,
+            "trawl",
+            &["net".into()],
+            |stream, line| lines.push((stream, line)),
+        )
+        .unwrap();
+        assert!(lines.contains(&(Stream::Stdout, "[trawl] net".to_string())));
+        assert!(lines.contains(&(Stream::Stderr, "[trawl] oops".to_string())));
+
+        // The metric went to the metric store instead of the log
+        let metrics = context.metrics.as_ref().unwrap().read("run-1").unwrap();
+        assert_eq!(metrics.get("loss").unwrap().points, vec![(1, 0.5)]);
+
+        let registry = write_tool(dir.path(), "exit 9");
+        let logged = context
+            .log
+            .as_ref()
+            .unwrap()
+            .read(&Default::default())
+            .unwrap();
+        let mut messages: Vec<&str> = logged.iter().map(|r| r.message.as_str()).collect();
+        // Stdout and stderr are read apart, so their lines may interleave either way
+        messages[1..3].sort_unstable();
+        assert_eq!(
+            messages,
+            vec!["tool started", "net", "oops", "tool succeeded"]
+        );
+        assert!(logged.iter().all(|r| r.stage.as_deref() == Some("trawl")));
+
+        let error = run_tool(&registry, &context, "trawl", &[], |_, _| {}).unwrap_err();
+        assert!(matches!(error, CliError::CommandFailed { code: 9, .. }));
+        assert_eq!(error.exit_code(), 5);
+
+        let error = run_tool(&registry, &context, "seine", &[], |_, _| {}).unwrap_err();
+        assert!(matches!(error, CliError::UnknownTool(_)));
+        assert_eq!(error.exit_code(), 3);
+    }
+
+    /// Set for the copy of this test binary that `test_tools_reading_the_terminal` runs
+    const PTY_HELPER: &str = "RIPTIDE_TEST_PTY_HELPER";
+
+    /// Run this binary's `test` as the session leader of a new pseudo-terminal with `input`
+    /// typed into it, returning its exit status and everything it printed
+    fn run_in_pty(test: &str, input: &[u8]) -> (std::process::ExitStatus, String) {
+        use std::io::{Read, Write};
+        use std::os::fd::{FromRawFd, OwnedFd};
+        use std::os::unix::process::CommandExt;
+        use std::time::{Duration, Instant};
+
+        let (mut master, slave) = {
+            let (mut master, mut slave) = (0, 0);
+            // SAFETY: openpty only writes the two descriptors it opens, which we then own
+            unsafe {
+                let opened = libc::openpty(
+                    &mut master,
+                    &mut slave,
+                    std::ptr::null_mut(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                );
+                assert_eq!(opened, 0, "{}", io::Error::last_os_error());
+                for fd in [master, slave] {
+                    libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+                }
+                (fs::File::from_raw_fd(master), OwnedFd::from_raw_fd(slave))
+            }
+        };
+
+        let mut cmd = Command::new(std::env::current_exe().unwrap());
+        cmd.args([test, "--exact", "--nocapture"])
+            .env(PTY_HELPER, "1")
+            .stdin(slave.try_clone().unwrap())
+            .stdout(slave.try_clone().unwrap())
+            .stderr(slave.try_clone().unwrap());
+        // SAFETY: setsid and ioctl are async-signal-safe
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY, 0) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let mut child = cmd.spawn().unwrap();
+        // The command holds copies of the slave too
+        drop((cmd, slave));
+        master.write_all(input).unwrap();
+
+        // Reading the master fails once the child and so every slave descriptor is gone
+        let mut reader = master.try_clone().unwrap();
+        let output = std::thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = reader.read_to_end(&mut output);
+            String::from_utf8_lossy(&output).into_owned()
+        });
+        let deadline = Instant::now() + Duration::from_secs(20);
+        let status = loop {
+            if let Some(status) = child.try_wait().unwrap() {
+                break status;
+            }
+            if Instant::now() > deadline {
+                let _ = child.kill();
+                panic!("{test} hung in the pseudo-terminal");
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        drop(master);
+        (status, output.join().unwrap())
+    }
+
+    /// Runs only inside the pseudo-terminal of `test_tools_reading_the_terminal`
+    #[test]
+    fn pty_helper() {
+        if std::env::var_os(PTY_HELPER).is_none() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let context = RunContext {
+            run_id: "run-1".into(),
+            artifact_dir: dir.path().join("artifacts"),
+            log: None,
+            metrics: None,
+            history: None,
+        };
+        let print = |_: Stream, line: String| println!("{line}");
+
+        let registry = write_tool_with(
+            dir.path(),
+            "echo asking; read answer; echo \"got $answer\"",
+            true,
+        );
+        run_tool(&registry, &context, "trawl", &[], print).unwrap();
+
+        let registry = write_tool(dir.path(), "read answer || echo 'no input'");
+        run_tool(&registry, &context, "trawl", &[], print).unwrap();
+    }
+
+    #[test]
+    fn test_tools_reading_the_terminal() {
+        let (status, output) = run_in_pty("commands::run::tests::pty_helper", b"net\n");
+
+        assert!(status.success(), "{output}");
+        // The interactive tool read what was typed, its output unprefixed
+        assert!(output.contains("\ngot net"), "{output}");
+        // The other one ran in the background and saw no input instead of stopping
+        assert!(output.contains("[trawl] no input"), "{output}");
+    }
+
+    /// Set for the copy of this test binary that `test_interrupts` runs
+    const INTERRUPT_HELPER: &str = "RIPTIDE_TEST_INTERRUPT_HELPER";
+
+    /// Runs only in the process `test_interrupts` starts, which it expects to exit with 130
+    #[test]
+    fn interrupt_helper() {
+        if std::env::var_os(INTERRUPT_HELPER).is_none() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let context = RunContext {
+            run_id: "run-1".into(),
+            artifact_dir: dir.path().join("artifacts"),
+            log: None,
+            metrics: None,
+            history: None,
+        };
+        forward_interrupts();
+
+        // Ctrl-C while the tool runs goes to the tool, and riptide carries on
+        let registry = write_tool(dir.path(), "kill -INT $PPID; sleep 5");
+        let error = run_tool(&registry, &context, "trawl", &[], |_, _| {}).unwrap_err();
+        assert!(matches!(error, CliError::Interrupted(_)));
+        println!("tool interrupted");
+
+        // With nothing running it stops riptide
+        // SAFETY: raise has no memory-safety preconditions
+        unsafe { libc::raise(libc::SIGINT) };
+        std::thread::sleep(std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_interrupts() {
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args([
+                "commands::run::tests::interrupt_helper",
+                "--exact",
+                "--nocapture",
+            ])
+            .env(INTERRUPT_HELPER, "1")
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        assert!(stdout.contains("tool interrupted"), "{stdout}");
+        assert_eq!(output.status.code(), Some(130), "{stdout}");
+    }
+
+    #[test]
+    fn test_run_pipeline_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pipeline.yaml");
+        fs::write(
+            &path,
+            "name: smoke\nstages:\n  - name: make\n    run: echo hi > out.txt\n    outputs: [out.txt]\n  - name: check\n    run: test -f out.txt\n    needs: [make]\n    inputs: [out.txt]\n",
+        )
+        .unwrap();
+
+        let context = || RunContext {
+            run_id: "test".into(),
+            artifact_dir: dir.path().join("artifacts"),
+            log: Some(LogStore::new(dir.path().join("logs"))),
+            metrics: None,
+            history: None,
+        };
+
+        assert!(is_pipeline(path.to_str().unwrap()));
+        run_pipeline_with(&path, context()).unwrap();
+        assert!(dir.path().join("out.txt").exists());
+        assert!(dir.path().join("artifacts").is_dir());
+        let logs = LogStore::new(dir.path().join("logs"));
+        let filter = LogFilter {
+            stage: Some("check".into()),
+            ..LogFilter::default()
+        };
+        let logged: Vec<String> = logs
+            .read(&filter)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.message)
+            .collect();
+        assert_eq!(logged, vec!["stage started", "stage succeeded"]);
+
+        fs::write(
+            &path,
+            "name: broken\nstages:\n  - name: fail\n    run: exit 1\n",
+        )
+        .unwrap();
+        match run_pipeline_with(&path, context()) {
+            Err(CliError::PipelineFailed { failed, .. }) => assert_eq!(failed, vec!["fail"]),
+            other => panic!("expected the pipeline to fail, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Locations of riptide's on-disk state
+
+use std::env;
+use std::path::PathBuf;
+
+/// Root directory for riptide state: `$RIPTIDE_HOME`, falling back to `~/.riptide`
+pub fn riptide_home() -> PathBuf {
+    if let Some(home) = env::var_os("RIPTIDE_HOME") {
+        return PathBuf::from(home);
+    }
+    match env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(".riptide"),
+        None => PathBuf::from(".riptide"),
+    }
+}
+
+/// Where the dashboard pane layout is kept between sessions
+pub fn layout_path() -> PathBuf {
+    riptide_home().join("layout.json")
+}
+
+/// Where the user's key bindings are read from
+pub fn keymap_path() -> PathBuf {
+    riptide_home().join("keymap.toml")
+}
+
+/// Per-run state such as artifacts, one directory per run id
+pub fn runs_dir() -> PathBuf {
+    riptide_home().join("runs")
+}
+
+/// Where `riptide install` puts tools, one directory per tool
+pub fn tools_dir() -> PathBuf {
+    riptide_home().join("tools")
+}
+
+/// Structured logs of runs, one JSON-lines file per run id
+pub fn logs_dir() -> PathBuf {
+    riptide_home().join("logs")
+}
+
+/// Metric points reported by runs, one JSON-lines file per run id
+pub fn metrics_dir() -> PathBuf {
+    riptide_home().join("metrics")
+}
+
+/// Where runs, stages, datasets, models and metrics are recorded: `$RIPTIDE_DB`, falling back
+/// to an embedded database under the riptide home
+pub fn db_endpoint() -> String {
+    match env::var("RIPTIDE_DB") {
+        Ok(endpoint) if !endpoint.is_empty() => endpoint,
+        _ => format!(
+            "surrealkv://{}",
+            riptide_home().join("riptide.db").display()
+        ),
+    }
+}
+
+/// Root credentials for a database server, from `$RIPTIDE_DB_USER` and `$RIPTIDE_DB_PASS`
+pub fn db_credentials() -> Option<(String, String)> {
+    Some((
+        env::var("RIPTIDE_DB_USER").ok()?,
+        env::var("RIPTIDE_DB_PASS").ok()?,
+    ))
+}
@@ -0,0 +1,35 @@
+//! Schema changes, applied in order when a database is opened
+//!
+//! A migration is never edited once released; change the schema by appending a new one.
+
+/// One step of the schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    /// SurrealQL run inside a transaction
+    pub sql: &'static str,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        sql: include_str!("migrations/0001_init.surql"),
+    },
+    Migration {
+        version: 2,
+        name: "history",
+        sql: include_str!("migrations/0002_history.surql"),
+    },
+    Migration {
+        version: 3,
+        name: "ordering",
+        sql: include_str!("migrations/0003_ordering.surql"),
+    },
+];
+
+/// Version of the newest schema this build knows
+pub fn latest() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
@@ -0,0 +1,540 @@
+//! Persistent record of runs, stages, datasets, models and metrics
+//!
+//! Backed by SurrealDB through its `any` engine, so the endpoint picks the storage:
+//!
+//! - `surrealkv://<path>`: embedded, persisted to disk; the default is
+//!   `~/.riptide/riptide.db`, which one riptide process can hold at a time
+//! - `mem://`: embedded and in memory, used by tests
+//! - `ws://<host>:<port>`: a SurrealDB server such as the one in `docker-compose.yml`,
+//!   when riptide is built with the `remote` feature
+//!
+//! Opening a database brings its schema up to date by applying pending
+//! [migrations](migrations::MIGRATIONS).
+
+pub mod migrations;
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use surrealdb::Surreal;
+use surrealdb::engine::any::{self, Any};
+use surrealdb::opt::auth::Root;
+use surrealdb::types::SurrealValue;
+use thiserror::Error;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::config;
+use crate::metrics::{MetricPoint, RunMetrics};
+use migrations::{MIGRATIONS, Migration};
+
+const NAMESPACE: &str = "riptide";
+const DATABASE: &str = "riptide";
+
+/// How long closing a database may wait for the engine to shut down
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A pipeline run
+#[derive(Debug, Clone, PartialEq, SurrealValue)]
+pub struct RunRecord {
+    pub run_id: String,
+    /// Name of the pipeline; `riptide run <tool>` is not recorded
+    pub pipeline: String,
+    /// `running`, `succeeded`, `failed` or `aborted`
+    pub status: String,
+    pub started: DateTime<Utc>,
+    pub finished: Option<DateTime<Utc>>,
+    pub duration_secs: Option<f64>,
+    /// Directory the stages ran in
+    pub base_dir: Option<String>,
+    /// The pipeline as executed, in YAML, including parameters edited during the run
+    pub config: Option<String>,
+}
+
+/// How one stage of a run ended
+#[derive(Debug, Clone, PartialEq, SurrealValue)]
+pub struct StageRecord {
+    pub run_id: String,
+    pub stage: String,
+    /// `succeeded`, `failed`, `error`, `skipped` or `aborted`
+    pub status: String,
+    pub exit_code: Option<i64>,
+    pub duration_secs: f64,
+    pub finished: DateTime<Utc>,
+    /// Tool arguments or script the stage ran with
+    pub params: Option<String>,
+    /// Position in the run's execution order
+    pub position: Option<i64>,
+}
+
+/// A file a stage declared as output, as it was when the run ended
+#[derive(Debug, Clone, PartialEq, SurrealValue)]
+pub struct OutputRecord {
+    pub run_id: String,
+    pub stage: String,
+    /// As written in the pipeline, relative to the run's `base_dir`
+    pub path: String,
+    pub sha256: String,
+    pub bytes: u64,
+}
+
+/// A versioned dataset or model file; which of the two depends on the table it is kept in
+#[derive(Debug, Clone, PartialEq, SurrealValue)]
+pub struct ArtifactRecord {
+    pub name: String,
+    pub version: String,
+    pub path: String,
+    /// Run that produced it, `None` when registered by hand
+    pub run_id: Option<String>,
+    pub created: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum DbError {
+    #[error("Cannot start the database runtime: {0}")]
+    Runtime(std::io::Error),
+
+    #[error("Cannot open database {endpoint}: {source}")]
+    Open {
+        endpoint: String,
+        source: Box<surrealdb::Error>,
+    },
+
+    #[error("Database schema version {found} is newer than this riptide supports ({supported})")]
+    NewerSchema { found: u32, supported: u32 },
+
+    #[error("Migration {version} `{name}` failed: {source}")]
+    Migration {
+        version: u32,
+        name: &'static str,
+        source: Box<surrealdb::Error>,
+    },
+
+    #[error("Database query failed: {0}")]
+    Query(Box<surrealdb::Error>),
+}
+
+impl From<surrealdb::Error> for DbError {
+    fn from(e: surrealdb::Error) -> Self {
+        DbError::Query(Box::new(e))
+    }
+}
+
+/// Connection to the riptide database.
+///
+/// riptide is synchronous, so each database drives its SurrealDB client on a runtime of
+/// its own and every call blocks until the query completes.
+pub struct Database {
+    runtime: Runtime,
+    db: Surreal<Any>,
+    endpoint: String,
+}
+
+impl Database {
+    /// Connect to `endpoint` and migrate its schema to the latest version
+    pub fn open(endpoint: &str) -> Result<Self, DbError> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(DbError::Runtime)?;
+        let open_error = |source| DbError::Open {
+            endpoint: endpoint.to_string(),
+            source: Box::new(source),
+        };
+        let db = runtime.block_on(async {
+            let db = any::connect(endpoint).await?;
+            if let Some((username, password)) = config::db_credentials() {
+                db.signin(Root { username, password }).await?;
+            }
+            db.use_ns(NAMESPACE).use_db(DATABASE).await?;
+            Ok(db)
+        });
+        let db = Self {
+            runtime,
+            db: db.map_err(open_error)?,
+            endpoint: endpoint.to_string(),
+        };
+        db.migrate()?;
+        Ok(db)
+    }
+
+    /// The database at `$RIPTIDE_DB`, falling back to the embedded one under `~/.riptide`
+    pub fn open_default() -> Result<Self, DbError> {
+        Self::open(&config::db_endpoint())
+    }
+
+    /// A fresh in-memory database
+    pub fn memory() -> Result<Self, DbError> {
+        Self::open("mem://")
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Version of the schema, 0 before the first migration
+    pub fn schema_version(&self) -> Result<u32, DbError> {
+        let versions: Vec<u32> = self.runtime.block_on(async {
+            self.db
+                .query("DEFINE TABLE IF NOT EXISTS migration SCHEMALESS")
+                .query("SELECT VALUE version FROM migration")
+                .await?
+                .check()?
+                .take(1)
+        })?;
+        Ok(versions.into_iter().max().unwrap_or(0))
+    }
+
+    /// Apply every migration newer than the schema, each in its own transaction
+    fn migrate(&self) -> Result<(), DbError> {
+        let current = self.schema_version()?;
+        if current > migrations::latest() {
+            return Err(DbError::NewerSchema {
+                found: current,
+                supported: migrations::latest(),
+            });
+        }
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+            self.apply(migration).map_err(|source| DbError::Migration {
+                version: migration.version,
+                name: migration.name,
+                source: Box::new(source),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, migration: &Migration) -> Result<(), surrealdb::Error> {
+        let sql = format!(
+            "BEGIN TRANSACTION;\n{}\nCREATE type::record('migration', $version) CONTENT {{ version: $version, name: $name, applied: time::now() }};\nCOMMIT TRANSACTION;",
+            migration.sql
+        );
+        self.runtime.block_on(async {
+            self.db
+                .query(sql)
+                .bind(("version", migration.version))
+                .bind(("name", migration.name))
+                .await?
+                .check()?;
+            Ok(())
+        })
+    }
+
+    /// Insert or update a run
+    pub fn record_run(&self, run: &RunRecord) -> Result<(), DbError> {
+        self.execute(
+            "UPSERT type::record('run', $record.run_id) CONTENT $record",
+            run.clone(),
+        )
+    }
+
+    pub fn run(&self, run_id: &str) -> Result<Option<RunRecord>, DbError> {
+        Ok(self
+            .runtime
+            .block_on(self.db.select(("run", run_id)).into_future())?)
+    }
+
+    /// Every run, newest first
+    pub fn runs(&self) -> Result<Vec<RunRecord>, DbError> {
+        self.fetch("SELECT * OMIT id FROM run ORDER BY started DESC", None)
+    }
+
+    /// Insert or update the outcome of a stage; a retried stage keeps its last outcome
+    pub fn record_stage(&self, stage: &StageRecord) -> Result<(), DbError> {
+        self.execute(
+            "UPSERT type::record('stage', [$record.run_id, $record.stage]) CONTENT $record",
+            stage.clone(),
+        )
+    }
+
+    /// Stages of a run in the order they ran
+    pub fn stages(&self, run_id: &str) -> Result<Vec<StageRecord>, DbError> {
+        self.fetch(
+            "SELECT * OMIT id FROM stage WHERE run_id = $value ORDER BY position, finished",
+            Some(run_id.to_string()),
+        )
+    }
+
+    pub fn record_dataset(&self, dataset: &ArtifactRecord) -> Result<(), DbError> {
+        self.record_artifact("dataset", dataset)
+    }
+
+    /// Every version of the datasets named `name`, or of all datasets, newest first
+    pub fn datasets(&self, name: Option<&str>) -> Result<Vec<ArtifactRecord>, DbError> {
+        self.artifacts("dataset", name)
+    }
+
+    pub fn record_model(&self, model: &ArtifactRecord) -> Result<(), DbError> {
+        self.record_artifact("model", model)
+    }
+
+    /// Every version of the models named `name`, or of all models, newest first
+    pub fn models(&self, name: Option<&str>) -> Result<Vec<ArtifactRecord>, DbError> {
+        self.artifacts("model", name)
+    }
+
+    fn record_artifact(&self, table: &str, artifact: &ArtifactRecord) -> Result<(), DbError> {
+        self.execute(
+            &format!(
+                "UPSERT type::record('{table}', [$record.name, $record.version]) CONTENT $record"
+            ),
+            artifact.clone(),
+        )
+    }
+
+    fn artifacts(&self, table: &str, name: Option<&str>) -> Result<Vec<ArtifactRecord>, DbError> {
+        let filter = if name.is_some() {
+            "WHERE name = $value"
+        } else {
+            ""
+        };
+        self.fetch(
+            &format!("SELECT * OMIT id FROM {table} {filter} ORDER BY created DESC"),
+            name.map(str::to_string),
+        )
+    }
+
+    /// Insert or update a point; a run has one value per metric and step
+    pub fn record_metric(&self, point: &MetricPoint) -> Result<(), DbError> {
+        self.record_metrics(std::slice::from_ref(point))
+    }
+
+    /// Insert or update many points in one statement
+    pub fn record_metrics(&self, points: &[MetricPoint]) -> Result<(), DbError> {
+        if points.is_empty() {
+            return Ok(());
+        }
+        self.execute(
+            "FOR $point IN $record {
+                UPSERT type::record('metric', [$point.run, $point.name, $point.step])
+                    CONTENT $point;
+            }",
+            points.to_vec(),
+        )
+    }
+
+    /// Every metric a run reported
+    pub fn metrics(&self, run_id: &str) -> Result<RunMetrics, DbError> {
+        let points: Vec<MetricPoint> = self.fetch(
+            "SELECT run, step, name, value FROM metric WHERE run = $value ORDER BY step",
+            Some(run_id.to_string()),
+        )?;
+        let mut metrics = RunMetrics::default();
+        for point in points {
+            metrics.add(point);
+        }
+        Ok(metrics)
+    }
+
+    /// Replace the outputs recorded for a run
+    pub fn record_outputs(&self, run_id: &str, outputs: &[OutputRecord]) -> Result<(), DbError> {
+        self.runtime.block_on(async {
+            let mut query = self
+                .db
+                .query("DELETE output WHERE run_id = $run")
+                .bind(("run", run_id.to_string()));
+            if !outputs.is_empty() {
+                query = query
+                    .query("INSERT INTO output $record")
+                    .bind(("record", outputs.to_vec()));
+            }
+            query.await?.check()?;
+            Ok(())
+        })
+    }
+
+    /// Outputs of a run by stage and path
+    pub fn outputs(&self, run_id: &str) -> Result<Vec<OutputRecord>, DbError> {
+        self.fetch(
+            "SELECT * OMIT id FROM output WHERE run_id = $value ORDER BY stage, path",
+            Some(run_id.to_string()),
+        )
+    }
+
+    /// Run a statement with `$record` bound
+    fn execute(&self, sql: &str, record: impl SurrealValue) -> Result<(), DbError> {
+        self.runtime.block_on(async {
+            self.db.query(sql).bind(("record", record)).await?.check()?;
+            Ok(())
+        })
+    }
+
+    /// Rows of a query with `$value` bound, when given
+    fn fetch<T: SurrealValue>(&self, sql: &str, value: Option<String>) -> Result<Vec<T>, DbError> {
+        Ok(self.runtime.block_on(async {
+            let mut query = self.db.query(sql);
+            if let Some(value) = value {
+                query = query.bind(("value", value));
+            }
+            query.await?.check()?.take(0)
+        })?)
+    }
+}
+
+impl Drop for Database {
+    /// Let the engine shut down, which releases the lock of an embedded file database
+    fn drop(&mut self) {
+        let db = std::mem::replace(&mut self.db, Surreal::init());
+        self.runtime.block_on(async {
+            drop(db);
+            let deadline = Instant::now() + CLOSE_TIMEOUT;
+            while self.runtime.metrics().num_alive_tasks() > 0 && Instant::now() < deadline {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_760_000_000 + secs, 0).unwrap()
+    }
+
+    fn run(run_id: &str, started: i64) -> RunRecord {
+        RunRecord {
+            run_id: run_id.into(),
+            pipeline: "train".into(),
+            status: "running".into(),
+            started: at(started),
+            finished: None,
+            duration_secs: None,
+            base_dir: None,
+            config: None,
+        }
+    }
+
+    #[test]
+    fn test_migrations_apply_once() {
+        let db = Database::memory().unwrap();
+        assert_eq!(db.schema_version().unwrap(), migrations::latest());
+        // Reapplying is a no-op
+        db.migrate().unwrap();
+        assert_eq!(db.schema_version().unwrap(), migrations::latest());
+
+        db.runtime
+            .block_on(
+                db.db
+                    .query("CREATE migration:99 CONTENT { version: 99, name: 'future' }")
+                    .into_future(),
+            )
+            .unwrap();
+        assert!(matches!(
+            db.migrate(),
+            Err(DbError::NewerSchema { found: 99, .. })
+        ));
+    }
+
+    #[test]
+    fn test_runs_and_stages_round_trip() {
+        let db = Database::memory().unwrap();
+        db.record_run(&run("1", 0)).unwrap();
+        db.record_run(&run("2", 60)).unwrap();
+        let finished = RunRecord {
+            status: "succeeded".into(),
+            finished: Some(at(30)),
+            ..run("1", 0)
+        };
+        db.record_run(&finished).unwrap();
+
+        assert_eq!(db.run("1").unwrap(), Some(finished));
+        assert_eq!(db.run("3").unwrap(), None);
+        let ids: Vec<String> = db.runs().unwrap().into_iter().map(|r| r.run_id).collect();
+        assert_eq!(ids, vec!["2", "1"]);
+
+        let stage = |name: &str, status: &str, position| StageRecord {
+            run_id: "1".into(),
+            stage: name.into(),
+            status: status.into(),
+            exit_code: None,
+            duration_secs: 1.5,
+            finished: at(10),
+            params: Some("--epochs 3".into()),
+            position: Some(position),
+        };
+        // Stages recorded together finish at the same time; their position orders them
+        db.record_stage(&stage("fit", "failed", 1)).unwrap();
+        db.record_stage(&stage("load", "succeeded", 0)).unwrap();
+        db.record_stage(&stage("fit", "succeeded", 1)).unwrap();
+        db.record_stage(&stage("report", "skipped", 2)).unwrap();
+        let stages = db.stages("1").unwrap();
+        assert_eq!(
+            stages,
+            vec![
+                stage("load", "succeeded", 0),
+                stage("fit", "succeeded", 1),
+                stage("report", "skipped", 2)
+            ]
+        );
+        assert!(db.stages("2").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_artifacts_and_metrics() {
+        let db = Database::memory().unwrap();
+        let artifact = |name: &str, version: &str, created| ArtifactRecord {
+            name: name.into(),
+            version: version.into(),
+            path: format!("/data/{name}-{version}"),
+            run_id: None,
+            created: at(created),
+        };
+        db.record_dataset(&artifact("iris", "v1", 0)).unwrap();
+        db.record_dataset(&artifact("iris", "v2", 10)).unwrap();
+        db.record_dataset(&artifact("wine", "v1", 5)).unwrap();
+        db.record_model(&artifact("forest", "v1", 20)).unwrap();
+
+        let versions: Vec<String> = db
+            .datasets(Some("iris"))
+            .unwrap()
+            .into_iter()
+            .map(|d| d.version)
+            .collect();
+        assert_eq!(versions, vec!["v2", "v1"]);
+        assert_eq!(db.datasets(None).unwrap().len(), 3);
+        assert_eq!(db.models(None).unwrap(), vec![artifact("forest", "v1", 20)]);
+
+        let point = |step, value| MetricPoint {
+            run: "1".into(),
+            step,
+            name: "loss".into(),
+            value,
+        };
+        db.record_metric(&point(2, 0.4)).unwrap();
+        db.record_metrics(&[point(1, 0.9), point(3, 0.2)]).unwrap();
+        // Recording a run again replaces its points instead of repeating them
+        db.record_metrics(&[point(1, 0.9), point(3, 0.2)]).unwrap();
+        let metrics = db.metrics("1").unwrap();
+        assert_eq!(
+            metrics.get("loss").unwrap().points,
+            vec![(1, 0.9), (2, 0.4), (3, 0.2)]
+        );
+        assert!(db.metrics("2").unwrap().is_empty());
+
+        let output = |path: &str, sha256: &str| OutputRecord {
+            run_id: "1".into(),
+            stage: "fit".into(),
+            path: path.into(),
+            sha256: sha256.into(),
+            bytes: 3,
+        };
+        db.record_outputs("1", &[output("model.bin", "aa"), output("curve.png", "bb")])
+            .unwrap();
+        db.record_outputs("1", &[output("model.bin", "cc")])
+            .unwrap();
+        assert_eq!(db.outputs("1").unwrap(), vec![output("model.bin", "cc")]);
+    }
+
+    #[test]
+    fn test_embedded_file_database_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let endpoint = format!("surrealkv://{}", dir.path().join("riptide.db").display());
+        {
+            let db = Database::open(&endpoint).unwrap();
+            db.record_run(&run("1", 0)).unwrap();
+        }
+        let db = Database::open(&endpoint).unwrap();
+        assert_eq!(db.run("1").unwrap(), Some(run("1", 0)));
+    }
+}
@@ -0,0 +1,591 @@
+//! History of pipeline runs, kept in the riptide database
+//!
+//! When a pipeline run ends, its status and duration, the pipeline as executed, the
+//! parameters of every stage, the metrics it reported and the hashes of its declared
+//! outputs are recorded. Outputs that are tables or model weights, told by their
+//! extension, are also recorded as versions of a dataset or a model named by their path.
+//! Past runs can then be listed, compared with [`RunDiff`] and executed again with
+//! exactly the same configuration.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::config;
+use crate::db::{ArtifactRecord, Database, DbError, OutputRecord, RunRecord, StageRecord};
+use crate::metrics::{Goal, MetricPoint, RunMetrics, format_value};
+use crate::workflow::{Pipeline, RunContext, RunReport, StageStatus, WorkflowError};
+
+/// Length of the output hashes shown to users
+const SHORT_HASH: usize = 12;
+
+/// Extensions of the outputs recorded as datasets
+const DATASET_EXTENSIONS: [&str; 7] = ["csv", "tsv", "json", "ndjson", "jsonl", "parquet", "xlsx"];
+
+/// Extensions of the outputs recorded as models
+const MODEL_EXTENSIONS: [&str; 8] = [
+    "mpk",
+    "bin",
+    "pt",
+    "pth",
+    "onnx",
+    "safetensors",
+    "ckpt",
+    "h5",
+];
+
+/// Where runs are recorded.
+///
+/// The database is opened for each operation only, so an embedded database is not held
+/// locked while a pipeline runs or the TUI is open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunHistory {
+    endpoint: String,
+}
+
+/// Everything recorded about one run
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    pub run: RunRecord,
+    pub stages: Vec<StageRecord>,
+    pub metrics: RunMetrics,
+    pub outputs: Vec<OutputRecord>,
+}
+
+/// A value that differs between two runs; `None` where a run does not have it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// Stage name, or `stage/path` for outputs
+    pub key: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Latest value of a metric in two runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricDelta {
+    pub name: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+}
+
+/// How a run differs from an earlier one
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunDiff {
+    pub before: String,
+    pub after: String,
+    /// Stages whose tool arguments or script changed
+    pub params: Vec<Change>,
+    /// Stages that finished differently
+    pub statuses: Vec<Change>,
+    /// Every metric reported by either run
+    pub metrics: Vec<MetricDelta>,
+    /// Outputs whose content changed
+    pub outputs: Vec<Change>,
+}
+
+impl RunHistory {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+        }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn open(&self) -> Result<Database, DbError> {
+        Database::open(&self.endpoint)
+    }
+
+    /// Record a finished run of `pipeline` that started at `started`
+    pub fn record(
+        &self,
+        pipeline: &Pipeline,
+        report: &RunReport,
+        context: &RunContext,
+        started: DateTime<Utc>,
+    ) -> Result<(), DbError> {
+        let finished = Utc::now();
+        let status = if report
+            .stages
+            .iter()
+            .any(|s| s.status == StageStatus::Aborted)
+        {
+            "aborted"
+        } else if report.succeeded() {
+            "succeeded"
+        } else {
+            "failed"
+        };
+        let db = self.open()?;
+        db.record_run(&RunRecord {
+            run_id: context.run_id.clone(),
+            pipeline: pipeline.name().to_string(),
+            status: status.to_string(),
+            started,
+            finished: Some(finished),
+            duration_secs: Some((finished - started).as_seconds_f64().max(0.0)),
+            base_dir: Some(pipeline.base_dir.display().to_string()),
+            config: serde_yaml::to_string(&pipeline.def).ok(),
+        })?;
+
+        for (position, stage) in report.stages.iter().enumerate() {
+            db.record_stage(&StageRecord {
+                run_id: context.run_id.clone(),
+                stage: stage.name.clone(),
+                status: stage.status.label().to_string(),
+                exit_code: stage.status.exit_code().map(i64::from),
+                duration_secs: stage.duration.as_secs_f64(),
+                finished,
+                params: pipeline.stage(&stage.name).map(|s| s.params()),
+                position: Some(position as i64),
+            })?;
+        }
+
+        let outputs: Vec<OutputRecord> = pipeline
+            .stages()
+            .flat_map(|stage| stage.outputs.iter().map(move |path| (stage, path)))
+            .filter_map(|(stage, path)| {
+                let (sha256, bytes) = hash_file(&pipeline.base_dir.join(path))?;
+                Some(OutputRecord {
+                    run_id: context.run_id.clone(),
+                    stage: stage.name.clone(),
+                    path: path.clone(),
+                    sha256,
+                    bytes,
+                })
+            })
+            .collect();
+        db.record_outputs(&context.run_id, &outputs)?;
+        for output in &outputs {
+            let extension = Path::new(&output.path)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            let artifact = ArtifactRecord {
+                name: output.path.clone(),
+                version: output.sha256.clone(),
+                path: pipeline.base_dir.join(&output.path).display().to_string(),
+                run_id: Some(context.run_id.clone()),
+                created: finished,
+            };
+            if DATASET_EXTENSIONS.contains(&extension.as_str()) {
+                db.record_dataset(&artifact)?;
+            } else if MODEL_EXTENSIONS.contains(&extension.as_str()) {
+                db.record_model(&artifact)?;
+            }
+        }
+
+        if let Some(store) = &context.metrics
+            && let Ok(metrics) = store.read(&context.run_id)
+        {
+            db.record_metrics(&points(&context.run_id, &metrics))?;
+        }
+        Ok(())
+    }
+
+    /// Every recorded run, newest first
+    pub fn list(&self) -> Result<Vec<RunSummary>, DbError> {
+        let db = self.open()?;
+        db.runs()?
+            .into_iter()
+            .map(|run| summarize(&db, run))
+            .collect()
+    }
+
+    pub fn get(&self, run_id: &str) -> Result<Option<RunSummary>, DbError> {
+        let db = self.open()?;
+        db.run(run_id)?.map(|run| summarize(&db, run)).transpose()
+    }
+
+    /// Every version of the datasets runs wrote, newest first
+    pub fn datasets(&self) -> Result<Vec<ArtifactRecord>, DbError> {
+        self.open()?.datasets(None)
+    }
+
+    /// Every version of the models runs wrote, newest first
+    pub fn models(&self) -> Result<Vec<ArtifactRecord>, DbError> {
+        self.open()?.models(None)
+    }
+}
+
+impl Default for RunHistory {
+    /// The database at `config::db_endpoint()`
+    fn default() -> Self {
+        Self::new(config::db_endpoint())
+    }
+}
+
+fn summarize(db: &Database, run: RunRecord) -> Result<RunSummary, DbError> {
+    Ok(RunSummary {
+        stages: db.stages(&run.run_id)?,
+        metrics: db.metrics(&run.run_id)?,
+        outputs: db.outputs(&run.run_id)?,
+        run,
+    })
+}
+
+/// SHA-256 and size of a file, `None` when it does not exist or is a directory
+fn hash_file(path: &Path) -> Option<(String, u64)> {
+    if !path.is_file() {
+        return None;
+    }
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut file, &mut hasher).ok()?;
+    Some((format!("{:x}", hasher.finalize()), size))
+}
+
+/// The points of `metrics` as reported by `run`
+fn points(run: &str, metrics: &RunMetrics) -> Vec<MetricPoint> {
+    metrics
+        .series
+        .iter()
+        .flat_map(|(name, series)| {
+            series.points.iter().map(|(step, value)| MetricPoint {
+                run: run.to_string(),
+                step: *step,
+                name: name.clone(),
+                value: *value,
+            })
+        })
+        .collect()
+}
+
+impl RunSummary {
+    pub fn run_id(&self) -> &str {
+        &self.run.run_id
+    }
+
+    /// Latest value of the first `count` metrics by name, e.g. `acc=0.9100 loss=0.1840`
+    pub fn headline(&self, count: usize) -> String {
+        self.metrics
+            .series
+            .iter()
+            .filter_map(|(name, series)| Some(format!("{name}={}", format_value(series.latest()?))))
+            .take(count)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Run duration as `12.34s`, empty while unknown
+    pub fn duration(&self) -> String {
+        self.run
+            .duration_secs
+            .map(|secs| format!("{secs:.2}s"))
+            .unwrap_or_default()
+    }
+
+    /// The pipeline exactly as it ran, with any parameters edited during the run.
+    /// `None` for runs recorded without their configuration.
+    pub fn pipeline(&self) -> Result<Option<Pipeline>, WorkflowError> {
+        let Some(config) = &self.run.config else {
+            return Ok(None);
+        };
+        let base_dir = self.run.base_dir.as_deref().unwrap_or(".");
+        Pipeline::from_yaml(config, Path::new(base_dir)).map(Some)
+    }
+
+    fn params(&self) -> BTreeMap<&str, &str> {
+        self.stages
+            .iter()
+            .filter_map(|s| Some((s.stage.as_str(), s.params.as_deref()?)))
+            .collect()
+    }
+
+    fn statuses(&self) -> BTreeMap<&str, &str> {
+        self.stages
+            .iter()
+            .map(|s| (s.stage.as_str(), s.status.as_str()))
+            .collect()
+    }
+
+    fn output_hashes(&self) -> BTreeMap<String, &str> {
+        self.outputs
+            .iter()
+            .map(|o| (format!("{}/{}", o.stage, o.path), o.sha256.as_str()))
+            .collect()
+    }
+}
+
+impl fmt::Display for RunSummary {
+    /// Detailed form printed by `riptide history show`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let run = &self.run;
+        writeln!(
+            f,
+            "Run {} of `{}`: {} in {}",
+            run.run_id,
+            run.pipeline,
+            run.status,
+            self.duration()
+        )?;
+        writeln!(f, "Started {}", run.started.format("%Y-%m-%d %H:%M:%S UTC"))?;
+        if let Some(dir) = &run.base_dir {
+            writeln!(f, "Directory {dir}")?;
+        }
+
+        writeln!(
+            f,
+            "\n{:<20} {:<10} {:>9}  PARAMS",
+            "STAGE", "STATUS", "DURATION"
+        )?;
+        for stage in &self.stages {
+            writeln!(
+                f,
+                "{:<20} {:<10} {:>8.2}s  {}",
+                stage.stage,
+                stage.status,
+                stage.duration_secs,
+                stage.params.as_deref().unwrap_or_default()
+            )?;
+        }
+
+        if !self.metrics.is_empty() {
+            writeln!(f, "\n{:<20} {:>12} {:>14}", "METRIC", "LATEST", "BEST")?;
+            for (name, series) in &self.metrics.series {
+                let goal = Goal::of(name);
+                writeln!(
+                    f,
+                    "{name:<20} {:>12} {:>14}",
+                    series.latest().map(format_value).unwrap_or_default(),
+                    series.best(goal).map(format_value).unwrap_or_default()
+                )?;
+            }
+        }
+
+        if !self.outputs.is_empty() {
+            writeln!(
+                f,
+                "\n{:<20} {:<30} {:<14} BYTES",
+                "STAGE", "OUTPUT", "SHA256"
+            )?;
+            for output in &self.outputs {
+                writeln!(
+                    f,
+                    "{:<20} {:<30} {:<14} {}",
+                    output.stage,
+                    output.path,
+                    short_hash(&output.sha256),
+                    output.bytes
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(SHORT_HASH)]
+}
+
+impl MetricDelta {
+    /// Change from the earlier to the later run, when both reported the metric
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.after? - self.before?)
+    }
+
+    /// Whether the later run did better, `None` when unchanged or not comparable
+    pub fn improved(&self) -> Option<bool> {
+        let delta = self.delta().filter(|d| *d != 0.0)?;
+        Some(match Goal::of(&self.name) {
+            Goal::Minimize => delta < 0.0,
+            Goal::Maximize => delta > 0.0,
+        })
+    }
+}
+
+impl RunDiff {
+    /// How `after` differs from `before`
+    pub fn between(before: &RunSummary, after: &RunSummary) -> Self {
+        let names: BTreeSet<&str> = before
+            .metrics
+            .names()
+            .chain(after.metrics.names())
+            .collect();
+        let latest = |summary: &RunSummary, name: &str| summary.metrics.get(name)?.latest();
+        Self {
+            before: before.run_id().to_string(),
+            after: after.run_id().to_string(),
+            params: changes(&before.params(), &after.params()),
+            statuses: changes(&before.statuses(), &after.statuses()),
+            metrics: names
+                .into_iter()
+                .map(|name| MetricDelta {
+                    name: name.to_string(),
+                    before: latest(before, name),
+                    after: latest(after, name),
+                })
+                .collect(),
+            outputs: changes(&before.output_hashes(), &after.output_hashes()),
+        }
+    }
+
+    /// True when the runs ran the same parameters to the same outcome and outputs
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+            && self.statuses.is_empty()
+            && self.outputs.is_empty()
+            && self.metrics.iter().all(|m| m.delta() == Some(0.0))
+    }
+}
+
+/// Keys whose value differs between two maps, in key order
+fn changes<K: AsRef<str> + Ord>(
+    before: &BTreeMap<K, &str>,
+    after: &BTreeMap<K, &str>,
+) -> Vec<Change> {
+    let keys: BTreeSet<&K> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|key| before.get(*key) != after.get(*key))
+        .map(|key| Change {
+            key: key.as_ref().to_string(),
+            before: before.get(key).map(|v| v.to_string()),
+            after: after.get(key).map(|v| v.to_string()),
+        })
+        .collect()
+}
+
+impl fmt::Display for RunDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".to_string());
+        let hash = |v: &Option<String>| v.as_deref().map_or("-", short_hash).to_string();
+
+        writeln!(f, "Run {} → {}", self.before, self.after)?;
+        section(f, "Parameters", &self.params, |c| {
+            format!("{:<20} {} → {}", c.key, value(&c.before), value(&c.after))
+        })?;
+        section(f, "Stages", &self.statuses, |c| {
+            format!("{:<20} {} → {}", c.key, value(&c.before), value(&c.after))
+        })?;
+        section(f, "Metrics", &self.metrics, |m| {
+            let side = |v: Option<f64>| v.map(format_value).unwrap_or_else(|| "-".to_string());
+            let mut line = format!("{:<20} {} → {}", m.name, side(m.before), side(m.after));
+            if let Some(delta) = m.delta() {
+                line.push_str(&format!(" ({delta:+.4}"));
+                match m.improved() {
+                    Some(true) => line.push_str(", better)"),
+                    Some(false) => line.push_str(", worse)"),
+                    None => line.push(')'),
+                }
+            }
+            line
+        })?;
+        section(f, "Outputs", &self.outputs, |c| {
+            format!("{:<20} {} → {}", c.key, hash(&c.before), hash(&c.after))
+        })
+    }
+}
+
+/// A titled list of differences, or a note that there are none
+fn section<T>(
+    f: &mut fmt::Formatter<'_>,
+    title: &str,
+    items: &[T],
+    line: impl Fn(&T) -> String,
+) -> fmt::Result {
+    writeln!(f, "\n{title}")?;
+    if items.is_empty() {
+        return writeln!(f, "  no changes");
+    }
+    for item in items {
+        writeln!(f, "  {}", line(item))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::MetricStore;
+    use crate::workflow::Executor;
+
+    const PIPELINE: &str = r#"
+name: fit
+stages:
+  - name: train
+    run: |
+      echo '{"step": 1, "name": "loss", "value": 0.5}'
+      echo a > model.bin
+    outputs: [model.bin]
+  - name: score
+    run: echo id,score > scores.csv
+    outputs: [scores.csv]
+"#;
+
+    fn run(dir: &Path, history: &RunHistory, run_id: &str, edit: Option<&str>) -> RunReport {
+        let pipeline = Pipeline::from_yaml(PIPELINE, dir).unwrap();
+        let context = RunContext {
+            run_id: run_id.into(),
+            artifact_dir: dir.join("artifacts"),
+            log: None,
+            metrics: Some(MetricStore::new(dir.join("metrics"))),
+            history: Some(history.clone()),
+        };
+        let mut executor = Executor::with_output(pipeline, |_| {}).with_context(context);
+        if let Some(params) = edit {
+            executor.edit(params).unwrap();
+        }
+        executor.run()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_runs_are_recorded_and_compared() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = RunHistory::new(format!(
+            "surrealkv://{}",
+            dir.path().join("riptide.db").display()
+        ));
+
+        assert!(run(dir.path(), &history, "1", None).succeeded());
+        let edited =
+            "echo '{\"step\": 1, \"name\": \"loss\", \"value\": 0.25}'\necho b > model.bin";
+        assert!(run(dir.path(), &history, "2", Some(edited)).succeeded());
+
+        let runs = history.list().unwrap();
+        let ids: Vec<&str> = runs.iter().map(RunSummary::run_id).collect();
+        assert_eq!(ids, vec!["2", "1"]);
+        let (second, first) = (&runs[0], &runs[1]);
+        assert_eq!(first.run.status, "succeeded");
+        assert_eq!(first.headline(3), "loss=0.5000");
+        assert_eq!(first.outputs.len(), 2);
+        assert_eq!(first.outputs[1].bytes, 2);
+
+        // The recorded configuration includes the edit, so a rerun repeats it
+        let rerun = second.pipeline().unwrap().unwrap();
+        assert_eq!(rerun.stage("train").unwrap().params(), edited);
+        assert_eq!(rerun.base_dir, dir.path());
+
+        let diff = RunDiff::between(first, second);
+        assert_eq!(diff.params.len(), 1);
+        assert_eq!(diff.params[0].after.as_deref(), Some(edited));
+        assert!(diff.statuses.is_empty());
+        assert_eq!(diff.metrics[0].delta(), Some(-0.25));
+        assert_eq!(diff.metrics[0].improved(), Some(true));
+        assert_eq!(diff.outputs.len(), 1);
+        assert_eq!(diff.outputs[0].key, "train/model.bin");
+        assert!(!diff.is_empty());
+        assert!(RunDiff::between(first, first).is_empty());
+
+        let text = diff.to_string();
+        assert!(text.contains("0.5000 → 0.2500 (-0.2500, better)"), "{text}");
+        assert!(history.get("3").unwrap().is_none());
+
+        // Both runs wrote the same scores, which are one version of the dataset
+        let datasets = history.datasets().unwrap();
+        assert_eq!(datasets.len(), 1);
+        assert_eq!(datasets[0].name, "scores.csv");
+        assert_eq!(datasets[0].version, first.outputs[0].sha256);
+        let models = history.models().unwrap();
+        let runs: Vec<Option<&str>> = models.iter().map(|m| m.run_id.as_deref()).collect();
+        assert_eq!(runs, [Some("2"), Some("1")]);
+    }
+}
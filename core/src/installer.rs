@@ -0,0 +1,657 @@
+//! Installs tools into riptide's managed prefix (FR-CLI-03)
+//!
+//! A source is a local directory, a git URL or a `.tar.gz`/`.tgz`/`.tar` archive holding a
+//! `riptide-tool.toml`. Cargo crates are built with `cargo install`; anything else is copied
+//! as is. Each tool lands in `<prefix>/<name>`, where the registry discovers it, together
+//! with a receipt recording where it came from.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::config;
+use crate::registry::{MANIFEST, RegistryError, ToolManifest, is_valid_name};
+
+/// File in an installed tool's directory describing the install
+pub const RECEIPT: &str = "riptide-install.toml";
+
+/// Directories never copied or hashed
+const IGNORED: [&str; 2] = [".git", "target"];
+
+/// Where a tool is installed from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Dir(PathBuf),
+    Git(String),
+    Tarball(PathBuf),
+}
+
+/// Record of an installed tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub name: String,
+    pub version: String,
+    /// The source as given to `install`, with local paths made absolute
+    pub source: String,
+    /// SHA-256 of the archive, or of the source tree for directories and git checkouts
+    pub sha256: String,
+}
+
+/// Outcome of `Installer::upgrade`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Upgrade {
+    UpToDate(Receipt),
+    Upgraded { from: Receipt, to: Receipt },
+}
+
+/// Options of `Installer::install`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstallOptions {
+    /// Expected SHA-256 of the source; installing fails when it differs
+    pub sha256: Option<String>,
+    /// Replace an installed tool of the same name
+    pub force: bool,
+}
+
+/// Errors
+#[derive(Debug, Error)]
+pub enum InstallError {
+    #[error("{0}: {1}")]
+    Io(PathBuf, io::Error),
+
+    #[error(transparent)]
+    Manifest(#[from] RegistryError),
+
+    #[error("No {MANIFEST} found in {0}")]
+    NoManifest(PathBuf),
+
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    #[error("`{name}` {version} is already installed; use `riptide upgrade {name}`")]
+    AlreadyInstalled { name: String, version: String },
+
+    #[error("`{0}` is not installed")]
+    NotInstalled(String),
+
+    #[error("`{0}` is not a valid tool name")]
+    InvalidName(String),
+
+    #[error("Invalid install receipt {0}: {1}")]
+    Receipt(PathBuf, toml::de::Error),
+
+    #[error("`{command}` failed: {reason}")]
+    Command { command: String, reason: String },
+
+    #[error("cargo built no binary `{binary}` for entry `{entry}`; name a [[bin]] after it")]
+    MissingBinary { entry: String, binary: String },
+}
+
+/// Installs, upgrades and removes tools under one prefix
+#[derive(Debug, Clone)]
+pub struct Installer {
+    prefix: PathBuf,
+}
+
+impl Source {
+    /// Recognise git URLs and archives by their shape; anything else is a directory
+    pub fn parse(source: &str) -> Self {
+        let is_git = ["https://", "http://", "ssh://", "git://", "git@", "file://"]
+            .iter()
+            .any(|scheme| source.starts_with(scheme))
+            || source.ends_with(".git");
+        if is_git {
+            return Source::Git(source.to_string());
+        }
+        if [".tar.gz", ".tgz", ".tar"]
+            .iter()
+            .any(|ext| source.ends_with(ext))
+        {
+            return Source::Tarball(PathBuf::from(source));
+        }
+        Source::Dir(PathBuf::from(source))
+    }
+
+    /// How the source is written in a receipt, so `upgrade` can find it from anywhere
+    fn describe(&self) -> String {
+        match self {
+            Source::Git(url) => url.clone(),
+            Source::Dir(path) | Source::Tarball(path) => fs::canonicalize(path)
+                .unwrap_or_else(|_| path.clone())
+                .display()
+                .to_string(),
+        }
+    }
+}
+
+impl Installer {
+    pub fn new(prefix: impl Into<PathBuf>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+
+    pub fn prefix(&self) -> &Path {
+        &self.prefix
+    }
+
+    /// Fetch, verify and install a tool
+    pub fn install(&self, source: &str, options: &InstallOptions) -> Result<Receipt, InstallError> {
+        let parsed = Source::parse(source);
+        let work = tempfile::tempdir().map_err(|e| InstallError::Io(std::env::temp_dir(), e))?;
+        let (root, sha256) = fetch(&parsed, work.path())?;
+
+        if let Some(expected) = &options.sha256
+            && !expected.eq_ignore_ascii_case(&sha256)
+        {
+            return Err(InstallError::ChecksumMismatch {
+                expected: expected.clone(),
+                actual: sha256,
+            });
+        }
+
+        let manifest = ToolManifest::load(&root.join(MANIFEST))?;
+        if !options.force
+            && let Some(installed) = self.receipt(&manifest.name)?
+        {
+            return Err(InstallError::AlreadyInstalled {
+                name: installed.name,
+                version: installed.version,
+            });
+        }
+
+        let receipt = Receipt {
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            source: parsed.describe(),
+            sha256,
+        };
+        self.place(&root, manifest, &receipt)?;
+        Ok(receipt)
+    }
+
+    /// Remove an installed tool
+    pub fn uninstall(&self, name: &str) -> Result<Receipt, InstallError> {
+        let receipt = self
+            .receipt(name)?
+            .ok_or_else(|| InstallError::NotInstalled(name.to_string()))?;
+        let dir = self.tool_dir(name)?;
+        fs::remove_dir_all(&dir).map_err(|e| InstallError::Io(dir, e))?;
+        Ok(receipt)
+    }
+
+    /// Reinstall a tool from its recorded source when its version or checksum changed
+    pub fn upgrade(&self, name: &str) -> Result<Upgrade, InstallError> {
+        let from = self
+            .receipt(name)?
+            .ok_or_else(|| InstallError::NotInstalled(name.to_string()))?;
+
+        let source = Source::parse(&from.source);
+        let work = tempfile::tempdir().map_err(|e| InstallError::Io(std::env::temp_dir(), e))?;
+        let (root, sha256) = fetch(&source, work.path())?;
+        let manifest = ToolManifest::load(&root.join(MANIFEST))?;
+        if manifest.version == from.version && sha256 == from.sha256 {
+            return Ok(Upgrade::UpToDate(from));
+        }
+
+        let to = Receipt {
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            source: from.source.clone(),
+            sha256,
+        };
+        self.place(&root, manifest, &to)?;
+        Ok(Upgrade::Upgraded { from, to })
+    }
+
+    /// Receipt of an installed tool, `None` when it is not installed
+    pub fn receipt(&self, name: &str) -> Result<Option<Receipt>, InstallError> {
+        let path = self.tool_dir(name)?.join(RECEIPT);
+        match fs::read_to_string(&path) {
+            Ok(source) => toml::from_str(&source)
+                .map(Some)
+                .map_err(|e| InstallError::Receipt(path, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(InstallError::Io(path, e)),
+        }
+    }
+
+    /// Directory of the tool `name`, which must not lead outside the prefix
+    fn tool_dir(&self, name: &str) -> Result<PathBuf, InstallError> {
+        if !is_valid_name(name) {
+            return Err(InstallError::InvalidName(name.to_string()));
+        }
+        Ok(self.prefix.join(name))
+    }
+
+    /// Build or copy the tool into a staging directory, then swap it into place
+    fn place(
+        &self,
+        root: &Path,
+        mut manifest: ToolManifest,
+        receipt: &Receipt,
+    ) -> Result<(), InstallError> {
+        let target = self.tool_dir(&manifest.name)?;
+        let staging = self.prefix.join(format!(".staging-{}", manifest.name));
+        remove_if_exists(&staging)?;
+
+        if root.join("Cargo.toml").is_file() {
+            cargo_install(root, &staging)?;
+            // cargo puts binaries in <root>/bin
+            let entry = Path::new(&manifest.entry);
+            let binary = entry.file_name().unwrap_or(entry.as_os_str());
+            let installed = Path::new("bin").join(binary);
+            if !staging.join(&installed).is_file() {
+                let binary = binary.to_string_lossy().into_owned();
+                remove_if_exists(&staging)?;
+                return Err(InstallError::MissingBinary {
+                    entry: manifest.entry,
+                    binary,
+                });
+            }
+            manifest.entry = installed.display().to_string();
+        } else {
+            copy_tree(root, &staging)?;
+        }
+
+        let manifest_path = staging.join(MANIFEST);
+        let manifest = toml::to_string(&manifest).expect("manifests serialize");
+        fs::write(&manifest_path, manifest).map_err(|e| InstallError::Io(manifest_path, e))?;
+        let receipt_path = staging.join(RECEIPT);
+        let receipt = toml::to_string(receipt).expect("receipts serialize");
+        fs::write(&receipt_path, receipt).map_err(|e| InstallError::Io(receipt_path, e))?;
+
+        remove_if_exists(&target)?;
+        fs::rename(&staging, &target).map_err(|e| InstallError::Io(target, e))
+    }
+}
+
+impl Default for Installer {
+    /// Installs into `~/.riptide/tools`
+    fn default() -> Self {
+        Self::new(config::tools_dir())
+    }
+}
+
+/// Make the source available locally; returns the directory holding the manifest and the
+/// source's checksum
+fn fetch(source: &Source, work: &Path) -> Result<(PathBuf, String), InstallError> {
+    match source {
+        Source::Dir(dir) => {
+            if !dir.join(MANIFEST).is_file() {
+                return Err(InstallError::NoManifest(dir.clone()));
+            }
+            Ok((dir.clone(), hash_tree(dir)?))
+        }
+        Source::Git(url) => {
+            let checkout = work.join("checkout");
+            run(Command::new("git")
+                .args(["clone", "--depth", "1", "--quiet", url])
+                .arg(&checkout))?;
+            if !checkout.join(MANIFEST).is_file() {
+                return Err(InstallError::NoManifest(PathBuf::from(url)));
+            }
+            let sha256 = hash_tree(&checkout)?;
+            Ok((checkout, sha256))
+        }
+        Source::Tarball(path) => {
+            let bytes = fs::read(path).map_err(|e| InstallError::Io(path.clone(), e))?;
+            let sha256 = hex(&Sha256::digest(&bytes));
+            let unpacked = work.join("unpacked");
+            let reader: Box<dyn Read> = if path.extension().is_some_and(|ext| ext == "tar") {
+                Box::new(bytes.as_slice())
+            } else {
+                Box::new(GzDecoder::new(bytes.as_slice()))
+            };
+            tar::Archive::new(reader)
+                .unpack(&unpacked)
+                .map_err(|e| InstallError::Io(path.clone(), e))?;
+            Ok((find_manifest_root(&unpacked, path)?, sha256))
+        }
+    }
+}
+
+/// Archives usually wrap the tool in one top-level directory
+fn find_manifest_root(unpacked: &Path, archive: &Path) -> Result<PathBuf, InstallError> {
+    if unpacked.join(MANIFEST).is_file() {
+        return Ok(unpacked.to_path_buf());
+    }
+    let entries = fs::read_dir(unpacked).map_err(|e| InstallError::Io(unpacked.into(), e))?;
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|dir| dir.join(MANIFEST).is_file())
+        .ok_or_else(|| InstallError::NoManifest(archive.to_path_buf()))
+}
+
+fn cargo_install(root: &Path, prefix: &Path) -> Result<(), InstallError> {
+    run(Command::new("cargo")
+        .args(["install", "--quiet", "--path"])
+        .arg(root)
+        .arg("--root")
+        .arg(prefix))
+}
+
+fn run(cmd: &mut Command) -> Result<(), InstallError> {
+    let command = format!("{cmd:?}");
+    match cmd.status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(InstallError::Command {
+            command,
+            reason: status.to_string(),
+        }),
+        Err(e) => Err(InstallError::Command {
+            command,
+            reason: e.to_string(),
+        }),
+    }
+}
+
+/// SHA-256 over the relative path and contents of every file, in sorted order
+fn hash_tree(root: &Path) -> Result<String, InstallError> {
+    let mut files = Vec::new();
+    walk(root, root, &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for relative in files {
+        let path = root.join(&relative);
+        let contents = fs::read(&path).map_err(|e| InstallError::Io(path, e))?;
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(&contents);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Relative paths of the files under `dir`
+fn walk(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), InstallError> {
+    let entries = fs::read_dir(dir).map_err(|e| InstallError::Io(dir.to_path_buf(), e))?;
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if IGNORED.iter().any(|ignored| entry.file_name() == *ignored) {
+            continue;
+        }
+        if path.is_dir() {
+            walk(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.push(relative.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+fn copy_tree(from: &Path, to: &Path) -> Result<(), InstallError> {
+    let mut files = Vec::new();
+    walk(from, from, &mut files)?;
+    for relative in files {
+        let target = to.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| InstallError::Io(parent.to_path_buf(), e))?;
+        }
+        // fs::copy keeps permissions, so scripts stay executable
+        fs::copy(from.join(&relative), &target).map_err(|e| InstallError::Io(target, e))?;
+    }
+    Ok(())
+}
+
+fn remove_if_exists(dir: &Path) -> Result<(), InstallError> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(InstallError::Io(dir.into(), e)),
+        _ => Ok(()),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::find_on_path;
+    use crate::registry::ToolRegistry;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::fs::File;
+
+    /// A prebuilt tool: a manifest plus a script as its entry
+    fn write_tool(dir: &Path, version: &str) {
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        fs::write(
+            dir.join(MANIFEST),
+            format!(
+                "name = \"trawl\"\ndescription = \"Nets data\"\nversion = \"{version}\"\nentry = \"bin/trawl\"\n"
+            ),
+        )
+        .unwrap();
+        fs::write(dir.join("bin").join("trawl"), "#!/bin/sh\necho trawling\n").unwrap();
+    }
+
+    /// A cargo crate building the binary `bin`, with `entry` in its manifest
+    fn write_crate(dir: &Path, bin: &str, entry: &str) {
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"trawl-net\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[[bin]]\nname = \"{bin}\"\npath = \"src/main.rs\"\n\n[workspace]\n"
+            ),
+        )
+        .unwrap();
+        fs::write(dir.join("src").join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(
+            dir.join(MANIFEST),
+            format!("name = \"trawl\"\nversion = \"0.1.0\"\nentry = \"{entry}\"\n"),
+        )
+        .unwrap();
+    }
+
+    fn tarball(tool: &Path, archive: &Path) {
+        let file = File::create(archive).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        builder.append_dir_all("trawl-0.1.0", tool).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_parse_sources() {
+        assert_eq!(
+            Source::parse("https://github.com/rykunk21/trawl"),
+            Source::Git("https://github.com/rykunk21/trawl".into())
+        );
+        assert_eq!(
+            Source::parse("git@github.com:rykunk21/trawl.git"),
+            Source::Git("git@github.com:rykunk21/trawl.git".into())
+        );
+        assert_eq!(
+            Source::parse("dist/trawl.tgz"),
+            Source::Tarball("dist/trawl.tgz".into())
+        );
+        assert_eq!(Source::parse("./sail"), Source::Dir("./sail".into()));
+    }
+
+    #[test]
+    fn test_install_upgrade_uninstall_directory() {
+        let source = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        write_tool(source.path(), "0.1.0");
+        let installer = Installer::new(prefix.path());
+        let source_arg = source.path().to_str().unwrap();
+
+        let receipt = installer
+            .install(source_arg, &InstallOptions::default())
+            .unwrap();
+        assert_eq!(receipt.version, "0.1.0");
+        assert_eq!(installer.receipt("trawl").unwrap(), Some(receipt.clone()));
+
+        // The registry finds the installed copy with its entry binary
+        let registry = ToolRegistry::discover(&[prefix.path().to_path_buf()]);
+        let tool = registry.get("trawl").unwrap();
+        assert_eq!(tool.manifest.version, "0.1.0");
+        assert_eq!(
+            tool.entry_path(),
+            Some(prefix.path().join("trawl").join("bin/trawl"))
+        );
+
+        assert!(matches!(
+            installer.install(source_arg, &InstallOptions::default()),
+            Err(InstallError::AlreadyInstalled { .. })
+        ));
+        assert!(matches!(
+            installer.upgrade("trawl").unwrap(),
+            Upgrade::UpToDate(_)
+        ));
+
+        write_tool(source.path(), "0.2.0");
+        match installer.upgrade("trawl").unwrap() {
+            Upgrade::Upgraded { from, to } => {
+                assert_eq!(from.version, "0.1.0");
+                assert_eq!(to.version, "0.2.0");
+            }
+            other => panic!("expected an upgrade, got {other:?}"),
+        }
+
+        installer.uninstall("trawl").unwrap();
+        assert!(!prefix.path().join("trawl").exists());
+        assert!(matches!(
+            installer.uninstall("trawl"),
+            Err(InstallError::NotInstalled(_))
+        ));
+    }
+
+    #[test]
+    fn test_install_cargo_crate() {
+        if find_on_path("cargo").is_none() {
+            return;
+        }
+        let source = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        let installer = Installer::new(prefix.path());
+        let source_arg = source.path().to_str().unwrap();
+
+        // The package is named differently from the binary the manifest runs
+        write_crate(source.path(), "trawl-bin", "trawl");
+        assert!(matches!(
+            installer.install(source_arg, &InstallOptions::default()),
+            Err(InstallError::MissingBinary { .. })
+        ));
+        assert!(!prefix.path().join("trawl").exists());
+
+        write_crate(source.path(), "trawl", "trawl");
+        installer
+            .install(source_arg, &InstallOptions::default())
+            .unwrap();
+        let registry = ToolRegistry::discover(&[prefix.path().to_path_buf()]);
+        let tool = registry.get("trawl").unwrap();
+        assert_eq!(tool.manifest.entry, "bin/trawl");
+        assert!(tool.entry_path().is_some_and(|entry| entry.is_file()));
+    }
+
+    #[test]
+    fn test_tarball_checksum_is_verified() {
+        let tool = tempfile::tempdir().unwrap();
+        let work = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        write_tool(tool.path(), "0.1.0");
+        let archive = work.path().join("trawl.tar.gz");
+        tarball(tool.path(), &archive);
+        let sha256 = hex(&Sha256::digest(fs::read(&archive).unwrap()));
+
+        let installer = Installer::new(prefix.path());
+        let wrong = InstallOptions {
+            sha256: Some("00".repeat(32)),
+            force: false,
+        };
+        assert!(matches!(
+            installer.install(archive.to_str().unwrap(), &wrong),
+            Err(InstallError::ChecksumMismatch { .. })
+        ));
+        assert!(!prefix.path().join("trawl").exists());
+
+        let right = InstallOptions {
+            sha256: Some(sha256.to_uppercase()),
+            force: false,
+        };
+        let receipt = installer
+            .install(archive.to_str().unwrap(), &right)
+            .unwrap();
+        assert_eq!(receipt.sha256, sha256);
+        assert!(prefix.path().join("trawl").join("bin/trawl").is_file());
+    }
+
+    #[test]
+    fn test_names_leading_outside_the_prefix_are_rejected() {
+        let root = tempfile::tempdir().unwrap();
+        let prefix = root.path().join("tools");
+        let victim = root.path().join("victim");
+        fs::create_dir_all(&victim).unwrap();
+        fs::write(victim.join("keep"), "").unwrap();
+
+        let work = tempfile::tempdir().unwrap();
+        let installer = Installer::new(&prefix);
+        for name in ["../victim", "..", "/tmp/victim", "a/b", "."] {
+            let tool = work.path().join(format!("tool{}", name.len()));
+            write_tool(&tool, "0.1.0");
+            let manifest = fs::read_to_string(tool.join(MANIFEST)).unwrap();
+            let manifest = manifest.replace("\"trawl\"", &format!("{name:?}"));
+            fs::write(tool.join(MANIFEST), manifest).unwrap();
+            let archive = work.path().join("hostile.tar.gz");
+            tarball(&tool, &archive);
+
+            assert!(
+                matches!(
+                    installer.install(archive.to_str().unwrap(), &InstallOptions::default()),
+                    Err(InstallError::Manifest(RegistryError::InvalidName(..)))
+                ),
+                "{name}"
+            );
+            assert!(matches!(
+                installer.uninstall(name),
+                Err(InstallError::InvalidName(_))
+            ));
+            fs::remove_dir_all(&tool).unwrap();
+        }
+        assert!(victim.join("keep").is_file());
+    }
+
+    #[test]
+    fn test_install_from_local_git_repository() {
+        if find_on_path("git").is_none() {
+            return;
+        }
+        let repo = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        write_tool(repo.path(), "0.1.0");
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args([
+                    "-c",
+                    "user.name=riptide",
+                    "-c",
+                    "user.email=riptide@localhost",
+                ])
+                .args(args)
+                .current_dir(repo.path())
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "--quiet"]);
+        git(&["add", "."]);
+        git(&["commit", "--quiet", "-m", "trawl"]);
+
+        let url = format!("file://{}", repo.path().display());
+        let receipt = Installer::new(prefix.path())
+            .install(&url, &InstallOptions::default())
+            .unwrap();
+        assert_eq!(receipt.source, url);
+        assert!(prefix.path().join("trawl").join(MANIFEST).is_file());
+    }
+}
@@ -0,0 +1,14 @@
+pub mod actors;
+pub mod app;
+pub mod catalog;
+pub mod commands;
+pub mod config;
+pub mod db;
+pub mod history;
+pub mod installer;
+pub mod logging;
+pub mod metrics;
+pub mod registry;
+pub mod runtime;
+pub mod util;
+pub mod workflow;
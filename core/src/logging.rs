@@ -0,0 +1,328 @@
+//! Structured logs of runs, kept on disk for `riptide logs` (FR-MON-01/03)
+//!
+//! Every run appends JSON lines to `~/.riptide/logs/<run id>.jsonl`:
+//!
+//! ```json
+//! {"ts":"2026-10-18T09:12:03.512Z","run":"1760778723512","stage":"fit","level":"info","message":"epoch 3","fields":{"stream":"stdout"}}
+//! ```
+//!
+//! Tools need no logging library: each line they print becomes a record, `info` for stdout
+//! and `warn` for stderr. A line that is itself a JSON object with a `message` is taken as a
+//! structured record; its `level` is used when valid and its other keys become fields.
+//! Tools print such lines with the `toollog` crate, which also defines [`Level`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub use toollog::Level;
+
+use crate::config;
+use crate::util::jsonl;
+use crate::util::process::Stream;
+
+/// One line of a run's log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub ts: DateTime<Utc>,
+    pub run: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    pub level: Level,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, Value>,
+}
+
+/// Which records to show; unset criteria match everything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    pub run: Option<String>,
+    pub stage: Option<String>,
+    /// Case-insensitive keyword searched in the message and field values
+    pub grep: Option<String>,
+    pub min_level: Option<Level>,
+}
+
+/// Directory holding one JSON-lines file per run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogStore {
+    dir: PathBuf,
+}
+
+/// Reads the records appended to a store since the last `poll`
+#[derive(Debug)]
+pub struct LogTail {
+    store: LogStore,
+    filter: LogFilter,
+    /// Bytes already read from each log file
+    offsets: BTreeMap<PathBuf, u64>,
+}
+
+impl LogRecord {
+    pub fn new(run: &str, stage: Option<&str>, level: Level, message: impl Into<String>) -> Self {
+        Self {
+            ts: Utc::now(),
+            run: run.to_string(),
+            stage: stage.map(str::to_string),
+            level,
+            message: message.into(),
+            fields: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_field(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.fields.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Fields as ` key=value` pairs, strings unquoted
+    pub fn fields_text(&self) -> String {
+        self.fields
+            .iter()
+            .map(|(key, value)| match value {
+                Value::String(s) => format!(" {key}={s}"),
+                value => format!(" {key}={value}"),
+            })
+            .collect()
+    }
+
+    /// A record for a line printed by a tool, structured when the line is a JSON object
+    pub fn from_line(run: &str, stage: Option<&str>, stream: Stream, line: &str) -> Self {
+        let default = match stream {
+            Stream::Stdout => Level::Info,
+            Stream::Stderr => Level::Warn,
+        };
+        if let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(line)
+            && let Some(Value::String(message)) = object.remove("message")
+        {
+            let level = match object.remove("level") {
+                Some(Value::String(level)) => level.parse().unwrap_or(default),
+                _ => default,
+            };
+            let mut record = Self::new(run, stage, level, message);
+            record.fields.extend(object);
+            return record;
+        }
+
+        let stream = match stream {
+            Stream::Stdout => "stdout",
+            Stream::Stderr => "stderr",
+        };
+        Self::new(run, stage, default, line).with_field("stream", stream)
+    }
+}
+
+impl fmt::Display for LogRecord {
+    /// Plain-mode line: time, level, run/stage, message and fields
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<5} [{}",
+            self.ts.format("%Y-%m-%d %H:%M:%S%.3f"),
+            self.level,
+            self.run
+        )?;
+        if let Some(stage) = &self.stage {
+            write!(f, "/{stage}")?;
+        }
+        write!(f, "] {}{}", self.message, self.fields_text())
+    }
+}
+
+impl LogFilter {
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        if self.run.as_ref().is_some_and(|run| *run != record.run)
+            || self
+                .stage
+                .as_ref()
+                .is_some_and(|stage| record.stage.as_ref() != Some(stage))
+            || self.min_level.is_some_and(|level| record.level < level)
+        {
+            return false;
+        }
+        match &self.grep {
+            Some(keyword) => {
+                let keyword = keyword.to_lowercase();
+                record.message.to_lowercase().contains(&keyword)
+                    || record.fields.values().any(|value| {
+                        let text = match value {
+                            Value::String(s) => s.clone(),
+                            value => value.to_string(),
+                        };
+                        text.to_lowercase().contains(&keyword)
+                    })
+            }
+            None => true,
+        }
+    }
+}
+
+impl LogStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Log file of a run
+    pub fn path(&self, run: &str) -> PathBuf {
+        self.dir.join(format!("{run}.jsonl"))
+    }
+
+    /// Append a record to its run's file
+    pub fn append(&self, record: &LogRecord) -> io::Result<()> {
+        jsonl::append(&self.path(&record.run), record)
+    }
+
+    /// Ids of the runs with logs, oldest first
+    pub fn runs(&self) -> io::Result<Vec<String>> {
+        jsonl::runs(&self.dir)
+    }
+
+    /// Every record matching `filter`, in the order written
+    pub fn read(&self, filter: &LogFilter) -> io::Result<Vec<LogRecord>> {
+        self.tail(filter.clone()).poll()
+    }
+
+    /// Follow the records matching `filter`, starting with those already written
+    pub fn tail(&self, filter: LogFilter) -> LogTail {
+        LogTail {
+            store: self.clone(),
+            filter,
+            offsets: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for LogStore {
+    /// The store under `~/.riptide/logs`
+    fn default() -> Self {
+        Self::new(config::logs_dir())
+    }
+}
+
+impl LogTail {
+    pub fn filter(&self) -> &LogFilter {
+        &self.filter
+    }
+
+    /// Records written since the previous call; lines still being written are left for later
+    pub fn poll(&mut self) -> io::Result<Vec<LogRecord>> {
+        let runs = match &self.filter.run {
+            Some(run) => vec![run.clone()],
+            None => self.store.runs()?,
+        };
+        let mut records = Vec::new();
+        for run in runs {
+            let path = self.store.path(&run);
+            let offset = self.offsets.get(&path).copied().unwrap_or(0);
+            let read = jsonl::read_from(&path, offset, |record: LogRecord| {
+                if self.filter.matches(&record) {
+                    records.push(record);
+                }
+            })?;
+            self.offsets.insert(path, offset + read);
+        }
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    #[test]
+    fn test_lines_become_records() {
+        let plain = LogRecord::from_line("r1", Some("fit"), Stream::Stderr, "disk almost full");
+        assert_eq!(plain.level, Level::Warn);
+        assert_eq!(plain.fields["stream"], "stderr");
+
+        let structured = LogRecord::from_line(
+            "r1",
+            Some("load"),
+            Stream::Stdout,
+            r#"{"level":"error","message":"Found 0 point clouds","file":"scan.pcd"}"#,
+        );
+        assert_eq!(structured.level, Level::Error);
+        assert_eq!(structured.message, "Found 0 point clouds");
+        assert_eq!(structured.fields["file"], "scan.pcd");
+        assert!(
+            structured
+                .to_string()
+                .ends_with("error [r1/load] Found 0 point clouds file=scan.pcd")
+        );
+
+        let not_a_record = LogRecord::from_line("r1", None, Stream::Stdout, r#"{"loss":0.3}"#);
+        assert_eq!(not_a_record.message, r#"{"loss":0.3}"#);
+    }
+
+    #[test]
+    fn test_store_filters_and_tails() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LogStore::new(dir.path());
+        store
+            .append(&LogRecord::new(
+                "1",
+                Some("load"),
+                Level::Info,
+                "reading data.csv",
+            ))
+            .unwrap();
+        store
+            .append(
+                &LogRecord::new("2", Some("fit"), Level::Error, "diverged").with_field("loss", 1e9),
+            )
+            .unwrap();
+        assert_eq!(store.runs().unwrap(), vec!["1", "2"]);
+
+        let messages = |records: Vec<LogRecord>| -> Vec<String> {
+            records.into_iter().map(|r| r.message).collect()
+        };
+        assert_eq!(
+            messages(store.read(&LogFilter::default()).unwrap()),
+            vec!["reading data.csv", "diverged"]
+        );
+        let filter = LogFilter {
+            grep: Some("DATA".into()),
+            ..LogFilter::default()
+        };
+        assert_eq!(
+            messages(store.read(&filter).unwrap()),
+            vec!["reading data.csv"]
+        );
+        let filter = LogFilter {
+            stage: Some("fit".into()),
+            min_level: Some(Level::Warn),
+            ..LogFilter::default()
+        };
+        assert_eq!(messages(store.read(&filter).unwrap()), vec!["diverged"]);
+
+        let mut tail = store.tail(LogFilter {
+            run: Some("2".into()),
+            ..LogFilter::default()
+        });
+        assert_eq!(tail.poll().unwrap().len(), 1);
+        assert!(tail.poll().unwrap().is_empty());
+
+        // A partly written line is picked up once complete
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(store.path("2"))
+            .unwrap();
+        let line = serde_json::to_string(&LogRecord::new("2", None, Level::Info, "done")).unwrap();
+        file.write_all(line.as_bytes()).unwrap();
+        assert!(tail.poll().unwrap().is_empty());
+        file.write_all(b"\n").unwrap();
+        assert_eq!(messages(tail.poll().unwrap()), vec!["done"]);
+    }
+}
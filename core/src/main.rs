@@ -1,7 +1,61 @@
-mod actors;
+use std::path::Path;
+use std::process::ExitCode;
 
-fn main() {
-    println!("Hello, world!");
+use riptide::actors::CLI;
+use riptide::actors::cli::{CLIMsg, CliCommand, CliError};
+use riptide::app::App;
+use riptide::workflow::{Pipeline, RunContext};
+
+fn main() -> ExitCode {
+    let mut cli = CLI::new();
+
+    let command = match CLI::parse(std::env::args_os()) {
+        Ok(Some(command)) => command,
+        Ok(None) => return ExitCode::SUCCESS,
+        Err(e) => return fail(e),
+    };
+    if let Err(e) = cli.send(CLIMsg::Execute(command.clone())) {
+        return fail(e);
+    }
+
+    if let CliCommand::Launch { pipeline } = command
+        && cli == CLI::LaunchingTUI
+    {
+        let result = launch(pipeline.as_deref());
+        let _ = cli.send(CLIMsg::Finish);
+        if let Err(e) = result {
+            eprintln!("riptide: {e}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Open the dashboard, stepping through `pipeline` if one was given
+fn launch(pipeline: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut app = App::new()?;
+    if let Some(path) = pipeline {
+        app.load_pipeline(Pipeline::load(Path::new(path))?, RunContext::recorded());
+    }
+
+    let terminal = ratatui::init();
+    let result = app.run(terminal);
+    ratatui::restore();
+    result
+}
+
+fn fail(error: CliError) -> ExitCode {
+    match error {
+        CliError::Usage(e) => {
+            let _ = e.print();
+            ExitCode::from(2)
+        }
+        e => {
+            eprintln!("riptide: {e}");
+            ExitCode::from(e.exit_code())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -11,6 +65,16 @@ mod tests {
 
     #[test]
     fn test_main() {
-        main();
+        assert_eq!(
+            CLI::parse(["riptide", "run", "sail", "--", "data.csv"]).unwrap(),
+            Some(CliCommand::Run {
+                module: "sail".into(),
+                args: vec!["data.csv".into()],
+            })
+        );
+        assert_eq!(CLI::parse(["riptide", "--help"]).unwrap(), None);
+
+        let error = CLI::parse(["riptide", "no-such-command"]).unwrap_err();
+        assert_eq!(fail(error), ExitCode::from(2));
     }
 }
@@ -0,0 +1,311 @@
+//! Metrics reported by training and evaluation runs (FR-MON-02)
+//!
+//! A tool reports a metric by printing one JSON object per line on stdout:
+//!
+//! ```json
+//! {"step": 12, "name": "loss", "value": 0.184}
+//! ```
+//!
+//! Points belong to the run printing the line, whose id the tool gets as `$RIPTIDE_RUN_ID`;
+//! a `run` key in the line is ignored. Points are kept in `~/.riptide/metrics/<run id>.jsonl`.
+//! Metrics named like a loss or an error (`loss`, `val_loss`, `mae`, `rmse`, ...) improve as
+//! they go down; every other metric improves as it goes up.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use surrealdb::types::SurrealValue;
+
+use crate::config;
+use crate::util::jsonl;
+
+/// One value of a metric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SurrealValue)]
+#[serde(deny_unknown_fields)]
+pub struct MetricPoint {
+    /// Set by riptide, never by the tool: it names the file the point is appended to
+    #[serde(default)]
+    pub run: String,
+    pub step: u64,
+    pub name: String,
+    pub value: f64,
+}
+
+/// Whether a metric improves by going up or down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Minimize,
+    Maximize,
+}
+
+/// The points of one metric in the order reported
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Series {
+    pub points: Vec<(u64, f64)>,
+}
+
+/// Every metric of a run, by name
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RunMetrics {
+    pub series: BTreeMap<String, Series>,
+}
+
+/// Directory holding one JSON-lines file of points per run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricStore {
+    dir: PathBuf,
+}
+
+/// Reads the points of one run appended since the last `poll`
+#[derive(Debug)]
+pub struct MetricTail {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl MetricPoint {
+    /// The point a line of output of the run `run` reports, if it is one
+    pub fn parse(line: &str, run: &str) -> Option<Self> {
+        let mut point: Self = serde_json::from_str(line.trim()).ok()?;
+        if !point.value.is_finite() || point.name.is_empty() {
+            return None;
+        }
+        point.run = run.to_string();
+        Some(point)
+    }
+}
+
+impl Goal {
+    pub fn of(name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        let lower_is_better = ["loss", "error", "err", "mae", "mse", "rmse", "perplexity"];
+        if name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|word| lower_is_better.contains(&word))
+        {
+            Goal::Minimize
+        } else {
+            Goal::Maximize
+        }
+    }
+}
+
+impl Series {
+    pub fn latest(&self) -> Option<f64> {
+        self.points.last().map(|(_, value)| *value)
+    }
+
+    /// The best value according to `goal`
+    pub fn best(&self, goal: Goal) -> Option<f64> {
+        let values = self.points.iter().map(|(_, value)| *value);
+        match goal {
+            Goal::Minimize => values.reduce(f64::min),
+            Goal::Maximize => values.reduce(f64::max),
+        }
+    }
+
+    /// Smallest and largest value, for scaling charts
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        bounds(&self.points)
+    }
+
+    /// The last `width` values as a one-line bar chart
+    pub fn sparkline(&self, width: usize) -> String {
+        const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+        let start = self.points.len().saturating_sub(width);
+        let values = &self.points[start..];
+        let Some((low, high)) = bounds(values) else {
+            return String::new();
+        };
+        values
+            .iter()
+            .map(|(_, value)| {
+                if high > low {
+                    let level = (value - low) / (high - low) * (BARS.len() - 1) as f64;
+                    BARS[level.round() as usize]
+                } else {
+                    BARS[BARS.len() / 2]
+                }
+            })
+            .collect()
+    }
+}
+
+impl RunMetrics {
+    pub fn add(&mut self, point: MetricPoint) {
+        self.series
+            .entry(point.name)
+            .or_default()
+            .points
+            .push((point.step, point.value));
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.series.keys().map(String::as_str)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Series> {
+        self.series.get(name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+}
+
+impl MetricStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Metrics file of a run
+    pub fn path(&self, run: &str) -> PathBuf {
+        self.dir.join(format!("{run}.jsonl"))
+    }
+
+    pub fn append(&self, point: &MetricPoint) -> io::Result<()> {
+        jsonl::append(&self.path(&point.run), point)
+    }
+
+    /// Ids of the runs with metrics, oldest first
+    pub fn runs(&self) -> io::Result<Vec<String>> {
+        jsonl::runs(&self.dir)
+    }
+
+    /// Every metric a run reported so far
+    pub fn read(&self, run: &str) -> io::Result<RunMetrics> {
+        let mut metrics = RunMetrics::default();
+        for point in self.tail(run).poll()? {
+            metrics.add(point);
+        }
+        Ok(metrics)
+    }
+
+    /// Follow the points of `run`, starting with those already written
+    pub fn tail(&self, run: &str) -> MetricTail {
+        MetricTail {
+            path: self.path(run),
+            offset: 0,
+        }
+    }
+}
+
+impl Default for MetricStore {
+    /// The store under `~/.riptide/metrics`
+    fn default() -> Self {
+        Self::new(config::metrics_dir())
+    }
+}
+
+impl MetricTail {
+    /// Points written since the previous call
+    pub fn poll(&mut self) -> io::Result<Vec<MetricPoint>> {
+        let mut points = Vec::new();
+        self.offset += jsonl::read_from(&self.path, self.offset, |point| points.push(point))?;
+        Ok(points)
+    }
+}
+
+/// A metric value in at most a dozen columns
+pub fn format_value(value: f64) -> String {
+    if value != 0.0 && !(1e-3..1e6).contains(&value.abs()) {
+        format!("{value:.3e}")
+    } else {
+        format!("{value:.4}")
+    }
+}
+
+fn bounds(points: &[(u64, f64)]) -> Option<(f64, f64)> {
+    points.iter().fold(None, |bounds, (_, value)| match bounds {
+        None => Some((*value, *value)),
+        Some((low, high)) => Some((value.min(low), value.max(high))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metric_lines() {
+        let point = MetricPoint::parse(r#"{"step": 3, "name": "loss", "value": 0.25}"#, "r1");
+        assert_eq!(
+            point,
+            Some(MetricPoint {
+                run: "r1".into(),
+                step: 3,
+                name: "loss".into(),
+                value: 0.25,
+            })
+        );
+        // Log records and other JSON are not metrics
+        assert_eq!(
+            MetricPoint::parse(r#"{"message": "hi", "level": "info"}"#, "r1"),
+            None
+        );
+        assert_eq!(
+            MetricPoint::parse(r#"{"step": 1, "name": "acc", "value": 1, "x": 2}"#, "r1"),
+            None
+        );
+        assert_eq!(MetricPoint::parse("epoch 3", "r1"), None);
+        // A tool cannot write into another run's file
+        let point = MetricPoint::parse(
+            r#"{"run": "../r0", "step": 1, "name": "acc", "value": 1}"#,
+            "r1",
+        );
+        assert_eq!(point.unwrap().run, "r1");
+    }
+
+    #[test]
+    fn test_series_summaries() {
+        assert_eq!(Goal::of("val_loss"), Goal::Minimize);
+        assert_eq!(Goal::of("RMSE"), Goal::Minimize);
+        assert_eq!(Goal::of("accuracy"), Goal::Maximize);
+
+        let series = Series {
+            points: vec![(1, 0.9), (2, 0.5), (3, 0.7), (4, 0.1)],
+        };
+        assert_eq!(series.latest(), Some(0.1));
+        assert_eq!(series.best(Goal::Minimize), Some(0.1));
+        assert_eq!(series.best(Goal::Maximize), Some(0.9));
+        assert_eq!(series.sparkline(10), "█▅▆▁");
+        assert_eq!(series.sparkline(2), "█▁");
+        assert_eq!(Series::default().sparkline(5), "");
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MetricStore::new(dir.path());
+        let point = |step, name: &str, value| MetricPoint {
+            run: "9".into(),
+            step,
+            name: name.into(),
+            value,
+        };
+        store.append(&point(1, "loss", 0.8)).unwrap();
+        store.append(&point(1, "accuracy", 0.6)).unwrap();
+
+        let mut tail = store.tail("9");
+        assert_eq!(tail.poll().unwrap().len(), 2);
+        store.append(&point(2, "loss", 0.4)).unwrap();
+        assert_eq!(tail.poll().unwrap(), vec![point(2, "loss", 0.4)]);
+
+        let metrics = store.read("9").unwrap();
+        assert_eq!(
+            metrics.names().collect::<Vec<_>>(),
+            vec!["accuracy", "loss"]
+        );
+        assert_eq!(
+            metrics.get("loss").unwrap().points,
+            vec![(1, 0.8), (2, 0.4)]
+        );
+        assert_eq!(store.runs().unwrap(), vec!["9"]);
+    }
+}
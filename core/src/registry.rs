@@ -0,0 +1,301 @@
+//! Tools riptide can orchestrate, discovered from `riptide-tool.toml` manifests (FR-EXT-01/02)
+//!
+//! ```toml
+//! name = "dock"
+//! description = "Data Organization and Cleaning Kit"
+//! version = "0.1.0"
+//! # A binary on PATH, or a path relative to the manifest
+//! entry = "dock"
+//! # Hand the tool the terminal instead of piping its output (default false)
+//! interactive = false
+//!
+//! [[args]]
+//! name = "dataset"
+//! help = "Dataset to inspect, clean, transform, export or snapshot"
+//! required = true
+//!
+//! [[artifacts]]
+//! name = "cleaned"
+//! path = "*.csv"
+//! ```
+//!
+//! A directory on the search path is a tool when it holds a manifest; otherwise each of its
+//! subdirectories holding one is. Adding a tool therefore needs no change to riptide (NFR-07).
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::commands::find_on_path;
+use crate::config;
+
+/// File name of a tool manifest
+pub const MANIFEST: &str = "riptide-tool.toml";
+
+/// Contents of a `riptide-tool.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub version: String,
+    /// Binary that runs the tool
+    pub entry: String,
+    /// Whether the tool reads from or draws on the terminal, like sail's viewer. `riptide
+    /// run` then hands it the terminal as is instead of prefixing its output.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub interactive: bool,
+    /// Arguments the entry binary accepts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<ArgSpec>,
+    /// Files the tool produces
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<ArtifactSpec>,
+}
+
+/// An argument accepted by a tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArgSpec {
+    pub name: String,
+    #[serde(default)]
+    pub help: String,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// A file a tool produces, matched by a glob-style path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArtifactSpec {
+    pub name: String,
+    pub path: String,
+}
+
+/// A discovered tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tool {
+    pub manifest: ToolManifest,
+    /// Directory holding the manifest
+    pub dir: PathBuf,
+}
+
+/// Errors
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("Cannot read {0}: {1}")]
+    Io(PathBuf, io::Error),
+
+    #[error("Invalid tool manifest {0}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+
+    #[error("Tool manifest {0} has an empty `{1}`")]
+    Empty(PathBuf, &'static str),
+
+    #[error("Tool manifest {0} has an invalid name `{1}`: it must be a plain directory name")]
+    InvalidName(PathBuf, String),
+}
+
+/// Every tool found on the search path
+#[derive(Debug, Default)]
+pub struct ToolRegistry {
+    tools: Vec<Tool>,
+    /// Manifests that were found but could not be used
+    errors: Vec<RegistryError>,
+}
+
+impl ToolManifest {
+    /// Read and validate a manifest
+    pub fn load(path: &Path) -> Result<Self, RegistryError> {
+        let source =
+            fs::read_to_string(path).map_err(|e| RegistryError::Io(path.to_path_buf(), e))?;
+        let manifest: Self =
+            toml::from_str(&source).map_err(|e| RegistryError::Parse(path.to_path_buf(), e))?;
+        for (field, value) in [
+            ("name", &manifest.name),
+            ("version", &manifest.version),
+            ("entry", &manifest.entry),
+        ] {
+            if value.trim().is_empty() {
+                return Err(RegistryError::Empty(path.to_path_buf(), field));
+            }
+        }
+        if !is_valid_name(&manifest.name) {
+            return Err(RegistryError::InvalidName(
+                path.to_path_buf(),
+                manifest.name,
+            ));
+        }
+        Ok(manifest)
+    }
+}
+
+/// Whether `name` can name a tool: tools are installed to `<prefix>/<name>`, so it must be
+/// a single plain path component, never `..`, a root or a nested path
+pub fn is_valid_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none()
+}
+
+impl Tool {
+    pub fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    /// The entry binary, if it exists: a path relative to the manifest or a name on `PATH`
+    pub fn entry_path(&self) -> Option<PathBuf> {
+        let entry = Path::new(&self.manifest.entry);
+        if entry.components().count() > 1 || entry.is_absolute() {
+            let path = self.dir.join(entry);
+            return path.is_file().then_some(path);
+        }
+        find_on_path(&self.manifest.entry)
+    }
+}
+
+impl ToolRegistry {
+    /// Discover tools on the default search path
+    pub fn load() -> Self {
+        Self::discover(&Self::search_path())
+    }
+
+    /// `$RIPTIDE_TOOL_PATH`, then the current directory, then `~/.riptide/tools`
+    pub fn search_path() -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = env::var_os("RIPTIDE_TOOL_PATH")
+            .map(|paths| env::split_paths(&paths).collect())
+            .unwrap_or_default();
+        dirs.push(PathBuf::from("."));
+        dirs.push(config::tools_dir());
+        dirs
+    }
+
+    /// Discover tools in `dirs`; when several share a name the first found wins
+    pub fn discover(dirs: &[PathBuf]) -> Self {
+        let mut registry = Self::default();
+        for dir in dirs {
+            for manifest in manifests_in(dir) {
+                match ToolManifest::load(&manifest) {
+                    Ok(tool) if registry.get(&tool.name).is_some() => {}
+                    Ok(tool) => registry.tools.push(Tool {
+                        manifest: tool,
+                        dir: manifest.parent().unwrap_or(dir).to_path_buf(),
+                    }),
+                    Err(e) => registry.errors.push(e),
+                }
+            }
+        }
+        registry.tools.sort_by(|a, b| a.name().cmp(b.name()));
+        registry
+    }
+
+    /// Tools sorted by name
+    pub fn tools(&self) -> &[Tool] {
+        &self.tools
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tool> {
+        self.tools.iter().find(|tool| tool.name() == name)
+    }
+
+    pub fn errors(&self) -> &[RegistryError] {
+        &self.errors
+    }
+}
+
+/// The manifest of `dir` itself, or else those of its immediate subdirectories
+fn manifests_in(dir: &Path) -> Vec<PathBuf> {
+    let own = dir.join(MANIFEST);
+    if own.is_file() {
+        return vec![own];
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut manifests: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path().join(MANIFEST))
+        .filter(|path| path.is_file())
+        .collect();
+    manifests.sort();
+    manifests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_tool(dir: &Path, name: &str, version: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join(MANIFEST),
+            format!("name = \"{name}\"\nversion = \"{version}\"\nentry = \"bin/{name}\"\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_bundled_submodules_are_discovered() {
+        let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let registry = ToolRegistry::discover(&[workspace]);
+        assert!(registry.errors().is_empty(), "{:?}", registry.errors());
+
+        let names: Vec<&str> = registry.tools().iter().map(Tool::name).collect();
+        assert_eq!(names, vec!["buoy", "dock", "sail", "surf"]);
+        let dock = registry.get("dock").unwrap();
+        assert_eq!(dock.manifest.args[0].name, "dataset");
+        assert_eq!(dock.manifest.artifacts[0].path, "*.csv");
+    }
+
+    #[test]
+    fn test_discovery_order_and_errors() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        write_tool(&first.path().join("trawl"), "trawl", "2.0.0");
+        write_tool(&second.path().join("trawl"), "trawl", "1.0.0");
+        // A directory that is itself a tool
+        write_tool(second.path(), "net", "0.3.0");
+        fs::create_dir(first.path().join("broken")).unwrap();
+        fs::write(first.path().join("broken").join(MANIFEST), "name = 3").unwrap();
+
+        let registry = ToolRegistry::discover(&[
+            first.path().to_path_buf(),
+            second.path().to_path_buf(),
+            first.path().join("missing"),
+        ]);
+
+        assert_eq!(registry.get("trawl").unwrap().manifest.version, "2.0.0");
+        assert!(registry.get("net").is_some());
+        assert_eq!(registry.tools().len(), 2);
+        assert!(matches!(registry.errors(), [RegistryError::Parse(..)]));
+    }
+
+    #[test]
+    fn test_entry_relative_to_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        write_tool(dir.path(), "trawl", "0.1.0");
+        let registry = ToolRegistry::discover(&[dir.path().to_path_buf()]);
+        let tool = registry.get("trawl").unwrap();
+        assert_eq!(tool.entry_path(), None);
+
+        fs::create_dir(dir.path().join("bin")).unwrap();
+        fs::write(dir.path().join("bin").join("trawl"), "").unwrap();
+        assert_eq!(tool.entry_path(), Some(dir.path().join("bin/trawl")));
+
+        let empty = dir.path().join("empty");
+        fs::create_dir(&empty).unwrap();
+        fs::write(
+            empty.join(MANIFEST),
+            "name = \"\"\nversion = \"1\"\nentry = \"x\"\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            ToolManifest::load(&empty.join(MANIFEST)),
+            Err(RegistryError::Empty(_, "name"))
+        ));
+    }
+}
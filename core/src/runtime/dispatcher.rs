@@ -0,0 +1,157 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{Actor, Handle, Mailbox, RuntimeError};
+
+/// Type-erased view of a spawned actor so the dispatcher can hold any mix of them
+trait Process {
+    /// Deliver every queued message, returning how many were handled
+    fn drain(&mut self) -> Result<usize, RuntimeError>;
+}
+
+struct Slot<A: Actor> {
+    name: String,
+    mailbox: Mailbox<A::Msg>,
+    actor: Rc<RefCell<A>>,
+}
+
+impl<A: Actor> Process for Slot<A> {
+    fn drain(&mut self) -> Result<usize, RuntimeError> {
+        let mut handled = 0;
+        while let Some(msg) = self.mailbox.next() {
+            self.actor
+                .borrow_mut()
+                .handle(msg)
+                .map_err(|e| RuntimeError::Actor {
+                    actor: self.name.clone(),
+                    source: Box::new(e),
+                })?;
+            handled += 1;
+        }
+        Ok(handled)
+    }
+}
+
+/// Single-threaded dispatcher delivering messages to spawned actors
+#[derive(Default)]
+pub struct Dispatcher {
+    processes: Vec<Box<dyn Process>>,
+}
+
+impl Dispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an actor and return a handle for messaging it
+    pub fn spawn<A: Actor>(&mut self, name: &str, actor: A) -> Handle<A> {
+        let (address, mailbox) = Mailbox::new();
+        let actor = Rc::new(RefCell::new(actor));
+
+        self.processes.push(Box::new(Slot {
+            name: name.to_string(),
+            mailbox,
+            actor: Rc::clone(&actor),
+        }));
+
+        Handle::new(address, actor)
+    }
+
+    /// Deliver messages until every mailbox is empty, returning the number handled.
+    ///
+    /// Messages sent while handling are delivered in the same call. The first actor
+    /// error stops delivery; messages still queued stay in their mailboxes.
+    pub fn run_until_idle(&mut self) -> Result<usize, RuntimeError> {
+        let mut total = 0;
+        loop {
+            let mut handled = 0;
+            for process in self.processes.iter_mut() {
+                handled += process.drain()?;
+            }
+            if handled == 0 {
+                return Ok(total);
+            }
+            total += handled;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Address;
+    use std::convert::Infallible;
+
+    /// Test double that counts pings and optionally forwards them
+    struct Counter {
+        count: usize,
+        forward: Option<Address<Ping>>,
+    }
+
+    struct Ping;
+
+    impl Actor for Counter {
+        type Msg = Ping;
+        type Error = Infallible;
+
+        fn handle(&mut self, msg: Ping) -> Result<(), Infallible> {
+            self.count += 1;
+            if let Some(next) = &self.forward {
+                next.send(msg).unwrap();
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("refused")]
+    struct Refused;
+
+    struct Grumpy;
+
+    impl Actor for Grumpy {
+        type Msg = Ping;
+        type Error = Refused;
+
+        fn handle(&mut self, _: Ping) -> Result<(), Refused> {
+            Err(Refused)
+        }
+    }
+
+    #[test]
+    fn test_messages_are_delivered_between_actors() {
+        let mut dispatcher = Dispatcher::new();
+        let last = dispatcher.spawn(
+            "last",
+            Counter {
+                count: 0,
+                forward: None,
+            },
+        );
+        let first = dispatcher.spawn(
+            "first",
+            Counter {
+                count: 0,
+                forward: Some(last.address()),
+            },
+        );
+
+        first.send(Ping).unwrap();
+        first.send(Ping).unwrap();
+
+        assert_eq!(dispatcher.run_until_idle().unwrap(), 4);
+        assert_eq!(first.state().count, 2);
+        assert_eq!(last.state().count, 2);
+        assert_eq!(dispatcher.run_until_idle().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_actor_error_names_the_actor() {
+        let mut dispatcher = Dispatcher::new();
+        let grumpy = dispatcher.spawn("grumpy", Grumpy);
+        grumpy.send(Ping).unwrap();
+
+        let err = dispatcher.run_until_idle().unwrap_err();
+        assert_eq!(err.to_string(), "Actor `grumpy` failed: refused");
+    }
+}
@@ -0,0 +1,81 @@
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use super::{Actor, RuntimeError};
+
+/// Sending half of a mailbox; cheap to clone and safe to move across threads
+pub struct Address<M> {
+    tx: Sender<M>,
+}
+
+/// Receiving half of a mailbox, owned by the dispatcher
+pub struct Mailbox<M> {
+    rx: Receiver<M>,
+}
+
+impl<M> Mailbox<M> {
+    /// Create a mailbox and the address that feeds it
+    pub fn new() -> (Address<M>, Self) {
+        let (tx, rx) = mpsc::channel();
+        (Address { tx }, Self { rx })
+    }
+
+    /// Take the next queued message without blocking
+    pub fn next(&self) -> Option<M> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl<M> Address<M> {
+    /// Queue a message for delivery
+    pub fn send(&self, msg: M) -> Result<(), RuntimeError> {
+        self.tx
+            .send(msg)
+            .map_err(|_| RuntimeError::Closed("receiver dropped".into()))
+    }
+}
+
+impl<M> Clone for Address<M> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+/// A spawned actor: its address plus read access to its current state
+pub struct Handle<A: Actor> {
+    address: Address<A::Msg>,
+    actor: Rc<RefCell<A>>,
+}
+
+impl<A: Actor> Handle<A> {
+    pub(super) fn new(address: Address<A::Msg>, actor: Rc<RefCell<A>>) -> Self {
+        Self { address, actor }
+    }
+
+    /// Queue a message for the actor
+    pub fn send(&self, msg: A::Msg) -> Result<(), RuntimeError> {
+        self.address.send(msg)
+    }
+
+    /// The actor's address, for handing to other actors or threads
+    pub fn address(&self) -> Address<A::Msg> {
+        self.address.clone()
+    }
+
+    /// Borrow the actor's current state
+    pub fn state(&self) -> Ref<'_, A> {
+        self.actor.borrow()
+    }
+}
+
+impl<A: Actor> Clone for Handle<A> {
+    fn clone(&self) -> Self {
+        Self {
+            address: self.address.clone(),
+            actor: Rc::clone(&self.actor),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! Message-passing runtime for riptide actors
+//!
+//! Each actor owns its state and a mailbox of typed messages. Actors talk to each other
+//! through [`Handle`]s, and a [`Dispatcher`] delivers queued messages until every mailbox
+//! is empty. New actors are added by implementing [`Actor`] and spawning them; nothing in
+//! the existing actors needs to change.
+
+mod dispatcher;
+mod mailbox;
+
+pub use dispatcher::Dispatcher;
+pub use mailbox::{Address, Handle, Mailbox};
+
+use thiserror::Error;
+
+/// An actor reacts to the messages delivered to its mailbox
+pub trait Actor: 'static {
+    type Msg: 'static;
+    type Error: std::error::Error + 'static;
+
+    /// Handle a single message, updating the actor's own state
+    fn handle(&mut self, msg: Self::Msg) -> Result<(), Self::Error>;
+}
+
+/// Errors
+#[derive(Debug, Error)]
+pub enum RuntimeError {
+    #[error("Actor `{actor}` failed: {source}")]
+    Actor {
+        actor: String,
+        source: Box<dyn std::error::Error>,
+    },
+
+    #[error("Mailbox closed: {0}")]
+    Closed(String),
+}
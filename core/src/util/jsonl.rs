@@ -0,0 +1,74 @@
+//! Append-only JSON-lines files, one per run, shared by the log and metric stores
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Append `item` as one line, creating the file and its directory as needed
+pub fn append<T: Serialize>(path: &Path, item: &T) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_string(item).map_err(io::Error::other)?;
+    line.push('\n');
+    // One write per line so concurrent writers never interleave within a line
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Parse the complete lines of `path` after `offset`, returning how many bytes were consumed.
+/// A line still being written is left for the next call.
+pub fn read_from<T: DeserializeOwned>(
+    path: &Path,
+    offset: u64,
+    mut on_item: impl FnMut(T),
+) -> io::Result<u64> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    file.seek(SeekFrom::Start(offset))?;
+
+    let mut reader = BufReader::new(file);
+    let mut consumed = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 || !line.ends_with('\n') {
+            break;
+        }
+        consumed += read as u64;
+        // Lines that do not parse, e.g. from a newer riptide, are skipped
+        if let Ok(item) = serde_json::from_str(&line) {
+            on_item(item);
+        }
+    }
+    Ok(consumed)
+}
+
+/// Run ids with a `<run id>.jsonl` file in `dir`, oldest first
+pub fn runs(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut runs: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            name.strip_suffix(".jsonl").map(str::to_string)
+        })
+        .collect();
+    // Run ids are timestamps, so shorter ids are older
+    runs.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    Ok(runs)
+}
@@ -0,0 +1,178 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::thread;
+
+/// Which output stream a line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Spawn `cmd` and hand each line it prints to `on_line` as it arrives
+pub fn run_streaming(
+    cmd: &mut Command,
+    on_line: impl FnMut(Stream, String),
+) -> io::Result<ExitStatus> {
+    run_streaming_with_input(cmd, None, on_line)
+}
+
+/// Like `run_streaming`, writing `input` to the child's stdin first when given
+pub fn run_streaming_with_input(
+    cmd: &mut Command,
+    input: Option<&[u8]>,
+    on_line: impl FnMut(Stream, String),
+) -> io::Result<ExitStatus> {
+    stream(cmd, input, |_| {}, on_line)
+}
+
+/// Like `run_streaming`, passing the child's process id to `on_spawn` once it started so
+/// another thread can `interrupt` it
+pub fn run_streaming_with_pid(
+    cmd: &mut Command,
+    on_spawn: impl FnOnce(u32),
+    on_line: impl FnMut(Stream, String),
+) -> io::Result<ExitStatus> {
+    stream(cmd, None, on_spawn, on_line)
+}
+
+/// Ask a child to stop as if Ctrl-C was pressed in its terminal. The child must lead its
+/// own process group, see `CommandExt::process_group`; the whole group is signalled, so
+/// the processes it started stop with it.
+#[cfg(unix)]
+pub fn interrupt(pid: u32) -> io::Result<()> {
+    let pid = libc::pid_t::try_from(pid).map_err(io::Error::other)?;
+    // SAFETY: killpg has no memory-safety preconditions
+    match unsafe { libc::killpg(pid, libc::SIGINT) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// Ask a child to stop; children share the console and already received Ctrl-C
+#[cfg(not(unix))]
+pub fn interrupt(_pid: u32) -> io::Result<()> {
+    Ok(())
+}
+
+fn stream(
+    cmd: &mut Command,
+    input: Option<&[u8]>,
+    on_spawn: impl FnOnce(u32),
+    mut on_line: impl FnMut(Stream, String),
+) -> io::Result<ExitStatus> {
+    if input.is_some() {
+        cmd.stdin(Stdio::piped());
+    }
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    on_spawn(child.id());
+
+    // Written from a thread so a child that prints before reading cannot deadlock us;
+    // children that never read their stdin are fine too
+    let writer = match (input, child.stdin.take()) {
+        (Some(input), Some(mut stdin)) => {
+            let input = input.to_vec();
+            Some(thread::spawn(move || {
+                let _ = stdin.write_all(&input);
+            }))
+        }
+        _ => None,
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|out| forward(out, Stream::Stdout, tx.clone())),
+        child
+            .stderr
+            .take()
+            .map(|err| forward(err, Stream::Stderr, tx.clone())),
+    ];
+    drop(tx);
+
+    for (stream, line) in rx {
+        on_line(stream, line);
+    }
+    for reader in readers.into_iter().flatten().chain(writer) {
+        let _ = reader.join();
+    }
+    child.wait()
+}
+
+fn forward(
+    pipe: impl Read + Send + 'static,
+    stream: Stream,
+    tx: mpsc::Sender<(Stream, String)>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            if tx.send((stream, line)).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_from_both_streams() {
+        let mut lines = Vec::new();
+        let status = run_streaming(
+            Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]),
+            |stream, line| lines.push((stream, line)),
+        )
+        .unwrap();
+
+        assert_eq!(status.code(), Some(3));
+        assert!(lines.contains(&(Stream::Stdout, "out".to_string())));
+        assert!(lines.contains(&(Stream::Stderr, "err".to_string())));
+    }
+
+    #[test]
+    fn test_input_is_written_to_stdin() {
+        let mut lines = Vec::new();
+        let status = run_streaming_with_input(
+            Command::new("sh").args(["-c", "read line; echo \"got $line\""]),
+            Some(b"payload\n"),
+            |_, line| lines.push(line),
+        )
+        .unwrap();
+
+        assert!(status.success());
+        assert_eq!(lines, vec!["got payload"]);
+    }
+
+    #[test]
+    fn test_interrupt_reaches_the_child_and_what_it_started() {
+        use std::os::unix::process::CommandExt;
+
+        let pid = std::cell::Cell::new(None);
+        let mut lines = Vec::new();
+        // The inner shell gives up after 5s should the interrupt not reach it
+        let inner = "trap 'echo inner caught; exit 7' INT; echo ready; \
+                     i=0; while [ $i -lt 100 ]; do sleep 0.05; i=$((i+1)); done; exit 1";
+        let outer = "trap 'echo outer caught' INT; sh -c \"$1\"; exit $?";
+        let status = run_streaming_with_pid(
+            Command::new("sh")
+                .args(["-c", outer, "sh", inner])
+                .process_group(0),
+            |id| pid.set(Some(id)),
+            |_, line| {
+                if line == "ready" {
+                    interrupt(pid.get().unwrap()).unwrap();
+                }
+                lines.push(line);
+            },
+        )
+        .unwrap();
+
+        assert_eq!(status.code(), Some(7));
+        assert_eq!(lines, vec!["ready", "inner caught", "outer caught"]);
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::{StageDef, WorkflowError};
+
+/// Indices of the stages each stage needs
+fn dependencies(stages: &[StageDef]) -> Result<Vec<Vec<usize>>, WorkflowError> {
+    stages
+        .iter()
+        .map(|stage| {
+            stage
+                .needs
+                .iter()
+                .map(|need| {
+                    stages.iter().position(|s| &s.name == need).ok_or_else(|| {
+                        WorkflowError::MissingDependency {
+                            stage: stage.name.clone(),
+                            missing: need.clone(),
+                        }
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Order stages so each runs after everything it needs, keeping file order where free
+pub fn topological_order(stages: &[StageDef]) -> Result<Vec<usize>, WorkflowError> {
+    let deps = dependencies(stages)?;
+    let mut order = Vec::with_capacity(stages.len());
+    let mut placed = vec![false; stages.len()];
+
+    while order.len() < stages.len() {
+        let ready = (0..stages.len())
+            .find(|&i| !placed[i] && deps[i].iter().all(|&d| placed[d]))
+            .ok_or_else(|| WorkflowError::Cycle(find_cycle(stages, &deps, &placed)))?;
+        placed[ready] = true;
+        order.push(ready);
+    }
+
+    Ok(order)
+}
+
+/// Walk dependencies among the unplaced stages until one repeats, naming the loop
+fn find_cycle(stages: &[StageDef], deps: &[Vec<usize>], placed: &[bool]) -> Vec<String> {
+    let Some(start) = (0..stages.len()).find(|&i| !placed[i]) else {
+        return Vec::new();
+    };
+    let mut path = vec![start];
+    loop {
+        let current = *path.last().unwrap_or(&start);
+        let Some(&next) = deps[current].iter().find(|&&d| !placed[d]) else {
+            return Vec::new();
+        };
+        if let Some(pos) = path.iter().position(|&p| p == next) {
+            let mut cycle: Vec<String> = path[pos..]
+                .iter()
+                .map(|&i| stages[i].name.clone())
+                .collect();
+            cycle.push(stages[next].name.clone());
+            // Report the loop in execution direction, dependency first
+            cycle.reverse();
+            return cycle;
+        }
+        path.push(next);
+    }
+}
+
+/// Every stage reachable by following `needs` from `stage`
+pub fn ancestors(stages: &[StageDef], stage: usize) -> HashSet<usize> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![stage];
+    while let Some(current) = stack.pop() {
+        for need in &stages[current].needs {
+            if let Some(dep) = stages.iter().position(|s| &s.name == need)
+                && seen.insert(dep)
+            {
+                stack.push(dep);
+            }
+        }
+    }
+    seen
+}
+
+/// Inputs must already exist under `base_dir` or be produced by an upstream stage
+pub fn check_inputs(stages: &[StageDef], base_dir: &Path) -> Result<(), WorkflowError> {
+    for (i, stage) in stages.iter().enumerate() {
+        let upstream = ancestors(stages, i);
+        for input in &stage.inputs {
+            let produced = upstream
+                .iter()
+                .any(|&dep| stages[dep].outputs.contains(input));
+            if !produced && !base_dir.join(input).exists() {
+                return Err(WorkflowError::MissingInput {
+                    stage: stage.name.clone(),
+                    input: input.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Condition;
+
+    fn stage(name: &str, needs: &[&str]) -> StageDef {
+        StageDef {
+            name: name.into(),
+            tool: None,
+            args: vec![],
+            run: Some("true".into()),
+            needs: needs.iter().map(|n| n.to_string()).collect(),
+            inputs: vec![],
+            outputs: vec![],
+            when: Condition::OnSuccess,
+        }
+    }
+
+    #[test]
+    fn test_order_respects_needs() {
+        let stages = vec![
+            stage("report", &["train", "evaluate"]),
+            stage("evaluate", &["train"]),
+            stage("clean", &[]),
+            stage("train", &["clean"]),
+        ];
+        let order = topological_order(&stages).unwrap();
+        let names: Vec<&str> = order.iter().map(|&i| stages[i].name.as_str()).collect();
+        assert_eq!(names, vec!["clean", "train", "evaluate", "report"]);
+    }
+
+    #[test]
+    fn test_cycle_is_named() {
+        let stages = vec![
+            stage("clean", &[]),
+            stage("a", &["clean", "c"]),
+            stage("b", &["a"]),
+            stage("c", &["b"]),
+        ];
+        match topological_order(&stages) {
+            Err(WorkflowError::Cycle(cycle)) => {
+                assert_eq!(cycle, vec!["a", "b", "c", "a"]);
+            }
+            other => panic!("expected a cycle, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_missing_dependency() {
+        let stages = vec![stage("train", &["clean"])];
+        assert!(matches!(
+            topological_order(&stages),
+            Err(WorkflowError::MissingDependency { .. })
+        ));
+    }
+
+    #[test]
+    fn test_inputs_must_be_produced_upstream() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("raw.csv"), "a\n1\n").unwrap();
+
+        let mut clean = stage("clean", &[]);
+        clean.inputs = vec!["raw.csv".into()];
+        clean.outputs = vec!["clean.csv".into()];
+        let mut train = stage("train", &["clean"]);
+        train.inputs = vec!["clean.csv".into()];
+        assert!(check_inputs(&[clean.clone(), train.clone()], dir.path()).is_ok());
+
+        // Without the dependency the output is not guaranteed to exist yet
+        train.needs.clear();
+        assert!(matches!(
+            check_inputs(&[clean, train], dir.path()),
+            Err(WorkflowError::MissingInput { .. })
+        ));
+    }
+}
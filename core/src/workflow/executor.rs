@@ -0,0 +1,863 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+
+use super::hooks::{HookEvent, HookPayload, Hooks, StagePayload};
+use super::{Condition, Pipeline, StageDef, WorkflowError};
+use crate::config;
+use crate::history::RunHistory;
+use crate::logging::{Level, LogRecord, LogStore};
+use crate::metrics::{MetricPoint, MetricStore};
+use crate::registry::ToolRegistry;
+use crate::util::process::{self, Stream};
+
+/// How a stage finished
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageStatus {
+    Succeeded,
+    /// Exited unsuccessfully; `None` when killed by a signal
+    Failed(Option<i32>),
+    /// The stage could not be started at all
+    Error(String),
+    /// Its `when` condition was not met, or the user skipped it
+    Skipped,
+    /// Never reached because the run was aborted
+    Aborted,
+}
+
+/// Outcome of one stage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageReport {
+    pub name: String,
+    pub status: StageStatus,
+    pub duration: Duration,
+}
+
+/// Outcome of a whole pipeline run, in execution order
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RunReport {
+    pub stages: Vec<StageReport>,
+}
+
+/// A line printed by a stage or one of its hooks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageOutput<'a> {
+    /// The stage, or the pipeline for `on_pipeline_end` hooks
+    pub stage: &'a str,
+    /// Set when a hook printed the line
+    pub hook: Option<HookEvent>,
+    pub stream: Stream,
+    pub line: &'a str,
+}
+
+/// Identity of one pipeline run, shared with stages and hooks through the environment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunContext {
+    pub run_id: String,
+    /// Where stages may leave files for later inspection
+    pub artifact_dir: PathBuf,
+    /// Where stage output and outcomes are logged; nothing is logged when unset
+    pub log: Option<LogStore>,
+    /// Where metrics printed by stages are kept; they are dropped when unset
+    pub metrics: Option<MetricStore>,
+    /// Where the run is recorded when it ends; it is forgotten when unset
+    pub history: Option<RunHistory>,
+}
+
+/// The next stage of a run, resolved and ready to execute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagePlan {
+    pub stage: String,
+    pub program: String,
+    pub args: Vec<String>,
+    /// Directory the command runs in
+    pub current_dir: PathBuf,
+    /// Declared inputs resolved against `current_dir`
+    pub inputs: Vec<PathBuf>,
+    /// False when the stage's `when` condition does not hold and it will be skipped
+    pub runnable: bool,
+    pub pipeline: String,
+    pub context: RunContext,
+    pub hooks: Hooks,
+}
+
+/// Runs the stages of a pipeline in dependency order, either all at once with `run`
+/// or one at a time with `plan` and `step`
+pub struct Executor<'a> {
+    pipeline: Pipeline,
+    context: RunContext,
+    /// Where the tools of `tool:` stages are looked up
+    registry: ToolRegistry,
+    on_output: Box<dyn FnMut(StageOutput) + 'a>,
+    /// Position of the next stage in execution order
+    next: usize,
+    report: RunReport,
+    /// When the first stage started
+    started: Option<DateTime<Utc>>,
+    /// Records the ended run in the history without holding up the caller
+    recording: Option<JoinHandle<()>>,
+    /// Times the run has ended; a stage retried after the end ends it again
+    endings: u32,
+}
+
+impl StageStatus {
+    pub fn succeeded(&self) -> bool {
+        *self == StageStatus::Succeeded
+    }
+
+    pub fn failed(&self) -> bool {
+        matches!(self, StageStatus::Failed(_) | StageStatus::Error(_))
+    }
+
+    /// One-word form used by hooks, e.g. `failed`
+    pub fn label(&self) -> &'static str {
+        match self {
+            StageStatus::Succeeded => "succeeded",
+            StageStatus::Failed(_) => "failed",
+            StageStatus::Error(_) => "error",
+            StageStatus::Skipped => "skipped",
+            StageStatus::Aborted => "aborted",
+        }
+    }
+
+    /// Exit code of the stage's command, when it exited
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            StageStatus::Succeeded => Some(0),
+            StageStatus::Failed(code) => *code,
+            _ => None,
+        }
+    }
+}
+
+impl RunReport {
+    /// True when no stage failed and the run was not aborted; skipped stages do not fail a run
+    pub fn succeeded(&self) -> bool {
+        !self
+            .stages
+            .iter()
+            .any(|s| s.status.failed() || s.status == StageStatus::Aborted)
+    }
+
+    pub fn status_of(&self, stage: &str) -> Option<&StageStatus> {
+        self.stages
+            .iter()
+            .find(|s| s.name == stage)
+            .map(|s| &s.status)
+    }
+
+    /// Names of the stages that failed
+    pub fn failed(&self) -> Vec<String> {
+        self.stages
+            .iter()
+            .filter(|s| s.status.failed())
+            .map(|s| s.name.clone())
+            .collect()
+    }
+}
+
+impl fmt::Display for StageStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageStatus::Succeeded => write!(f, "succeeded"),
+            StageStatus::Failed(Some(code)) => write!(f, "failed (exit {code})"),
+            StageStatus::Failed(None) => write!(f, "failed (killed)"),
+            StageStatus::Error(reason) => write!(f, "error: {reason}"),
+            StageStatus::Skipped => write!(f, "skipped"),
+            StageStatus::Aborted => write!(f, "aborted"),
+        }
+    }
+}
+
+impl fmt::Display for StageOutput<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.hook {
+            Some(hook) => write!(f, "[{} {hook}] {}", self.stage, self.line),
+            None => write!(f, "[{}] {}", self.stage, self.line),
+        }
+    }
+}
+
+impl RunContext {
+    /// A new run, identified by the milliseconds since the Unix epoch, with its
+    /// artifacts under `runs_dir()`
+    pub fn new() -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let run_id = millis.to_string();
+        Self {
+            artifact_dir: config::runs_dir().join(&run_id).join("artifacts"),
+            run_id,
+            log: None,
+            metrics: None,
+            history: None,
+        }
+    }
+
+    /// A new run whose logs, metrics and history are kept in riptide's stores
+    pub fn recorded() -> Self {
+        Self::new()
+            .with_log(LogStore::default())
+            .with_metrics(MetricStore::default())
+            .with_history(RunHistory::default())
+    }
+
+    /// Log the run to `store`
+    pub fn with_log(mut self, store: LogStore) -> Self {
+        self.log = Some(store);
+        self
+    }
+
+    /// Keep the run's metrics in `store`
+    pub fn with_metrics(mut self, store: MetricStore) -> Self {
+        self.metrics = Some(store);
+        self
+    }
+
+    /// Record the run in `history` when it ends
+    pub fn with_history(mut self, history: RunHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Create the artifact directory before the run starts
+    pub fn create_dirs(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.artifact_dir)
+    }
+
+    /// Append a record to the run's log, if it has one. Logging never fails a run.
+    pub fn log(&self, record: LogRecord) {
+        if let Some(store) = &self.log {
+            let _ = store.append(&record);
+        }
+    }
+
+    /// Log a line of stage or hook output
+    pub fn log_output(&self, out: &StageOutput) {
+        self.log_line(out.stage, out.hook, out.stream, out.line);
+    }
+
+    /// Keep a line printed by `stage`: a metric point when it reports one on stdout,
+    /// otherwise a log record
+    pub fn log_line(&self, stage: &str, hook: Option<HookEvent>, stream: Stream, line: &str) {
+        if let Some(store) = &self.metrics
+            && stream == Stream::Stdout
+            && let Some(point) = MetricPoint::parse(line, &self.run_id)
+        {
+            let _ = store.append(&point);
+            return;
+        }
+        if self.log.is_some() {
+            let record = LogRecord::from_line(&self.run_id, Some(stage), stream, line);
+            self.log(match hook {
+                Some(hook) => record.with_field("hook", hook.name()),
+                None => record,
+            });
+        }
+    }
+}
+
+impl Default for RunContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<20} {:<24} DURATION", "STAGE", "STATUS")?;
+        for stage in &self.stages {
+            writeln!(
+                f,
+                "{:<20} {:<24} {:.2}s",
+                stage.name,
+                stage.status.to_string(),
+                stage.duration.as_secs_f64()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl StagePlan {
+    /// The command as it would be typed in a shell
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Run the command between its hooks, handing every line printed to `on_output`
+    pub fn execute(&self, mut on_output: impl FnMut(StageOutput)) -> StageStatus {
+        let context = &self.context;
+        let mut on_output = |out: StageOutput| {
+            context.log_output(&out);
+            on_output(out)
+        };
+        context.log(
+            LogRecord::new(
+                &context.run_id,
+                Some(&self.stage),
+                Level::Info,
+                "stage started",
+            )
+            .with_field("command", self.command_line()),
+        );
+
+        let started = Instant::now();
+        let status = self.run_stage(&mut on_output);
+        let level = if status.failed() {
+            Level::Error
+        } else {
+            Level::Info
+        };
+        let mut record = LogRecord::new(
+            &context.run_id,
+            Some(&self.stage),
+            level,
+            format!("stage {status}"),
+        )
+        .with_field("status", status.label())
+        .with_field("duration_secs", started.elapsed().as_secs_f64());
+        if let Some(code) = status.exit_code() {
+            record = record.with_field("exit_code", code);
+        }
+        context.log(record);
+        status
+    }
+
+    fn run_stage(&self, on_output: &mut dyn FnMut(StageOutput)) -> StageStatus {
+        let started = Instant::now();
+        if let Err(status) = self.run_hooks(HookEvent::PreStage, None, on_output) {
+            return status;
+        }
+
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args)
+            .current_dir(&self.current_dir)
+            .env("RIPTIDE_PIPELINE", &self.pipeline)
+            .env("RIPTIDE_RUN_ID", &self.context.run_id)
+            .env("RIPTIDE_ARTIFACT_DIR", &self.context.artifact_dir)
+            .env("RIPTIDE_STAGE", &self.stage);
+
+        let result = process::run_streaming(&mut cmd, |stream, line| {
+            on_output(StageOutput {
+                stage: &self.stage,
+                hook: None,
+                stream,
+                line: &line,
+            })
+        });
+
+        let status = match result {
+            Ok(status) if status.success() => StageStatus::Succeeded,
+            Ok(status) => StageStatus::Failed(status.code()),
+            Err(e) => StageStatus::Error(format!("cannot run `{}`: {e}", self.program)),
+        };
+
+        // Failing post_stage or on_failure hooks are reported but do not change the outcome
+        let outcome = Some((&status, started.elapsed()));
+        let _ = self.run_hooks(HookEvent::PostStage, outcome, on_output);
+        if status.failed() {
+            let _ = self.run_hooks(HookEvent::OnFailure, outcome, on_output);
+        }
+        status
+    }
+
+    fn run_hooks(
+        &self,
+        event: HookEvent,
+        outcome: Option<(&StageStatus, Duration)>,
+        on_output: &mut dyn FnMut(StageOutput),
+    ) -> Result<(), StageStatus> {
+        if self.hooks.for_event(event).is_empty() {
+            return Ok(());
+        }
+        let mut payload = HookPayload::new(event, &self.pipeline, &self.context);
+        payload.status = outcome.map(|(status, _)| status.label());
+        payload.stage = Some(StagePayload {
+            name: self.stage.clone(),
+            command: std::iter::once(self.program.clone())
+                .chain(self.args.iter().cloned())
+                .collect(),
+            status: payload.status,
+            exit_code: outcome.and_then(|(status, _)| status.exit_code()),
+            duration_secs: outcome.map(|(_, duration)| duration.as_secs_f64()),
+        });
+        self.hooks
+            .run(&payload, &self.stage, &self.current_dir, on_output)
+    }
+}
+
+impl<'a> Executor<'a> {
+    /// An executor that prints stage output prefixed with the stage name
+    pub fn new(pipeline: Pipeline) -> Self {
+        Self::with_output(pipeline, |out| match out.stream {
+            Stream::Stdout => println!("{out}"),
+            Stream::Stderr => eprintln!("{out}"),
+        })
+    }
+
+    /// An executor that hands every line of stage output to `on_output`
+    pub fn with_output(pipeline: Pipeline, on_output: impl FnMut(StageOutput) + 'a) -> Self {
+        Self {
+            pipeline,
+            context: RunContext::new(),
+            registry: ToolRegistry::load(),
+            on_output: Box::new(on_output),
+            next: 0,
+            report: RunReport::default(),
+            started: None,
+            recording: None,
+            endings: 0,
+        }
+    }
+
+    /// Run under the given id and artifact directory instead of a fresh one
+    pub fn with_context(mut self, context: RunContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Look up the tools of `tool:` stages in `registry` instead of the default search path
+    pub fn with_registry(mut self, registry: ToolRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+
+    pub fn context(&self) -> &RunContext {
+        &self.context
+    }
+
+    /// Outcomes of the stages handled so far
+    pub fn report(&self) -> &RunReport {
+        &self.report
+    }
+
+    /// True once every stage has been run, skipped or aborted
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.pipeline.def.stages.len()
+    }
+
+    /// Execute every remaining stage whose condition holds and report how each finished
+    pub fn run(&mut self) -> RunReport {
+        while self.step().is_some() {}
+        self.wait_recorded();
+        self.report.clone()
+    }
+
+    /// Wait until the run has been recorded in the history
+    pub fn wait_recorded(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            let _ = recording.join();
+        }
+    }
+
+    /// The next stage with its command line and inputs resolved, `None` once finished
+    pub fn plan(&self) -> Option<StagePlan> {
+        let stage = self.pipeline.stages().nth(self.next)?;
+        let (mut program, args) = stage.command_line();
+        // Like `riptide run`, a registered tool runs its entry binary; any other name is
+        // looked up on `PATH`
+        if let Some(entry) = stage
+            .tool
+            .as_deref()
+            .and_then(|tool| self.registry.get(tool))
+            .and_then(|tool| tool.entry_path())
+        {
+            program = entry.display().to_string();
+        }
+        let current_dir = self.pipeline.base_dir.clone();
+        Some(StagePlan {
+            stage: stage.name.clone(),
+            program,
+            args,
+            inputs: stage.inputs.iter().map(|i| current_dir.join(i)).collect(),
+            current_dir,
+            runnable: should_run(stage, &self.report),
+            pipeline: self.pipeline.name().to_string(),
+            context: self.context.clone(),
+            hooks: self.pipeline.def.hooks.clone(),
+        })
+    }
+
+    /// Run the next stage, or skip it when its condition does not hold
+    pub fn step(&mut self) -> Option<&StageReport> {
+        let plan = self.plan()?;
+        let started = Instant::now();
+        let status = if plan.runnable {
+            plan.execute(&mut self.on_output)
+        } else {
+            StageStatus::Skipped
+        };
+        self.record(status, started.elapsed())
+    }
+
+    /// Record the outcome of the next stage after executing its plan elsewhere,
+    /// e.g. on a worker thread
+    pub fn record(&mut self, status: StageStatus, duration: Duration) -> Option<&StageReport> {
+        let name = self.pipeline.stages().nth(self.next)?.name.clone();
+        self.started.get_or_insert_with(|| Utc::now() - duration);
+        if matches!(status, StageStatus::Skipped | StageStatus::Aborted) {
+            self.context.log(
+                LogRecord::new(
+                    &self.context.run_id,
+                    Some(&name),
+                    Level::Info,
+                    format!("stage {status}"),
+                )
+                .with_field("status", status.label()),
+            );
+        }
+        self.next += 1;
+        self.report.stages.push(StageReport {
+            name,
+            status,
+            duration,
+        });
+        if self.is_finished() {
+            self.end_run();
+        }
+        self.report.stages.last()
+    }
+
+    /// Log and record the outcome of the run and run the `on_pipeline_end` hooks, each
+    /// time the run ends
+    fn end_run(&mut self) {
+        self.endings += 1;
+        let succeeded = self.report.succeeded();
+        self.context.log(
+            LogRecord::new(
+                &self.context.run_id,
+                None,
+                if succeeded { Level::Info } else { Level::Error },
+                format!(
+                    "pipeline `{}` {}",
+                    self.pipeline.name(),
+                    if succeeded { "succeeded" } else { "failed" }
+                ),
+            )
+            .with_field("attempt", self.endings),
+        );
+        // Like logging, recording the run never fails it. Opening the database can take
+        // seconds, so the run is recorded on a thread of its own, after any earlier
+        // recording of the same run when a stage was retried.
+        if let Some(history) = self.context.history.clone() {
+            let started = self.started.unwrap_or_else(Utc::now);
+            let (pipeline, report, context) = (
+                self.pipeline.clone(),
+                self.report.clone(),
+                self.context.clone(),
+            );
+            let previous = self.recording.take();
+            self.recording = Some(thread::spawn(move || {
+                if let Some(previous) = previous {
+                    let _ = previous.join();
+                }
+                if let Err(e) = history.record(&pipeline, &report, &context, started) {
+                    context.log(LogRecord::new(
+                        &context.run_id,
+                        None,
+                        Level::Warn,
+                        format!("cannot record run: {e}"),
+                    ));
+                }
+            }));
+        }
+
+        let hooks = &self.pipeline.def.hooks;
+        if hooks.on_pipeline_end.is_empty() {
+            return;
+        }
+        let name = self.pipeline.name();
+        let mut payload = HookPayload::new(HookEvent::OnPipelineEnd, name, &self.context);
+        payload.stages = self.report.stages.iter().map(StagePayload::from).collect();
+        payload.status = Some(if succeeded { "succeeded" } else { "failed" });
+        payload.attempt = Some(self.endings);
+        let context = &self.context;
+        let on_output = &mut self.on_output;
+        let _ = hooks.run(&payload, name, &self.pipeline.base_dir, &mut |out| {
+            context.log_output(&out);
+            on_output(out)
+        });
+    }
+
+    /// Skip the next stage without running it
+    pub fn skip(&mut self) -> Option<&StageReport> {
+        self.record(StageStatus::Skipped, Duration::ZERO)
+    }
+
+    /// Forget the outcome of the last stage that ran so it becomes the next one again,
+    /// together with those of the stages skipped or aborted after it, which follow it again
+    pub fn rewind(&mut self) -> bool {
+        let ran = self
+            .report
+            .stages
+            .iter()
+            .rposition(|s| !matches!(s.status, StageStatus::Skipped | StageStatus::Aborted))
+            .unwrap_or(0);
+        if ran >= self.report.stages.len() {
+            return false;
+        }
+        self.report.stages.truncate(ran);
+        self.next = ran;
+        true
+    }
+
+    /// Mark every remaining stage as aborted
+    pub fn abort(&mut self) {
+        while self.record(StageStatus::Aborted, Duration::ZERO).is_some() {}
+    }
+
+    /// Replace the parameters of the next stage: the arguments of a tool, or the
+    /// script of a `run` stage
+    pub fn edit(&mut self, params: &str) -> Result<(), WorkflowError> {
+        let Some(name) = self.plan().map(|plan| plan.stage) else {
+            return Ok(());
+        };
+        match self.pipeline.def.stages.iter_mut().find(|s| s.name == name) {
+            Some(stage) => stage.set_params(params),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Executor<'_> {
+    /// Finish recording the run rather than lose it when the process exits
+    fn drop(&mut self) {
+        self.wait_recorded();
+    }
+}
+
+/// Decide from the dependencies' outcomes whether a stage's `when` condition holds
+fn should_run(stage: &StageDef, report: &RunReport) -> bool {
+    let statuses: Vec<&StageStatus> = stage
+        .needs
+        .iter()
+        .filter_map(|need| report.status_of(need))
+        .collect();
+
+    match stage.when {
+        Condition::OnSuccess => statuses.iter().all(|s| s.succeeded()),
+        Condition::OnFailure => statuses.iter().any(|s| s.failed()),
+        Condition::Always => true,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn pipeline(source: &str) -> Pipeline {
+        Pipeline::from_yaml(source, Path::new(".")).unwrap()
+    }
+
+    #[test]
+    fn test_conditions() {
+        let pipeline = pipeline(
+            r#"
+name: conditions
+stages:
+  - name: ok
+    run: "true"
+  - name: broken
+    run: exit 4
+    needs: [ok]
+  - name: after_broken
+    run: "true"
+    needs: [broken]
+  - name: rescue
+    run: "true"
+    needs: [broken]
+    when: on_failure
+  - name: no_rescue
+    run: "true"
+    needs: [ok]
+    when: on_failure
+  - name: cleanup
+    run: "true"
+    needs: [after_broken]
+    when: always
+"#,
+        );
+
+        let report = Executor::with_output(pipeline, |_| {}).run();
+        assert_eq!(report.status_of("ok"), Some(&StageStatus::Succeeded));
+        assert_eq!(
+            report.status_of("broken"),
+            Some(&StageStatus::Failed(Some(4)))
+        );
+        assert_eq!(
+            report.status_of("after_broken"),
+            Some(&StageStatus::Skipped)
+        );
+        assert_eq!(report.status_of("rescue"), Some(&StageStatus::Succeeded));
+        assert_eq!(report.status_of("no_rescue"), Some(&StageStatus::Skipped));
+        assert_eq!(report.status_of("cleanup"), Some(&StageStatus::Succeeded));
+        assert_eq!(report.failed(), vec!["broken"]);
+        assert!(!report.succeeded());
+    }
+
+    #[test]
+    fn test_output_is_attributed_to_stages() {
+        let pipeline = pipeline(
+            r#"
+name: output
+stages:
+  - name: first
+    run: echo one
+  - name: second
+    run: echo two >&2
+    needs: [first]
+"#,
+        );
+
+        let mut lines = Vec::new();
+        let report = Executor::with_output(pipeline, |out| {
+            lines.push((out.stage.to_string(), out.stream, out.line.to_string()))
+        })
+        .run();
+
+        assert!(report.succeeded());
+        assert_eq!(
+            lines,
+            vec![
+                ("first".to_string(), Stream::Stdout, "one".to_string()),
+                ("second".to_string(), Stream::Stderr, "two".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_tool_is_an_error() {
+        let pipeline =
+            pipeline("name: missing\nstages:\n  - name: train\n    tool: riptide-no-such-tool\n");
+        let report = Executor::with_output(pipeline, |_| {}).run();
+        assert!(matches!(
+            report.status_of("train"),
+            Some(StageStatus::Error(_))
+        ));
+        assert!(report.to_string().contains("train"));
+    }
+
+    #[test]
+    fn test_tool_stages_run_installed_tools() {
+        use crate::installer::{InstallOptions, Installer};
+        use std::os::unix::fs::PermissionsExt;
+
+        let source = tempfile::tempdir().unwrap();
+        let prefix = tempfile::tempdir().unwrap();
+        fs::create_dir_all(source.path().join("bin")).unwrap();
+        fs::write(
+            source.path().join(crate::registry::MANIFEST),
+            "name = \"trawl\"\nversion = \"0.1.0\"\nentry = \"bin/trawl\"\n",
+        )
+        .unwrap();
+        let script = source.path().join("bin").join("trawl");
+        fs::write(&script, "#!/bin/sh\necho \"trawling $1\"\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        Installer::new(prefix.path())
+            .install(source.path().to_str().unwrap(), &InstallOptions::default())
+            .unwrap();
+
+        // Not on PATH: only the registry knows where the tool was installed
+        let pipeline = pipeline(
+            "name: installed\nstages:\n  - name: fish\n    tool: trawl\n    args: [net]\n",
+        );
+        let mut lines = Vec::new();
+        let mut executor = Executor::with_output(pipeline, |out| lines.push(out.to_string()))
+            .with_registry(ToolRegistry::discover(&[prefix.path().to_path_buf()]));
+        let entry = prefix.path().join("trawl").join("bin").join("trawl");
+        assert_eq!(
+            executor.plan().unwrap().program,
+            entry.display().to_string()
+        );
+        let report = executor.run();
+        drop(executor);
+
+        assert!(report.succeeded(), "{report}");
+        assert_eq!(lines, vec!["[fish] trawling net"]);
+    }
+
+    #[test]
+    fn test_stepping() {
+        let pipeline = pipeline(
+            r#"
+name: stepping
+stages:
+  - name: prepare
+    run: exit 1
+    inputs: [Cargo.toml]
+  - name: train
+    run: "true"
+    needs: [prepare]
+  - name: report
+    run: "true"
+    needs: [train]
+"#,
+        );
+
+        let mut executor = Executor::with_output(pipeline, |_| {});
+        let plan = executor.plan().unwrap();
+        assert_eq!(plan.stage, "prepare");
+        assert_eq!(plan.command_line(), "sh -c exit 1");
+        assert_eq!(plan.inputs, vec![Path::new(".").join("Cargo.toml")]);
+        assert!(plan.runnable);
+
+        let failed = executor.step().unwrap();
+        assert_eq!(failed.status, StageStatus::Failed(Some(1)));
+        assert!(!executor.plan().unwrap().runnable);
+
+        // Retry the failed stage with edited parameters
+        assert!(executor.rewind());
+        executor.edit("echo fixed").unwrap();
+        assert_eq!(executor.plan().unwrap().args, vec!["-c", "echo fixed"]);
+        assert!(executor.step().unwrap().status.succeeded());
+
+        executor.skip();
+        executor.abort();
+        assert!(executor.is_finished());
+        assert!(executor.plan().is_none());
+        assert_eq!(
+            executor.report().status_of("train"),
+            Some(&StageStatus::Skipped)
+        );
+        assert_eq!(
+            executor.report().status_of("report"),
+            Some(&StageStatus::Aborted)
+        );
+        assert!(!executor.report().succeeded());
+
+        // Retrying after the abort resumes at the last stage that ran, and the stages
+        // after it run again in order
+        assert!(executor.rewind());
+        assert!(executor.report().stages.is_empty());
+        assert_eq!(executor.plan().unwrap().stage, "prepare");
+        let report = executor.run();
+        let order: Vec<&str> = report.stages.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(order, ["prepare", "train", "report"]);
+        assert!(report.succeeded());
+
+        // Nothing ran yet: rewinding an aborted run starts it over
+        let mut aborted = Executor::with_output(executor.pipeline().clone(), |_| {});
+        assert!(!aborted.rewind());
+        aborted.abort();
+        assert!(aborted.rewind());
+        assert_eq!(aborted.plan().unwrap().stage, "prepare");
+        assert!(aborted.report().stages.is_empty());
+    }
+}
@@ -0,0 +1,381 @@
+//! Hooks run around stages (FR-ORCH-04)
+//!
+//! ```yaml
+//! hooks:
+//!   pre_stage: [./hooks/check-disk.sh]
+//!   post_stage:
+//!     - command: target/release/notify
+//!       args: [--channel, ml]
+//!   on_failure: ['echo "$RIPTIDE_STAGE failed" >> failures.txt']
+//!   on_pipeline_end: [./hooks/summary.sh]
+//! ```
+//!
+//! A hook written as a string runs through the shell; a `command` runs directly, so a
+//! compiled Rust binary needs no shell. Hooks run in the pipeline's directory with:
+//!
+//! | Variable               | Value                                                        |
+//! |------------------------|--------------------------------------------------------------|
+//! | `RIPTIDE_HOOK`         | `pre_stage`, `post_stage`, `on_failure` or `on_pipeline_end` |
+//! | `RIPTIDE_PIPELINE`     | Pipeline name                                                |
+//! | `RIPTIDE_RUN_ID`       | Id of the run                                                |
+//! | `RIPTIDE_ARTIFACT_DIR` | Directory for the run's artifacts                            |
+//! | `RIPTIDE_STAGE`        | Stage name; unset for `on_pipeline_end`                      |
+//! | `RIPTIDE_STATUS`       | `succeeded`, `failed`, `error`, `skipped` or `aborted`; unset for `pre_stage` |
+//! | `RIPTIDE_EXIT_STATUS`  | Exit code of the stage, when it exited                       |
+//! | `RIPTIDE_ATTEMPT`      | For `on_pipeline_end`, how many times the run has ended      |
+//!
+//! A run retried from the Workflow view after it ended ends again, so its
+//! `on_pipeline_end` hooks run once per ending; `RIPTIDE_ATTEMPT` is 1 the first time.
+//! The same information arrives as a JSON [`HookPayload`] on stdin. Hook output joins the
+//! stage's output. A failing `pre_stage` hook stops the stage, which is then reported as an
+//! error; failures of the other hooks are only reported in the output.
+
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{RunContext, StageOutput, StageReport, StageStatus};
+use crate::util::process::{self, Stream};
+
+/// When a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// Before a stage's command starts
+    PreStage,
+    /// After a stage's command finished, whatever the outcome
+    PostStage,
+    /// After a stage failed, following its `post_stage` hooks
+    OnFailure,
+    /// Once every stage has finished, and again whenever a retried stage ends the run anew
+    OnPipelineEnd,
+}
+
+/// A user script or binary run by a hook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Hook {
+    /// Shell script, run with `sh -c`
+    Script(String),
+    /// Program run directly with its arguments
+    Program {
+        command: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+    },
+}
+
+/// The `hooks` section of a pipeline file
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_stage: Vec<Hook>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_stage: Vec<Hook>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<Hook>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_pipeline_end: Vec<Hook>,
+}
+
+/// JSON document written to a hook's stdin
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HookPayload {
+    pub hook: HookEvent,
+    pub pipeline: String,
+    pub run_id: String,
+    pub artifact_dir: String,
+    /// The stage the hook runs for; absent for `on_pipeline_end`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<StagePayload>,
+    /// Every stage of the run, for `on_pipeline_end`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<StagePayload>,
+    /// Outcome of the stage or, for `on_pipeline_end`, of the whole run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<&'static str>,
+    /// For `on_pipeline_end`, how many times the run has ended, counting this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempt: Option<u32>,
+}
+
+/// A stage as described to hooks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StagePayload {
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub command: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+}
+
+impl HookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::PreStage => "pre_stage",
+            HookEvent::PostStage => "post_stage",
+            HookEvent::OnFailure => "on_failure",
+            HookEvent::OnPipelineEnd => "on_pipeline_end",
+        }
+    }
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Hook {
+    /// Program and arguments the hook executes
+    pub fn command_line(&self) -> (String, Vec<String>) {
+        match self {
+            Hook::Script(script) => super::shell_command(script),
+            Hook::Program { command, args } => (command.clone(), args.clone()),
+        }
+    }
+
+    /// Run the hook in `current_dir`, forwarding its output
+    pub fn run(
+        &self,
+        payload: &HookPayload,
+        current_dir: &Path,
+        mut on_line: impl FnMut(Stream, String),
+    ) -> StageStatus {
+        let (program, args) = self.command_line();
+        let mut cmd = Command::new(&program);
+        cmd.args(&args).current_dir(current_dir).envs(payload.env());
+
+        let input = serde_json::to_vec(payload).unwrap_or_default();
+        match process::run_streaming_with_input(&mut cmd, Some(&input), &mut on_line) {
+            Ok(status) if status.success() => StageStatus::Succeeded,
+            Ok(status) => StageStatus::Failed(status.code()),
+            Err(e) => StageStatus::Error(format!("cannot run `{program}`: {e}")),
+        }
+    }
+}
+
+impl fmt::Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hook::Script(script) => f.write_str(script),
+            Hook::Program { command, .. } => f.write_str(command),
+        }
+    }
+}
+
+impl Hooks {
+    pub fn for_event(&self, event: HookEvent) -> &[Hook] {
+        match event {
+            HookEvent::PreStage => &self.pre_stage,
+            HookEvent::PostStage => &self.post_stage,
+            HookEvent::OnFailure => &self.on_failure,
+            HookEvent::OnPipelineEnd => &self.on_pipeline_end,
+        }
+    }
+
+    /// Run the hooks of `payload.hook` in order, stopping at the first that fails.
+    ///
+    /// Output is attributed to `source`: the stage name, or the pipeline name at the end.
+    pub fn run(
+        &self,
+        payload: &HookPayload,
+        source: &str,
+        current_dir: &Path,
+        on_output: &mut dyn FnMut(StageOutput),
+    ) -> Result<(), StageStatus> {
+        let event = payload.hook;
+        for hook in self.for_event(event) {
+            let status = hook.run(payload, current_dir, |stream, line| {
+                on_output(StageOutput {
+                    stage: source,
+                    hook: Some(event),
+                    stream,
+                    line: &line,
+                })
+            });
+            if !status.succeeded() {
+                let status = StageStatus::Error(format!("{event} hook `{hook}` {status}"));
+                on_output(StageOutput {
+                    stage: source,
+                    hook: Some(event),
+                    stream: Stream::Stderr,
+                    line: &status.to_string(),
+                });
+                return Err(status);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl HookPayload {
+    pub fn new(event: HookEvent, pipeline: &str, context: &RunContext) -> Self {
+        Self {
+            hook: event,
+            pipeline: pipeline.to_string(),
+            run_id: context.run_id.clone(),
+            artifact_dir: context.artifact_dir.display().to_string(),
+            stage: None,
+            stages: Vec::new(),
+            status: None,
+            attempt: None,
+        }
+    }
+
+    /// Environment variables documented for hooks
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![
+            ("RIPTIDE_HOOK", self.hook.name().to_string()),
+            ("RIPTIDE_PIPELINE", self.pipeline.clone()),
+            ("RIPTIDE_RUN_ID", self.run_id.clone()),
+            ("RIPTIDE_ARTIFACT_DIR", self.artifact_dir.clone()),
+        ];
+        if let Some(stage) = &self.stage {
+            env.push(("RIPTIDE_STAGE", stage.name.clone()));
+            if let Some(code) = stage.exit_code {
+                env.push(("RIPTIDE_EXIT_STATUS", code.to_string()));
+            }
+        }
+        if let Some(status) = self.status {
+            env.push(("RIPTIDE_STATUS", status.to_string()));
+        }
+        if let Some(attempt) = self.attempt {
+            env.push(("RIPTIDE_ATTEMPT", attempt.to_string()));
+        }
+        env
+    }
+}
+
+impl From<&StageReport> for StagePayload {
+    fn from(report: &StageReport) -> Self {
+        Self {
+            name: report.name.clone(),
+            command: Vec::new(),
+            status: Some(report.status.label()),
+            exit_code: report.status.exit_code(),
+            duration_secs: Some(report.duration.as_secs_f64()),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::workflow::{Executor, Pipeline};
+
+    #[test]
+    fn test_parse_hooks() {
+        let hooks: Hooks = serde_yaml::from_str(
+            "pre_stage: [./check.sh]\npost_stage:\n  - command: notify\n    args: [--quiet]\n",
+        )
+        .unwrap();
+        assert_eq!(hooks.pre_stage, vec![Hook::Script("./check.sh".into())]);
+        assert_eq!(
+            hooks.for_event(HookEvent::PostStage)[0].command_line(),
+            ("notify".to_string(), vec!["--quiet".to_string()])
+        );
+        assert!(hooks.on_pipeline_end.is_empty());
+        assert!(serde_yaml::from_str::<Hooks>("before: [x]").is_err());
+    }
+
+    #[test]
+    fn test_hooks_see_environment_and_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let pipeline = Pipeline::from_yaml(
+            r#"
+name: hooked
+hooks:
+  pre_stage: ['echo "pre $RIPTIDE_STAGE $RIPTIDE_RUN_ID"']
+  post_stage: ['echo "post $RIPTIDE_STAGE $RIPTIDE_STATUS $RIPTIDE_EXIT_STATUS"']
+  on_failure: ['grep -o "\"exit_code\":[0-9]*"']
+  on_pipeline_end: ['echo "end $RIPTIDE_STATUS"; test -n "$RIPTIDE_ARTIFACT_DIR"']
+stages:
+  - name: fit
+    run: exit 2
+"#,
+            dir.path(),
+        )
+        .unwrap();
+
+        let mut lines = Vec::new();
+        let report = Executor::with_output(pipeline, |out| lines.push(out.to_string()))
+            .with_context(RunContext {
+                run_id: "run-1".into(),
+                artifact_dir: dir.path().join("artifacts"),
+                log: None,
+                metrics: None,
+                history: None,
+            })
+            .run();
+
+        assert_eq!(report.status_of("fit"), Some(&StageStatus::Failed(Some(2))));
+        assert_eq!(
+            lines,
+            vec![
+                "[fit pre_stage] pre fit run-1",
+                "[fit post_stage] post fit failed 2",
+                "[fit on_failure] \"exit_code\":2",
+                "[hooked on_pipeline_end] end failed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retried_run_ends_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let pipeline = Pipeline::from_yaml(
+            r#"
+name: retried
+hooks:
+  on_pipeline_end: ['echo "end $RIPTIDE_STATUS $RIPTIDE_ATTEMPT"']
+stages:
+  - name: fit
+    run: exit 1
+"#,
+            dir.path(),
+        )
+        .unwrap();
+
+        let mut lines = Vec::new();
+        let mut executor = Executor::with_output(pipeline, |out| lines.push(out.to_string()));
+        executor.step();
+        assert!(executor.is_finished());
+        assert!(executor.rewind());
+        executor.edit("true").unwrap();
+        executor.step();
+        drop(executor);
+
+        assert_eq!(
+            lines,
+            vec![
+                "[retried on_pipeline_end] end failed 1",
+                "[retried on_pipeline_end] end succeeded 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failing_pre_stage_hook_stops_the_stage() {
+        let dir = tempfile::tempdir().unwrap();
+        let pipeline = Pipeline::from_yaml(
+            "name: gated\nhooks:\n  pre_stage: ['exit 1']\nstages:\n  - name: fit\n    run: touch ran\n",
+            dir.path(),
+        )
+        .unwrap();
+
+        let report = Executor::with_output(pipeline, |_| {}).run();
+        assert!(matches!(
+            report.status_of("fit"),
+            Some(StageStatus::Error(reason)) if reason.contains("pre_stage hook `exit 1`")
+        ));
+        assert!(!dir.path().join("ran").exists());
+    }
+}
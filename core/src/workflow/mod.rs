@@ -0,0 +1,324 @@
+//! YAML pipeline definitions and their execution (FR-ORCH-01/02, FUT-01)
+//!
+//! ```yaml
+//! name: churn
+//! stages:
+//!   - name: clean
+//!     tool: dock
+//!     args: [clean, data/raw.csv, --out, data/clean.csv]
+//!     inputs: [data/raw.csv]
+//!     outputs: [data/clean.csv]
+//!   - name: train
+//!     tool: sail
+//!     args: [train, data/clean.csv]
+//!     needs: [clean]
+//!     inputs: [data/clean.csv]
+//!   - name: cleanup
+//!     run: rm -rf tmp/
+//!     needs: [train]
+//!     when: always
+//! ```
+//!
+//! Scripts can also run around every stage; see [`hooks`].
+
+pub mod dag;
+pub mod executor;
+pub mod hooks;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub use executor::{Executor, RunContext, RunReport, StagePlan, StageReport, StageStatus};
+pub use hooks::{Hook, HookEvent, Hooks};
+
+/// When a stage runs, based on how the stages it `needs` finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// Every dependency succeeded
+    #[default]
+    OnSuccess,
+    /// At least one dependency failed
+    OnFailure,
+    /// Once every dependency has finished, whatever the outcome
+    Always,
+}
+
+/// A stage as written in the pipeline file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StageDef {
+    pub name: String,
+    /// Tool to invoke, e.g. `dock`, `sail` or `surf`: a registered tool, else a program on `PATH`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    /// Arguments passed to `tool`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Shell command to run instead of a tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<String>,
+    /// Stages that must finish before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub needs: Vec<String>,
+    /// Files the stage reads; each must exist or be an output of an upstream stage
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<String>,
+    /// Files the stage produces
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<String>,
+    #[serde(default)]
+    pub when: Condition,
+}
+
+/// A pipeline file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineDef {
+    pub name: String,
+    /// Scripts run around every stage and at the end of the run
+    #[serde(default)]
+    pub hooks: Hooks,
+    #[serde(default)]
+    pub stages: Vec<StageDef>,
+}
+
+/// Errors
+#[derive(Debug, Error)]
+pub enum WorkflowError {
+    #[error("Cannot read pipeline {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+
+    #[error("Invalid pipeline definition: {0}")]
+    Parse(#[from] serde_yaml::Error),
+
+    #[error("Pipeline has no stages")]
+    Empty,
+
+    #[error("Stage `{0}` is defined more than once")]
+    DuplicateStage(String),
+
+    #[error("Stage `{0}` must set exactly one of `tool` or `run`")]
+    InvalidCommand(String),
+
+    #[error("Cannot split the arguments of stage `{stage}`, a quote is not closed: {params}")]
+    InvalidParams { stage: String, params: String },
+
+    #[error("Stage `{stage}` needs unknown stage `{missing}`")]
+    MissingDependency { stage: String, missing: String },
+
+    #[error("Stages form a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+
+    #[error("Input `{input}` of stage `{stage}` does not exist and no upstream stage produces it")]
+    MissingInput { stage: String, input: String },
+
+    #[error("Cannot create artifact directory {0}: {1}")]
+    ArtifactDir(PathBuf, std::io::Error),
+}
+
+/// A validated pipeline, ready to execute
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    pub def: PipelineDef,
+    /// Directory stage commands run in and inputs are resolved against
+    pub base_dir: PathBuf,
+    /// Stage indices in execution order
+    order: Vec<usize>,
+}
+
+impl StageDef {
+    /// Program and arguments the stage executes
+    pub fn command_line(&self) -> (String, Vec<String>) {
+        match (&self.tool, &self.run) {
+            (Some(tool), _) => (tool.clone(), self.args.clone()),
+            (None, Some(script)) => shell_command(script),
+            (None, None) => (String::new(), Vec::new()),
+        }
+    }
+
+    /// What a user may edit before the stage runs: the tool's arguments, quoted like shell
+    /// words where needed, or the script
+    pub fn params(&self) -> String {
+        match &self.run {
+            Some(script) if self.tool.is_none() => script.clone(),
+            // Only an argument holding a nul byte cannot be quoted
+            _ => shlex::try_join(self.args.iter().map(String::as_str))
+                .unwrap_or_else(|_| self.args.join(" ")),
+        }
+    }
+
+    /// Replace `params`; tool arguments are split like shell words, so `--name "a b"` is
+    /// two arguments
+    pub fn set_params(&mut self, params: &str) -> Result<(), WorkflowError> {
+        if self.tool.is_none() && self.run.is_some() {
+            self.run = Some(params.to_string());
+        } else {
+            self.args = shlex::split(params).ok_or_else(|| WorkflowError::InvalidParams {
+                stage: self.name.clone(),
+                params: params.to_string(),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Program and arguments that run `script` through the platform shell
+fn shell_command(script: &str) -> (String, Vec<String>) {
+    if cfg!(windows) {
+        ("cmd".into(), vec!["/C".into(), script.to_string()])
+    } else {
+        ("sh".into(), vec!["-c".into(), script.to_string()])
+    }
+}
+
+impl Pipeline {
+    /// Read and validate a pipeline file
+    pub fn load(path: &Path) -> Result<Self, WorkflowError> {
+        let source =
+            fs::read_to_string(path).map_err(|e| WorkflowError::Io(path.to_path_buf(), e))?;
+        let base_dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        Self::from_yaml(&source, base_dir)
+    }
+
+    /// Parse and validate a pipeline whose relative paths are resolved against `base_dir`
+    pub fn from_yaml(source: &str, base_dir: &Path) -> Result<Self, WorkflowError> {
+        let def: PipelineDef = serde_yaml::from_str(source)?;
+        Self::new(def, base_dir)
+    }
+
+    /// Validate a definition: unique names, one command per stage, known dependencies,
+    /// no cycles and every input available
+    pub fn new(def: PipelineDef, base_dir: &Path) -> Result<Self, WorkflowError> {
+        if def.stages.is_empty() {
+            return Err(WorkflowError::Empty);
+        }
+        for (i, stage) in def.stages.iter().enumerate() {
+            if def.stages[..i].iter().any(|s| s.name == stage.name) {
+                return Err(WorkflowError::DuplicateStage(stage.name.clone()));
+            }
+            if stage.tool.is_some() == stage.run.is_some() {
+                return Err(WorkflowError::InvalidCommand(stage.name.clone()));
+            }
+        }
+
+        let order = dag::topological_order(&def.stages)?;
+        dag::check_inputs(&def.stages, base_dir)?;
+
+        Ok(Self {
+            def,
+            base_dir: base_dir.to_path_buf(),
+            order,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.def.name
+    }
+
+    /// Stages in the order they execute
+    pub fn stages(&self) -> impl Iterator<Item = &StageDef> {
+        self.order.iter().map(|i| &self.def.stages[*i])
+    }
+
+    pub fn stage(&self, name: &str) -> Option<&StageDef> {
+        self.def.stages.iter().find(|s| s.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIPELINE: &str = r#"
+name: churn
+stages:
+  - name: train
+    tool: sail
+    args: [train, clean.csv]
+    needs: [clean]
+    inputs: [clean.csv]
+  - name: clean
+    tool: dock
+    args: [clean, raw.csv]
+    outputs: [clean.csv]
+  - name: notify
+    run: echo done
+    needs: [train]
+    when: always
+"#;
+
+    fn parse(source: &str) -> Result<Pipeline, WorkflowError> {
+        Pipeline::from_yaml(source, Path::new("."))
+    }
+
+    #[test]
+    fn test_parse_and_order() {
+        let pipeline = parse(PIPELINE).unwrap();
+        let names: Vec<&str> = pipeline.stages().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["clean", "train", "notify"]);
+
+        let notify = pipeline.stage("notify").unwrap();
+        assert_eq!(notify.when, Condition::Always);
+        if cfg!(unix) {
+            assert_eq!(
+                notify.command_line(),
+                (
+                    "sh".to_string(),
+                    vec!["-c".to_string(), "echo done".to_string()]
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_stages() {
+        assert!(matches!(
+            parse("name: x\nstages: []"),
+            Err(WorkflowError::Empty)
+        ));
+        assert!(matches!(
+            parse("name: x\nstages:\n  - name: a\n    run: 'true'\n  - name: a\n    run: 'true'"),
+            Err(WorkflowError::DuplicateStage(_))
+        ));
+        assert!(matches!(
+            parse("name: x\nstages:\n  - name: a\n    tool: dock\n    run: 'true'"),
+            Err(WorkflowError::InvalidCommand(_))
+        ));
+        assert!(matches!(
+            parse("name: x\nstages:\n  - name: a\n    rn: 'true'"),
+            Err(WorkflowError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_params_are_shell_words() {
+        let mut pipeline = parse(PIPELINE).unwrap();
+        let train = &mut pipeline.def.stages[0];
+        train.set_params(r#"train --name "a b" 'it''s'"#).unwrap();
+        assert_eq!(train.args, vec!["train", "--name", "a b", "its"]);
+        assert_eq!(train.params(), "train --name 'a b' its");
+
+        // What `params` shows reads back as the same arguments
+        let shown = train.params();
+        train.set_params(&shown).unwrap();
+        assert_eq!(train.args, vec!["train", "--name", "a b", "its"]);
+
+        assert!(matches!(
+            train.set_params("train --name \"a b"),
+            Err(WorkflowError::InvalidParams { .. })
+        ));
+        assert_eq!(train.args, vec!["train", "--name", "a b", "its"]);
+
+        // Scripts are kept as written
+        let notify = &mut pipeline.def.stages[2];
+        notify.set_params("echo \"a  b\"").unwrap();
+        assert_eq!(notify.params(), "echo \"a  b\"");
+    }
+}
@@ -0,0 +1,648 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use polars::prelude::PolarsError;
+use thiserror::Error;
+
+use crate::commands;
+use crate::data::catalog;
+use crate::data::pipeline::PipelineError;
+use crate::data::semantic::SchemaError;
+use crate::data::versions::VersionRef;
+use crate::data::{CleanOptions, DataType};
+
+/// Subcommands of the `dock` binary (FR-DOCK-01..04)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DockCommand {
+    /// Catalog the files under a data root
+    Ls {
+        dir: PathBuf,
+        json: bool,
+    },
+    /// Print the shape, schema and first rows of a dataset
+    Inspect {
+        dataset: PathBuf,
+        rows: usize,
+    },
+    /// Print the distribution of every column
+    Report {
+        dataset: PathBuf,
+        json: bool,
+    },
+    /// Override the measurement level of columns, then print the level of every column
+    Schema {
+        dataset: PathBuf,
+        set: Vec<(String, DataType)>,
+        order: Vec<(String, Vec<String>)>,
+        unset: Vec<String>,
+    },
+    Clean {
+        dataset: PathBuf,
+        out: PathBuf,
+        options: CleanOptions,
+    },
+    /// Fit a pipeline of stages such as `scaler:minmax` to a dataset and write the result
+    Transform {
+        dataset: PathBuf,
+        out: PathBuf,
+        stages: Vec<String>,
+    },
+    /// Convert a dataset to the format of `out`
+    Export {
+        dataset: PathBuf,
+        out: PathBuf,
+    },
+    /// Store the current contents of a dataset as a version
+    Snapshot {
+        dataset: PathBuf,
+    },
+    Versions {
+        dataset: PathBuf,
+    },
+    /// Restore a version to `out`, or to the file it was taken of
+    Checkout {
+        version: VersionRef,
+        out: Option<PathBuf>,
+    },
+    /// Compare the schema and row count of two versions
+    Diff {
+        before: VersionRef,
+        after: VersionRef,
+    },
+}
+
+/// Errors, each mapped to the process exit code by [`DockError::exit_code`]:
+/// `2` for an invalid command line and `1` for anything else
+#[derive(Debug, Error)]
+pub enum DockError {
+    #[error("{0}")]
+    Usage(#[from] clap::Error),
+
+    #[error("Unsupported format of {0}; use .csv, .tsv, .json, .ndjson, .parquet or .xlsx")]
+    UnsupportedFormat(PathBuf),
+
+    #[error("Cannot read {0}: {1}")]
+    Read(PathBuf, PolarsError),
+
+    #[error("Cannot write {0}: {1}")]
+    Write(PathBuf, PolarsError),
+
+    #[error("Cannot clean {0}: {1}")]
+    Clean(PathBuf, PolarsError),
+
+    #[error("Cannot transform {0}: {1}")]
+    Transform(PathBuf, PipelineError),
+
+    #[error("Cannot type the columns of {0}: {1}")]
+    Schema(PathBuf, SchemaError),
+
+    #[error("Cannot catalog {0}: {1}")]
+    Catalog(PathBuf, std::io::Error),
+
+    #[error("Cannot read or write the versions in {0}: {1}")]
+    Versions(PathBuf, std::io::Error),
+
+    #[error("No version {0}; list them with `dock versions`")]
+    UnknownVersion(VersionRef),
+
+    #[error("{0} matches {1} versions; give more of the hash")]
+    AmbiguousVersion(VersionRef, usize),
+
+    #[error("`{0}` may be any of {datasets}; give its path", datasets = .1.join(", "))]
+    AmbiguousDataset(String, Vec<String>),
+}
+
+impl DockError {
+    /// Exit code of the `dock` process for this error
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            DockError::Usage(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Parse `args` (including the binary name) and execute the requested subcommand.
+///
+/// Running without a subcommand prints the help text.
+pub fn run<I, T>(args: I) -> Result<(), DockError>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = command().try_get_matches_from(args)?;
+    match DockCommand::from_matches(&matches) {
+        Some(command) => commands::execute(&command),
+        None => {
+            let _ = command().print_help();
+            Ok(())
+        }
+    }
+}
+
+/// The clap definition of the `dock` command line
+pub fn command() -> Command {
+    let dataset = || {
+        Arg::new("dataset")
+            .help("A CSV, TSV, JSON, NDJSON, Parquet or XLSX file")
+            .required(true)
+            .value_parser(clap::value_parser!(PathBuf))
+            .value_name("DATASET")
+    };
+    let version = |id: &'static str, help: &'static str| {
+        Arg::new(id)
+            .help(help)
+            .required(true)
+            .value_name("NAME[@HASH]")
+    };
+    let out = |help: &'static str| {
+        Arg::new("out")
+            .long("out")
+            .short('o')
+            .help(help)
+            .value_parser(clap::value_parser!(PathBuf))
+            .value_name("FILE")
+    };
+
+    Command::new("dock")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Data Organization and Cleaning Kit")
+        .subcommand(
+            Command::new("ls")
+                .about("Catalog the tables, media and graph files under a data root")
+                .arg(
+                    Arg::new("dir")
+                        .help("Data root [default: $DOCK_DATA_DIR or the current directory]")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .value_name("DIR"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the catalog, with table schemas, as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("inspect")
+                .about("Show the shape, schema and first rows of a dataset")
+                .arg(dataset())
+                .arg(
+                    Arg::new("rows")
+                        .long("rows")
+                        .short('n')
+                        .help("Number of rows to show")
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(usize))
+                        .value_name("N"),
+                ),
+        )
+        .subcommand(
+            Command::new("report")
+                .about("Profile the distribution of every column")
+                .arg(dataset())
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the distributions as JSON")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("schema")
+                .about("Show or override the measurement level of each column")
+                .arg(dataset())
+                .arg(
+                    Arg::new("set")
+                        .long("set")
+                        .help("Override the level of a column: nominal, ordinal, interval or ratio")
+                        .action(ArgAction::Append)
+                        .value_parser(parse_level)
+                        .value_name("COLUMN=LEVEL"),
+                )
+                .arg(
+                    Arg::new("unset")
+                        .long("unset")
+                        .help("Go back to the inferred level of a column")
+                        .action(ArgAction::Append)
+                        .value_name("COLUMN"),
+                )
+                .arg(
+                    Arg::new("order")
+                        .long("order")
+                        .help("Make a column ordinal, its categories from lowest to highest")
+                        .action(ArgAction::Append)
+                        .value_name("COLUMN=A,B,...")
+                        .value_parser(parse_order),
+                ),
+        )
+        .subcommand(
+            Command::new("clean")
+                .about("Drop empty columns, and optionally incomplete or duplicate rows")
+                .arg(dataset())
+                .arg(out(
+                    "Where to write the result [default: <DATASET>.clean.<ext>]",
+                ))
+                .arg(
+                    Arg::new("drop-nulls")
+                        .long("drop-nulls")
+                        .help("Drop rows with a missing value")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("dedup")
+                        .long("dedup")
+                        .help("Drop repeated rows, keeping the first")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("transform")
+                .about("Fit a pipeline to a dataset and write the transformed data")
+                .arg(dataset())
+                .arg(
+                    Arg::new("stage")
+                        .long("stage")
+                        .short('s')
+                        .help(
+                            "A stage, in order: imputer:{auto,mean,median,mode,constant:V}, \
+                             scaler:{minmax,standard,robust}, encoder:{auto,onehot,ordinal} \
+                             or pca:N. `auto` picks by the level of each column",
+                        )
+                        .default_values(["imputer:auto", "encoder:auto"])
+                        .action(ArgAction::Append)
+                        .value_name("STAGE"),
+                )
+                .arg(out(
+                    "Where to write the result [default: <DATASET>.transformed.<ext>]",
+                )),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Convert a dataset to the format given by the extension of --out")
+                .arg(dataset())
+                .arg(out("File to write").required(true)),
+        )
+        .subcommand(
+            Command::new("snapshot")
+                .about("Store the current contents of a dataset as a new version")
+                .arg(dataset()),
+        )
+        .subcommand(
+            Command::new("versions")
+                .about("List the versions of a dataset, oldest first")
+                .arg(
+                    Arg::new("dataset")
+                        .help("Name or path of the dataset")
+                        .required(true)
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .value_name("NAME"),
+                ),
+        )
+        .subcommand(
+            Command::new("checkout")
+                .about("Restore a version of a dataset")
+                .arg(version("version", "Version to restore [default hash: the latest]"))
+                .arg(out(
+                    "Where to write it, converting by extension [default: the file it was taken of]",
+                )),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare the schema and row count of two versions")
+                .arg(version("before", "Earlier version"))
+                .arg(version("after", "Later version")),
+        )
+}
+
+impl DockCommand {
+    /// Build a command from parsed matches, `None` when no subcommand was given
+    pub fn from_matches(matches: &ArgMatches) -> Option<Self> {
+        let path = |sub: &ArgMatches, id: &str| sub.get_one::<PathBuf>(id).cloned();
+        let version = |sub: &ArgMatches, id: &str| {
+            sub.get_one::<String>(id)
+                .map(|reference| VersionRef::parse(reference))
+        };
+        let command = match matches.subcommand()? {
+            ("ls", sub) => DockCommand::Ls {
+                dir: path(sub, "dir").unwrap_or_else(catalog::data_root),
+                json: sub.get_flag("json"),
+            },
+            ("inspect", sub) => DockCommand::Inspect {
+                dataset: path(sub, "dataset")?,
+                rows: *sub.get_one::<usize>("rows")?,
+            },
+            ("report", sub) => DockCommand::Report {
+                dataset: path(sub, "dataset")?,
+                json: sub.get_flag("json"),
+            },
+            ("schema", sub) => DockCommand::Schema {
+                dataset: path(sub, "dataset")?,
+                set: sub
+                    .get_many::<(String, DataType)>("set")
+                    .map(|set| set.cloned().collect())
+                    .unwrap_or_default(),
+                order: sub
+                    .get_many::<(String, Vec<String>)>("order")
+                    .map(|order| order.cloned().collect())
+                    .unwrap_or_default(),
+                unset: sub
+                    .get_many::<String>("unset")
+                    .map(|unset| unset.cloned().collect())
+                    .unwrap_or_default(),
+            },
+            ("clean", sub) => {
+                let dataset = path(sub, "dataset")?;
+                DockCommand::Clean {
+                    out: path(sub, "out").unwrap_or_else(|| derived_path(&dataset, "clean")),
+                    dataset,
+                    options: CleanOptions {
+                        drop_nulls: sub.get_flag("drop-nulls"),
+                        dedup: sub.get_flag("dedup"),
+                    },
+                }
+            }
+            ("transform", sub) => {
+                let dataset = path(sub, "dataset")?;
+                DockCommand::Transform {
+                    out: path(sub, "out").unwrap_or_else(|| derived_path(&dataset, "transformed")),
+                    dataset,
+                    stages: sub.get_many::<String>("stage")?.cloned().collect(),
+                }
+            }
+            ("export", sub) => DockCommand::Export {
+                dataset: path(sub, "dataset")?,
+                out: path(sub, "out")?,
+            },
+            ("snapshot", sub) => DockCommand::Snapshot {
+                dataset: path(sub, "dataset")?,
+            },
+            ("versions", sub) => DockCommand::Versions {
+                dataset: path(sub, "dataset")?,
+            },
+            ("checkout", sub) => DockCommand::Checkout {
+                version: version(sub, "version")?,
+                out: path(sub, "out"),
+            },
+            ("diff", sub) => DockCommand::Diff {
+                before: version(sub, "before")?,
+                after: version(sub, "after")?,
+            },
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+/// `region=nominal` for `--set`
+fn parse_level(value: &str) -> Result<(String, DataType), String> {
+    let (column, level) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected COLUMN=LEVEL, not `{value}`"))?;
+    let level = level.parse().map_err(|e: SchemaError| e.to_string())?;
+    Ok((column.to_string(), level))
+}
+
+/// `size=S,M,L` for `--order`
+fn parse_order(value: &str) -> Result<(String, Vec<String>), String> {
+    let (column, order) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected COLUMN=A,B,..., not `{value}`"))?;
+    let order: Vec<String> = order.split(',').map(str::to_string).collect();
+    if order.iter().any(String::is_empty) {
+        return Err(format!("empty category in `{value}`"));
+    }
+    Ok((column.to_string(), order))
+}
+
+/// `data/raw.csv` becomes `data/raw.clean.csv` for the suffix `clean`
+fn derived_path(dataset: &std::path::Path, suffix: &str) -> PathBuf {
+    let stem = dataset
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match dataset.extension() {
+        Some(ext) => format!("{stem}.{suffix}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{suffix}"),
+    };
+    dataset.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Option<DockCommand> {
+        let matches = command()
+            .try_get_matches_from(args)
+            .expect("arguments should parse");
+        DockCommand::from_matches(&matches)
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        assert_eq!(
+            parse(&["dock", "ls", "data", "--json"]),
+            Some(DockCommand::Ls {
+                dir: "data".into(),
+                json: true
+            })
+        );
+        assert_eq!(
+            parse(&["dock", "inspect", "data.csv", "-n", "3"]),
+            Some(DockCommand::Inspect {
+                dataset: "data.csv".into(),
+                rows: 3
+            })
+        );
+        assert_eq!(
+            parse(&["dock", "clean", "data/raw.csv", "--dedup"]),
+            Some(DockCommand::Clean {
+                dataset: "data/raw.csv".into(),
+                out: "data/raw.clean.csv".into(),
+                options: CleanOptions {
+                    drop_nulls: false,
+                    dedup: true
+                },
+            })
+        );
+        assert_eq!(
+            parse(&["dock", "export", "data.csv", "--out", "data.json"]),
+            Some(DockCommand::Export {
+                dataset: "data.csv".into(),
+                out: "data.json".into()
+            })
+        );
+        assert_eq!(
+            parse(&[
+                "dock",
+                "transform",
+                "raw.csv",
+                "-s",
+                "imputer:mean",
+                "-s",
+                "pca:2"
+            ]),
+            Some(DockCommand::Transform {
+                dataset: "raw.csv".into(),
+                out: "raw.transformed.csv".into(),
+                stages: vec!["imputer:mean".into(), "pca:2".into()],
+            })
+        );
+        assert_eq!(
+            parse(&["dock", "transform", "raw.csv"]),
+            Some(DockCommand::Transform {
+                dataset: "raw.csv".into(),
+                out: "raw.transformed.csv".into(),
+                stages: vec!["imputer:auto".into(), "encoder:auto".into()],
+            })
+        );
+        assert_eq!(
+            parse(&[
+                "dock",
+                "schema",
+                "data.csv",
+                "--set",
+                "region=nominal",
+                "--order",
+                "size=S,M,L",
+                "--unset",
+                "age"
+            ]),
+            Some(DockCommand::Schema {
+                dataset: "data.csv".into(),
+                set: vec![("region".into(), DataType::Nominal)],
+                order: vec![("size".into(), vec!["S".into(), "M".into(), "L".into()])],
+                unset: vec!["age".into()],
+            })
+        );
+        assert!(
+            command()
+                .try_get_matches_from(["dock", "schema", "data.csv", "--order", "size=S,,L"])
+                .is_err()
+        );
+        assert!(
+            command()
+                .try_get_matches_from(["dock", "schema", "data.csv", "--set", "region=huge"])
+                .is_err()
+        );
+        assert_eq!(
+            parse(&["dock", "report", "data.csv", "--json"]),
+            Some(DockCommand::Report {
+                dataset: "data.csv".into(),
+                json: true
+            })
+        );
+        assert_eq!(
+            parse(&["dock", "versions", "data.csv"]),
+            Some(DockCommand::Versions {
+                dataset: "data.csv".into()
+            })
+        );
+        assert_eq!(
+            parse(&["dock", "checkout", "churn@ab12", "-o", "old.csv"]),
+            Some(DockCommand::Checkout {
+                version: VersionRef::parse("churn@ab12"),
+                out: Some("old.csv".into())
+            })
+        );
+        assert_eq!(
+            parse(&["dock", "diff", "churn@ab12", "churn"]),
+            Some(DockCommand::Diff {
+                before: VersionRef::parse("churn@ab12"),
+                after: VersionRef::parse("churn")
+            })
+        );
+        assert_eq!(parse(&["dock"]), None);
+
+        let error = run(["dock", "export", "data.csv"]).unwrap_err();
+        assert_eq!(error.exit_code(), 2);
+    }
+
+    /// Set for the child process that runs `commands_helper`
+    const COMMANDS_HELPER: &str = "DOCK_TEST_COMMANDS_HELPER";
+
+    #[test]
+    fn test_commands_end_to_end() {
+        // The data root is read from the environment, so the commands run in a child with
+        // one of their own rather than beside the other tests of this process
+        let root = tempfile::tempdir().unwrap();
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["cli::tests::commands_helper", "--exact", "--nocapture"])
+            .env(COMMANDS_HELPER, "1")
+            .env(catalog::DATA_DIR_ENV, root.path())
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(root.path().join(".dock/index.jsonl").exists());
+    }
+
+    /// Runs only in the child of `test_commands_end_to_end`, with the data root set
+    #[test]
+    fn commands_helper() {
+        use crate::data::versions::Store;
+        use std::fs;
+
+        if std::env::var_os(COMMANDS_HELPER).is_none() {
+            return;
+        }
+        let root = catalog::data_root();
+        let store = Store::open(&root);
+        let raw = root.join("raw.csv");
+        fs::write(&raw, "id,age\n1,3\n1,3\n2,5\n").unwrap();
+        let raw_arg = raw.to_str().unwrap();
+
+        run(["dock", "clean", raw_arg, "--dedup"]).unwrap();
+        let cleaned = root.join("raw.clean.csv");
+        assert_eq!(fs::read_to_string(&cleaned).unwrap().lines().count(), 3);
+        let source = store.versions("raw.csv").unwrap();
+        let derived = store.versions("raw.clean.csv").unwrap();
+        assert_eq!((source.len(), derived.len()), (1, 1));
+        assert_eq!(derived[0].parent.as_ref(), Some(&source[0].hash));
+        assert_eq!(derived[0].rows, 2);
+
+        let json = root.join("raw.json");
+        run(["dock", "export", raw_arg, "--out", json.to_str().unwrap()]).unwrap();
+        assert!(json.exists());
+        // The unchanged source is not stored again
+        assert_eq!(store.versions("raw.csv").unwrap(), source);
+        assert_eq!(
+            store.versions("raw.json").unwrap()[0].parent.as_ref(),
+            Some(&source[0].hash)
+        );
+
+        let error = run(["dock", "export", raw_arg, "--out", "raw.xyz"]).unwrap_err();
+        assert!(matches!(error, DockError::UnsupportedFormat(_)));
+        assert_eq!(error.exit_code(), 1);
+        assert_eq!(
+            run(["dock", "clean"]).unwrap_err().exit_code(),
+            2,
+            "a missing dataset is a usage error"
+        );
+
+        run(["dock", "versions", raw_arg]).unwrap();
+        run(["dock", "versions", "never-stored.csv"]).unwrap();
+
+        // Checking out the version restores the file it was taken of
+        fs::write(&cleaned, "id,age\n9,9\n").unwrap();
+        run(["dock", "checkout", "raw.clean.csv"]).unwrap();
+        assert_eq!(fs::read_to_string(&cleaned).unwrap().lines().count(), 3);
+        let copy = root.join("copy.parquet");
+        let id = derived[0].id();
+        run(["dock", "checkout", &id, "-o", copy.to_str().unwrap()]).unwrap();
+        assert!(copy.exists());
+
+        let error = run(["dock", "checkout", "raw.csv@ffffffff"]).unwrap_err();
+        assert!(matches!(error, DockError::UnknownVersion(_)));
+        assert_eq!(error.exit_code(), 1);
+        let error = run(["dock", "checkout", "raw"]).unwrap_err();
+        assert!(matches!(error, DockError::AmbiguousDataset(_, _)));
+        assert_eq!(error.exit_code(), 1);
+    }
+}
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+use crate::data::{self, versions::VersionRef};
+
+/// `dock checkout`: restore the contents of a version to `out`, or to the file it was
+/// taken of under the data root, converting when `out` is of another format
+pub fn run(reference: &VersionRef, out: Option<&Path>) -> Result<(), DockError> {
+    let store = super::store();
+    let version = super::resolve(&store, reference)?;
+    let taken_of = store.path(&version);
+    let out = out.unwrap_or(&taken_of);
+
+    let object = store.object(&version);
+    let format = super::detect(&object)?;
+    let target = super::format_of(out)?;
+    if format == target {
+        store
+            .restore(&version, out)
+            .map_err(super::versions_error(&store))?;
+    } else {
+        let mut df = data::read_data_frame(&object, format)
+            .map_err(|e| DockError::Read(object.clone(), e))?;
+        data::write_data_frame(&mut df, out, target)
+            .map_err(|e| DockError::Write(out.to_path_buf(), e))?;
+    }
+    println!("Checked out {} to {}", version.id(), out.display());
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+use crate::data::{self, CleanOptions};
+
+/// `dock clean`: write a tidied copy of `dataset` to `out`
+pub fn run(dataset: &Path, out: &Path, options: CleanOptions) -> Result<(), DockError> {
+    let df = super::load(dataset)?;
+    let mut cleaned =
+        data::clean(&df, options).map_err(|e| DockError::Clean(dataset.to_path_buf(), e))?;
+    let version = super::save(&mut cleaned, out, (dataset, &df), &[])?;
+    println!(
+        "Wrote {} ({} of {} rows, {} of {} columns), version {}",
+        out.display(),
+        cleaned.height(),
+        df.height(),
+        cleaned.width(),
+        df.width(),
+        version.id()
+    );
+    Ok(())
+}
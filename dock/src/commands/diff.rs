@@ -0,0 +1,16 @@
+use crate::cli::DockError;
+use crate::data::versions::{VersionDiff, VersionRef};
+
+/// `dock diff`: print how the schema and row count changed from `before` to `after`
+pub fn run(before: &VersionRef, after: &VersionRef) -> Result<(), DockError> {
+    let store = super::store();
+    let diff = VersionDiff::between(
+        &super::resolve(&store, before)?,
+        &super::resolve(&store, after)?,
+    );
+    print!("{diff}");
+    if diff.is_empty() {
+        println!("The schema and row count are the same.");
+    }
+    Ok(())
+}
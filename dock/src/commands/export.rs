@@ -0,0 +1,16 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+
+/// `dock export`: convert `dataset` to the format of `out`
+pub fn run(dataset: &Path, out: &Path) -> Result<(), DockError> {
+    let df = super::load(dataset)?;
+    let version = super::save(&mut df.clone(), out, (dataset, &df), &[])?;
+    println!(
+        "Wrote {} ({} rows), version {}",
+        out.display(),
+        df.height(),
+        version.id()
+    );
+    Ok(())
+}
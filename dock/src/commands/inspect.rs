@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+use crate::data::SemanticSchema;
+
+/// `dock inspect`: print the shape, the type, measurement level and missing values of each
+/// column, and the first `rows` rows. The dataset is scanned, so only those rows are
+/// loaded.
+pub fn run(dataset: &Path, rows: usize) -> Result<(), DockError> {
+    let source = super::open(dataset)?;
+    let read_error = |e| DockError::Read(dataset.to_path_buf(), e);
+    let height = source.count_rows().map_err(read_error)?;
+    let schema =
+        SemanticSchema::load(&source).map_err(|e| DockError::Schema(dataset.to_path_buf(), e))?;
+    println!(
+        "{}: {height} rows × {} columns\n",
+        dataset.display(),
+        schema.columns().len()
+    );
+    println!(
+        "{:<24} {:<12} {:<10} {:>8}",
+        "COLUMN", "TYPE", "LEVEL", "NULLS"
+    );
+    for column in schema.columns() {
+        println!(
+            "{:<24} {:<12} {:<10} {:>8}",
+            column.name,
+            column.dtype.to_string(),
+            column.level.to_string(),
+            column.stats.nulls
+        );
+    }
+    if rows > 0 {
+        println!("\n{}", source.window(0, rows).map_err(read_error)?);
+    }
+    Ok(())
+}
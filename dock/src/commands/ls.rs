@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+use crate::data::catalog::Catalog;
+
+/// `dock ls`: print the catalog of `dir`, as a table or as JSON
+pub fn run(dir: &Path, json: bool) -> Result<(), DockError> {
+    let catalog = Catalog::scan(dir).map_err(|e| DockError::Catalog(dir.to_path_buf(), e))?;
+    if json {
+        // Serializing plain data cannot fail
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&catalog).unwrap_or_default()
+        );
+        return Ok(());
+    }
+    if catalog.entries.is_empty() {
+        println!("No files under {}.", dir.display());
+        return Ok(());
+    }
+
+    println!(
+        "{:<36} {:<8} {:>12} {:<16} {:>8} {:>8}",
+        "PATH", "TYPE", "BYTES", "MODIFIED", "ROWS", "COLUMNS"
+    );
+    for entry in &catalog.entries {
+        let count = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_else(|| "-".into());
+        print!(
+            "{:<36} {:<8} {:>12} {:<16} {:>8} {:>8}",
+            entry.path.display(),
+            entry.category.label(),
+            entry.bytes,
+            entry
+                .modified
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default(),
+            count(entry.rows),
+            count(entry.columns)
+        );
+        match &entry.error {
+            Some(error) => println!("  unreadable: {error}"),
+            None => println!(),
+        }
+    }
+    Ok(())
+}
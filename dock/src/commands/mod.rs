@@ -0,0 +1,132 @@
+//! Implementations of the `dock` subcommands
+
+pub mod checkout;
+pub mod clean;
+pub mod diff;
+pub mod export;
+pub mod inspect;
+pub mod ls;
+pub mod report;
+pub mod schema;
+pub mod snapshot;
+pub mod transform;
+pub mod versions;
+
+use std::path::Path;
+
+use polars::prelude::DataFrame;
+
+use crate::cli::{DockCommand, DockError};
+use crate::data::catalog;
+use crate::data::versions::{Derivation, Store, Version, VersionRef};
+use crate::data::{self, DataSource, Format};
+
+/// Execute a subcommand to completion
+pub fn execute(command: &DockCommand) -> Result<(), DockError> {
+    match command {
+        DockCommand::Ls { dir, json } => ls::run(dir, *json),
+        DockCommand::Inspect { dataset, rows } => inspect::run(dataset, *rows),
+        DockCommand::Report { dataset, json } => report::run(dataset, *json),
+        DockCommand::Schema {
+            dataset,
+            set,
+            order,
+            unset,
+        } => schema::run(dataset, set, order, unset),
+        DockCommand::Clean {
+            dataset,
+            out,
+            options,
+        } => clean::run(dataset, out, *options),
+        DockCommand::Transform {
+            dataset,
+            out,
+            stages,
+        } => transform::run(dataset, out, stages),
+        DockCommand::Export { dataset, out } => export::run(dataset, out),
+        DockCommand::Snapshot { dataset } => snapshot::run(dataset),
+        DockCommand::Versions { dataset } => versions::run(dataset),
+        DockCommand::Checkout { version, out } => checkout::run(version, out.as_deref()),
+        DockCommand::Diff { before, after } => diff::run(before, after),
+    }
+}
+
+/// The format to write `path` in, by its extension
+fn format_of(path: &Path) -> Result<Format, DockError> {
+    Format::from_path(path).ok_or_else(|| DockError::UnsupportedFormat(path.to_path_buf()))
+}
+
+/// The format of the existing file at `path`, by its contents and extension
+fn detect(path: &Path) -> Result<Format, DockError> {
+    Format::detect(path)
+        .map_err(|e| DockError::Read(path.to_path_buf(), e.into()))?
+        .ok_or_else(|| DockError::UnsupportedFormat(path.to_path_buf()))
+}
+
+/// Open the dataset at `path` to read on demand
+fn open(path: &Path) -> Result<DataSource, DockError> {
+    DataSource::open_as(path, detect(path)?).map_err(|e| DockError::Read(path.to_path_buf(), e))
+}
+
+/// Read every row of the dataset at `path`
+fn load(path: &Path) -> Result<DataFrame, DockError> {
+    data::read_data_frame(path, detect(path)?).map_err(|e| DockError::Read(path.to_path_buf(), e))
+}
+
+/// The version store of the data root
+fn store() -> Store {
+    Store::open(&catalog::data_root())
+}
+
+fn versions_error(store: &Store) -> impl Fn(std::io::Error) -> DockError + '_ {
+    |e| DockError::Versions(store.dir().to_path_buf(), e)
+}
+
+/// Write `df` to `out` and snapshot it as made from `source` by `stages`. `source` is
+/// snapshotted first, so the new version always has its parent in the store.
+fn save(
+    df: &mut DataFrame,
+    out: &Path,
+    (source, source_df): (&Path, &DataFrame),
+    stages: &[String],
+) -> Result<Version, DockError> {
+    let store = store();
+    let parent = store
+        .snapshot(source, source_df, None)
+        .map_err(versions_error(&store))?;
+    data::write_data_frame(df, out, format_of(out)?)
+        .map_err(|e| DockError::Write(out.to_path_buf(), e))?;
+    // Snapshots hash the schema as read back, so `dock snapshot` of the file agrees
+    let written = load(out)?;
+    let derivation = Derivation {
+        parent: &parent,
+        stages,
+    };
+    store
+        .snapshot(out, &written, Some(derivation))
+        .map_err(versions_error(&store))
+}
+
+/// The stored dataset `name` means, `None` when there is none
+fn dataset(store: &Store, name: &str) -> Result<Option<String>, DockError> {
+    let mut datasets = store.datasets_named(name).map_err(versions_error(store))?;
+    match datasets.len() {
+        0 | 1 => Ok(datasets.pop()),
+        _ => Err(DockError::AmbiguousDataset(name.to_string(), datasets)),
+    }
+}
+
+/// The one version `reference` names
+fn resolve(store: &Store, reference: &VersionRef) -> Result<Version, DockError> {
+    let Some(dataset) = dataset(store, &reference.dataset)? else {
+        return Err(DockError::UnknownVersion(reference.clone()));
+    };
+    let mut found = store
+        .find(&dataset, reference.hash.as_deref())
+        .map_err(versions_error(store))?;
+    match found.len() {
+        0 => Err(DockError::UnknownVersion(reference.clone())),
+        1 => Ok(found.remove(0)),
+        n => Err(DockError::AmbiguousVersion(reference.clone(), n)),
+    }
+}
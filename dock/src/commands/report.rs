@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+use crate::data::SemanticSchema;
+use crate::data::distribution::{Distribution, Shape};
+use crate::data::semantic::Overrides;
+
+/// Width of the longest bar of a chart
+const BAR_WIDTH: usize = 40;
+
+/// `dock report`: print the distribution of every column, at its measurement level, as
+/// text or as JSON. Quantiles need every value, so the dataset is read once, whole, and
+/// the levels are inferred from the same rows.
+pub fn run(dataset: &Path, json: bool) -> Result<(), DockError> {
+    let read_error = |e| DockError::Read(dataset.to_path_buf(), e);
+    let schema_error = |e| DockError::Schema(dataset.to_path_buf(), e);
+    let df = super::load(dataset)?;
+    let mut schema = SemanticSchema::of(&df).map_err(read_error)?;
+    schema.apply(&Overrides::load(dataset).map_err(schema_error)?);
+    let distributions = df
+        .get_columns()
+        .iter()
+        .zip(schema.columns())
+        .map(|(values, column)| Distribution::of(values, column.level))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(read_error)?;
+
+    if json {
+        // Serializing plain data cannot fail
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&distributions).unwrap_or_default()
+        );
+        return Ok(());
+    }
+    for (index, distribution) in distributions.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        print_distribution(distribution);
+    }
+    Ok(())
+}
+
+fn print_distribution(distribution: &Distribution) {
+    println!(
+        "{} ({}): {} values, {} nulls",
+        distribution.column, distribution.level, distribution.count, distribution.nulls
+    );
+    match &distribution.shape {
+        Shape::Numeric(numeric) => {
+            let q = &numeric.quantiles;
+            println!(
+                "  min {}  5% {}  25% {}  median {}  75% {}  95% {}  max {}",
+                number(q.min),
+                number(q.p5),
+                number(q.q1),
+                number(q.median),
+                number(q.q3),
+                number(q.p95),
+                number(q.max)
+            );
+            println!(
+                "  mean {:.4}  std {:.4}  skew {:.4}  kurtosis {:.4}",
+                numeric.mean, numeric.std, numeric.skew, numeric.kurtosis
+            );
+            if let Some(fit) = &numeric.fit {
+                println!(
+                    "  best fit {} (KS distance {:.4})",
+                    fit.family, fit.distance
+                );
+            }
+            let bars = numeric.bins.iter().map(|bin| {
+                (
+                    format!("{} .. {}", number(bin.start), number(bin.end)),
+                    bin.count,
+                )
+            });
+            print_bars(bars.collect());
+        }
+        Shape::Categorical(categorical) => {
+            println!("  {} distinct", categorical.distinct);
+            let mut bars = categorical.values.clone();
+            if categorical.other > 0 {
+                bars.push(("(other)".to_string(), categorical.other));
+            }
+            print_bars(bars);
+        }
+    }
+}
+
+/// `x` to 4 decimals, without trailing zeros
+fn number(x: f64) -> String {
+    let text = format!("{x:.4}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// One labelled bar per count, scaled to the largest
+fn print_bars(bars: Vec<(String, usize)>) {
+    let label_width = bars.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let largest = bars
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for (label, count) in bars {
+        let bar = "█".repeat(count * BAR_WIDTH / largest);
+        println!("  {label:<label_width$}  {bar} {count}");
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+use crate::data::DataType;
+use crate::data::semantic::{Overrides, SchemaError, SemanticSchema};
+
+/// `dock schema`: apply `set`, `order` and `unset` to the overrides of `dataset`, then
+/// print the level of every column with the defaults it implies
+pub fn run(
+    dataset: &Path,
+    set: &[(String, DataType)],
+    order: &[(String, Vec<String>)],
+    unset: &[String],
+) -> Result<(), DockError> {
+    let schema_error = |e| DockError::Schema(dataset.to_path_buf(), e);
+    let source = super::open(dataset)?;
+    if !set.is_empty() || !order.is_empty() || !unset.is_empty() {
+        let mut overrides = Overrides::load(dataset).map_err(schema_error)?;
+        let known = |column: &String| match source.schema().get(column) {
+            Some(_) => Ok(()),
+            None => Err(schema_error(SchemaError::UnknownColumn(column.clone()))),
+        };
+        for (column, level) in set {
+            known(column)?;
+            overrides.set(column, *level);
+        }
+        for (column, categories) in order {
+            known(column)?;
+            overrides.set_order(column, categories.clone());
+        }
+        for column in unset {
+            overrides.remove(column);
+        }
+        overrides.save(dataset).map_err(schema_error)?;
+    }
+
+    let schema = SemanticSchema::load(&source).map_err(schema_error)?;
+    println!(
+        "{:<24} {:<12} {:<10} {:<8} {:<8} CHART",
+        "COLUMN", "TYPE", "LEVEL", "IMPUTE", "ENCODE"
+    );
+    for column in schema.columns() {
+        let mut level = column.level.to_string();
+        if column.overridden {
+            level.push('*');
+        }
+        println!(
+            "{:<24} {:<12} {:<10} {:<8} {:<8} {}",
+            column.name,
+            column.dtype.to_string(),
+            level,
+            column.level.imputer(),
+            column.encoder().unwrap_or("-"),
+            column.level.chart()
+        );
+        if let Some(order) = &column.order {
+            println!("{:<24} order: {}", "", order.join(" < "));
+        }
+    }
+    if schema.columns().iter().any(|column| column.overridden) {
+        println!("\n* set in {}", Overrides::path(dataset).display());
+    }
+    Ok(())
+}
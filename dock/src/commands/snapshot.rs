@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+
+/// `dock snapshot`: store the current contents of `dataset` as a version
+pub fn run(dataset: &Path) -> Result<(), DockError> {
+    let df = super::load(dataset)?;
+    let store = super::store();
+    let latest = store
+        .versions(&store.dataset(dataset))
+        .map_err(super::versions_error(&store))?
+        .pop();
+    let version = store
+        .snapshot(dataset, &df, None)
+        .map_err(super::versions_error(&store))?;
+    if latest.as_ref() == Some(&version) {
+        println!("{} is unchanged since {}", dataset.display(), version.id());
+    } else {
+        println!(
+            "Stored {} as {} ({} rows, {} columns)",
+            dataset.display(),
+            version.id(),
+            version.rows,
+            version.columns
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+use crate::data::SemanticSchema;
+use crate::data::pipeline::{Pipeline, Stage};
+
+/// `dock transform`: fit a pipeline of `stages` to `dataset` and write the result to `out`.
+/// Columns are treated by their measurement level, as `dock schema` shows them.
+pub fn run(dataset: &Path, out: &Path, stages: &[String]) -> Result<(), DockError> {
+    let transform_error = |e| DockError::Transform(dataset.to_path_buf(), e);
+    let parsed = stages
+        .iter()
+        .map(|stage| Stage::parse(stage))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(transform_error)?;
+    let schema = SemanticSchema::load(&super::open(dataset)?)
+        .map_err(|e| DockError::Schema(dataset.to_path_buf(), e))?;
+    let df = super::load(dataset)?;
+    let mut transformed = Pipeline::new(parsed)
+        .with_levels(schema.levels())
+        .with_orders(schema.orders())
+        .fit_transform(&df)
+        .map_err(transform_error)?;
+    let version = super::save(&mut transformed, out, (dataset, &df), stages)?;
+    println!(
+        "Wrote {} ({} rows, {} columns), version {}",
+        out.display(),
+        transformed.height(),
+        transformed.width(),
+        version.id()
+    );
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use std::path::Path;
+
+use crate::cli::DockError;
+
+/// `dock versions`: list the versions of `dataset`, oldest first
+pub fn run(dataset: &Path) -> Result<(), DockError> {
+    let store = super::store();
+    let name = dataset.to_string_lossy();
+    let versions = match super::dataset(&store, &name)? {
+        Some(dataset) => store
+            .versions(&dataset)
+            .map_err(super::versions_error(&store))?,
+        None => Vec::new(),
+    };
+    if versions.is_empty() {
+        println!(
+            "No versions of `{name}` stored. Store one with `dock snapshot`; `dock clean`, \
+             `dock transform` and `dock export` store what they write."
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{:<14} {:<20} {:>8} {:>8}  {:<14} STAGES",
+        "VERSION", "CREATED", "ROWS", "COLUMNS", "PARENT"
+    );
+    for version in &versions {
+        let parent = version.parent.as_deref().unwrap_or_default();
+        println!(
+            "{:<14} {:<20} {:>8} {:>8}  {:<14} {}",
+            version.short_hash(),
+            version.created.format("%Y-%m-%d %H:%M:%S"),
+            version.rows,
+            version.columns,
+            &parent[..parent.len().min(version.short_hash().len())],
+            version.stages.join(" → ")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,342 @@
+//! Catalog of the files under a data root (FR-DOCK-01)
+//!
+//! Files are sorted into the categories of `settypes.md`: tables (`.csv`, `.tsv`,
+//! `.parquet`, `.xlsx`, `.json`, ...), media (`.txt`, `.png`, `.mp4`, ...) and graphs
+//! (`.html`, `.xml`). Tables dock can read are profiled with their shape and schema,
+//! scanning rather than loading them where their format allows.
+//! Hidden files and directories, such as dock's own `.dock`, are skipped. Symlinked
+//! directories are followed, each directory at most once; those that cannot be read are
+//! listed with the error rather than failing the scan.
+//!
+//! `dock ls --json` prints the catalog as JSON for riptide's Data view.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use polars::prelude::DataFrame;
+
+use super::{DataSource, Format};
+
+/// Environment variable naming the data root, used when no directory is given
+pub const DATA_DIR_ENV: &str = "DOCK_DATA_DIR";
+
+/// Rows of a table parsed when profiling it
+const PROBE_ROWS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Table,
+    Media,
+    Graph,
+    Other,
+}
+
+/// A column of a table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    pub dtype: String,
+}
+
+/// One file of the catalog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Relative to the catalog root
+    pub path: PathBuf,
+    pub category: Category,
+    /// Lowercase extension, e.g. `csv`
+    pub kind: String,
+    pub bytes: u64,
+    pub modified: Option<DateTime<Utc>>,
+    /// Shape and schema, for tables dock can read
+    pub rows: Option<usize>,
+    pub columns: Option<usize>,
+    pub schema: Option<Vec<Field>>,
+    /// Why the file could not be read or a table could not be profiled
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Catalog {
+    pub root: PathBuf,
+    /// Sorted by path
+    pub entries: Vec<Entry>,
+}
+
+impl Category {
+    pub fn of(path: &Path) -> Self {
+        let kind = kind(path);
+        match kind.as_str() {
+            // JSON is listed as both a table and a graph type; dock reads it as a table
+            "csv" | "tsv" | "parquet" | "xlsx" | "json" | "ndjson" | "jsonl" => Category::Table,
+            "txt" | "png" | "jpg" | "jpeg" | "mp4" | "mp3" | "wav" => Category::Media,
+            "html" | "xml" => Category::Graph,
+            _ => Category::Other,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Category::Table => "table",
+            Category::Media => "media",
+            Category::Graph => "graph",
+            Category::Other => "other",
+        }
+    }
+}
+
+/// Name and type of every column of `df`
+pub fn schema_of(df: &DataFrame) -> Vec<Field> {
+    df.get_columns()
+        .iter()
+        .map(|column| Field {
+            name: column.name().to_string(),
+            dtype: column.dtype().to_string(),
+        })
+        .collect()
+}
+
+fn kind(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// The configured data root: `$DOCK_DATA_DIR`, or the current directory
+pub fn data_root() -> PathBuf {
+    match env::var_os(DATA_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from("."),
+    }
+}
+
+impl Catalog {
+    /// Walk `root` and describe every file under it; fails only when `root` cannot be read
+    pub fn scan(root: &Path) -> io::Result<Self> {
+        let mut walk = Walk::default();
+        walk.dir(root)?;
+        let mut files = walk.files;
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        let entries = files
+            .into_iter()
+            .map(|(path, error)| {
+                let error = match error {
+                    Some(error) => error,
+                    None => match Entry::describe(root, &path) {
+                        Ok(entry) => return entry,
+                        Err(error) => error,
+                    },
+                };
+                Entry::unreadable(root, &path, &error)
+            })
+            .collect();
+        Ok(Self {
+            root: root.to_path_buf(),
+            entries,
+        })
+    }
+
+    pub fn tables(&self) -> impl Iterator<Item = &Entry> {
+        self.entries
+            .iter()
+            .filter(|e| e.category == Category::Table)
+    }
+}
+
+/// Files found under a data root
+#[derive(Default)]
+struct Walk {
+    /// Each with why it could not be read, if so
+    files: Vec<(PathBuf, Option<io::Error>)>,
+    /// Directories entered, resolved, so a symlink to a parent is not entered again
+    visited: HashSet<PathBuf>,
+}
+
+impl Walk {
+    fn dir(&mut self, dir: &Path) -> io::Result<()> {
+        if !self.visited.insert(fs::canonicalize(dir)?) {
+            return Ok(());
+        }
+        for entry in fs::read_dir(dir)? {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(e) => {
+                    self.files.push((dir.to_path_buf(), Some(e)));
+                    continue;
+                }
+            };
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                if let Err(e) = self.dir(&path) {
+                    self.files.push((path, Some(e)));
+                }
+            } else if path.is_file() {
+                self.files.push((path, None));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Entry {
+    /// A file or directory listed only with why it could not be read
+    fn unreadable(root: &Path, path: &Path, error: &io::Error) -> Self {
+        Self {
+            path: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+            category: Category::of(path),
+            kind: kind(path),
+            bytes: 0,
+            modified: None,
+            rows: None,
+            columns: None,
+            schema: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    fn describe(root: &Path, path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        let mut entry = Self {
+            path: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+            category: Category::of(path),
+            kind: kind(path),
+            bytes: metadata.len(),
+            modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+            rows: None,
+            columns: None,
+            schema: None,
+            error: None,
+        };
+        if let Some(format) = Format::from_path(path) {
+            // Counting rows skips parsing them, so the first rows are read to catch a
+            // malformed file
+            let profile = DataSource::open_as(path, format).and_then(|source| {
+                source.window(0, PROBE_ROWS)?;
+                Ok((source.count_rows()?, source))
+            });
+            match profile {
+                Ok((rows, source)) => {
+                    entry.rows = Some(rows);
+                    entry.columns = Some(source.schema().len());
+                    entry.schema = Some(
+                        source
+                            .schema()
+                            .iter()
+                            .map(|(name, dtype)| Field {
+                                name: name.to_string(),
+                                dtype: dtype.to_string(),
+                            })
+                            .collect(),
+                    );
+                }
+                Err(e) => entry.error = Some(e.to_string()),
+            }
+        }
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_profiles_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("raw")).unwrap();
+        fs::create_dir_all(root.join(".dock")).unwrap();
+        fs::write(root.join("raw/churn.csv"), "age,gender\n23,M\n31,F\n").unwrap();
+        fs::write(root.join("broken.csv"), "a,b\n1,2,3,4\n\"").unwrap();
+        fs::write(root.join("notes.txt"), "hello").unwrap();
+        fs::write(root.join("report.html"), "<html/>").unwrap();
+        fs::write(root.join("model.bin"), [0u8; 4]).unwrap();
+        fs::write(root.join(".dock/index.jsonl"), "").unwrap();
+
+        let catalog = Catalog::scan(root).unwrap();
+        let paths: Vec<&Path> = catalog.entries.iter().map(|e| e.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("broken.csv"),
+                Path::new("model.bin"),
+                Path::new("notes.txt"),
+                Path::new("raw/churn.csv"),
+                Path::new("report.html"),
+            ]
+        );
+        let categories: Vec<Category> = catalog.entries.iter().map(|e| e.category).collect();
+        assert_eq!(
+            categories,
+            vec![
+                Category::Table,
+                Category::Other,
+                Category::Media,
+                Category::Table,
+                Category::Graph
+            ]
+        );
+
+        let churn = &catalog.entries[3];
+        assert_eq!((churn.rows, churn.columns), (Some(2), Some(2)));
+        assert_eq!(churn.bytes, 21);
+        assert!(churn.modified.is_some());
+        let schema = churn.schema.as_ref().unwrap();
+        assert_eq!(schema[0].name, "age");
+        assert_eq!(schema[1].dtype, "str");
+        assert!(catalog.entries[0].error.is_some());
+        assert_eq!(catalog.tables().count(), 2);
+
+        let json = serde_json::to_string(&catalog).unwrap();
+        assert!(json.contains("\"category\":\"table\""));
+        assert_eq!(serde_json::from_str::<Catalog>(&json).unwrap(), catalog);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_survives_symlink_cycles_and_unreadable_dirs() {
+        use std::os::unix::fs::{PermissionsExt, symlink};
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("raw")).unwrap();
+        fs::write(root.join("raw/churn.csv"), "age\n23\n").unwrap();
+        symlink(root, root.join("raw/up")).unwrap();
+        fs::create_dir(root.join("locked")).unwrap();
+        fs::write(root.join("locked/secret.csv"), "a\n1\n").unwrap();
+        fs::set_permissions(root.join("locked"), fs::Permissions::from_mode(0o000)).unwrap();
+
+        let catalog = Catalog::scan(root).unwrap();
+        let churn: Vec<&Entry> = catalog
+            .entries
+            .iter()
+            .filter(|e| e.path.ends_with("churn.csv"))
+            .collect();
+        assert_eq!(churn.len(), 1);
+        assert_eq!(churn[0].rows, Some(1));
+        // Permissions do not stop root, which reads the directory anyway
+        if fs::read_dir(root.join("locked")).is_err() {
+            let locked = catalog
+                .entries
+                .iter()
+                .find(|e| e.path == Path::new("locked"))
+                .unwrap();
+            assert!(locked.error.is_some());
+        }
+        fs::set_permissions(root.join("locked"), fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(Catalog::scan(&root.join("missing")).is_err());
+    }
+}
@@ -0,0 +1,700 @@
+//! Distributions of columns
+//!
+//! Quantities get a histogram, quantiles, moments and the best fit among the normal,
+//! lognormal and uniform families; categories get their value counts. Which a column gets
+//! follows its measurement level, so a numeric region code is counted rather than binned.
+//!
+//! [`Distribution::of`] reads a column in memory; [`Distribution::scan`] aggregates it in a
+//! query instead, for datasets too large to collect.
+
+use std::fmt;
+
+use polars::export::num::NumCast;
+use polars::prelude::{
+    DataFrame, DataType as PolarsType, Expr, LazyFrame, PolarsResult, QuantileInterpolOptions,
+    Series, col, len, lit,
+};
+use serde::Serialize;
+
+use super::DataType as Level;
+
+/// Most values listed for a categorical column; the rest are summed in `other`
+pub const TOP_VALUES: usize = 20;
+
+/// Most bins of a histogram
+const MAX_BINS: usize = 30;
+
+/// Name of the column a scan aggregates
+const VALUE: &str = "value";
+
+/// How the values of a column are spread
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Distribution {
+    pub column: String,
+    pub level: Level,
+    /// Values present
+    pub count: usize,
+    pub nulls: usize,
+    pub shape: Shape,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Shape {
+    Numeric(Numeric),
+    Categorical(Categorical),
+}
+
+/// Distribution of a quantity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Numeric {
+    /// Bins of equal width from the smallest to the largest value, by Sturges' rule
+    pub bins: Vec<Bin>,
+    pub quantiles: Quantiles,
+    pub mean: f64,
+    /// Sample standard deviation
+    pub std: f64,
+    /// Skewness, 0 for a symmetric distribution
+    pub skew: f64,
+    /// Excess kurtosis, 0 for a normal distribution
+    pub kurtosis: f64,
+    /// `None` when no family fits, such as with fewer than 3 values
+    pub fit: Option<Fit>,
+}
+
+/// Values from `start` up to `end`, the last bin including `end`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+/// Quantiles, interpolated between values
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Quantiles {
+    pub min: f64,
+    pub p5: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+/// Counts of the values of a category
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Categorical {
+    /// The [`TOP_VALUES`] most frequent values, most frequent first; for ordinal columns
+    /// the values are in order instead
+    pub values: Vec<(String, usize)>,
+    /// Values not listed
+    pub other: usize,
+    pub distinct: usize,
+}
+
+/// A family of distributions with the parameters that fit a column best
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "family", rename_all = "snake_case")]
+pub enum Family {
+    Normal {
+        mean: f64,
+        std: f64,
+    },
+    /// The logarithms of the values are normal
+    LogNormal {
+        mu: f64,
+        sigma: f64,
+    },
+    Uniform {
+        min: f64,
+        max: f64,
+    },
+}
+
+/// The family fitting a column best
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Fit {
+    pub family: Family,
+    /// Kolmogorov–Smirnov distance between the values and the family: the largest gap
+    /// between their cumulative distributions, from 0 (perfect) to 1
+    pub distance: f64,
+}
+
+impl Distribution {
+    /// The distribution of `series` at `level`. Numeric columns of quantities are binned;
+    /// every other column, including dates, is counted by value.
+    pub fn of(series: &Series, level: Level) -> PolarsResult<Self> {
+        let nulls = series.null_count();
+        let shape = if series.dtype().is_numeric() && !level.is_categorical() {
+            let values = series.cast(&PolarsType::Float64)?;
+            let mut values: Vec<f64> = values.f64()?.into_iter().flatten().collect();
+            values.retain(|x| x.is_finite());
+            values.sort_by(f64::total_cmp);
+            match Numeric::of(&values) {
+                Some(numeric) => Shape::Numeric(numeric),
+                None => Shape::Categorical(Categorical::of(series, level)?),
+            }
+        } else {
+            Shape::Categorical(Categorical::of(series, level)?)
+        };
+        Ok(Self {
+            column: series.name().to_string(),
+            level,
+            count: series.len() - nulls,
+            nulls,
+            shape,
+        })
+    }
+
+    /// The distribution of `column`, of type `dtype`, over every row of `frame`, like
+    /// [`Distribution::of`] but without collecting the column: the histogram, quantiles and
+    /// moments are aggregated by the query, and only the counts of each category come back.
+    /// The best fit is judged at the edges of the histogram's bins.
+    pub fn scan(
+        frame: LazyFrame,
+        column: &str,
+        dtype: &PolarsType,
+        level: Level,
+    ) -> PolarsResult<Self> {
+        let counts = frame
+            .clone()
+            .select([
+                col(column).count().alias("count"),
+                col(column).null_count().alias("nulls"),
+            ])
+            .collect()?;
+        let shape = if dtype.is_numeric() && !level.is_categorical() {
+            let values = frame
+                .clone()
+                .select([col(column).cast(PolarsType::Float64).alias(VALUE)])
+                .filter(col(VALUE).is_finite());
+            match Numeric::scan(values)? {
+                Some(numeric) => Shape::Numeric(numeric),
+                None => Shape::Categorical(Categorical::scan(frame, column, dtype, level)?),
+            }
+        } else {
+            Shape::Categorical(Categorical::scan(frame, column, dtype, level)?)
+        };
+        Ok(Self {
+            column: column.to_string(),
+            level,
+            count: extract(&counts, "count")?.unwrap_or_default(),
+            nulls: extract(&counts, "nulls")?.unwrap_or_default(),
+            shape,
+        })
+    }
+}
+
+impl Numeric {
+    /// `None` without any value
+    fn of(sorted: &[f64]) -> Option<Self> {
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let moment = |k: i32| sorted.iter().map(|x| (x - mean).powi(k)).sum::<f64>() / n;
+        let (m2, m3, m4) = (moment(2), moment(3), moment(4));
+        let std = (m2 * n / (n - 1.0).max(1.0)).sqrt();
+        let (skew, kurtosis) = moments(m2, m3, m4);
+
+        Some(Self {
+            bins: bins(sorted, min, max),
+            quantiles: Quantiles {
+                min,
+                p5: quantile(sorted, 0.05),
+                q1: quantile(sorted, 0.25),
+                median: quantile(sorted, 0.5),
+                q3: quantile(sorted, 0.75),
+                p95: quantile(sorted, 0.95),
+                max,
+            },
+            mean,
+            std,
+            skew,
+            kurtosis,
+            fit: Fit::best(sorted, mean, std),
+        })
+    }
+
+    /// `None` without any value in the `VALUE` column of `values`
+    fn scan(values: LazyFrame) -> PolarsResult<Option<Self>> {
+        let x = || col(VALUE);
+        let quantile = |q: f64| x().quantile(lit(q), QuantileInterpolOptions::Linear);
+        let first = values
+            .clone()
+            .select([
+                x().count().alias("n"),
+                x().min().alias("min"),
+                x().max().alias("max"),
+                x().mean().alias("mean"),
+                quantile(0.05).alias("p5"),
+                quantile(0.25).alias("q1"),
+                quantile(0.5).alias("median"),
+                quantile(0.75).alias("q3"),
+                quantile(0.95).alias("p95"),
+                x().log(std::f64::consts::E).mean().alias("mu"),
+                x().log(std::f64::consts::E).std(1).alias("sigma"),
+            ])
+            .collect()?;
+        let n: usize = extract(&first, "n")?.unwrap_or_default();
+        let (Some(min), Some(max), Some(mean)) = (
+            extract(&first, "min")?,
+            extract(&first, "max")?,
+            extract(&first, "mean")?,
+        ) else {
+            return Ok(None);
+        };
+        let get = |name| -> PolarsResult<f64> { Ok(extract(&first, name)?.unwrap_or(f64::NAN)) };
+
+        // Central moments and the bins need the mean and range found above
+        let mut bins = bin_edges(n, min, max);
+        let deviation = || x() - lit(mean);
+        let mut exprs = vec![
+            (deviation() * deviation()).mean().alias("m2"),
+            (deviation() * deviation() * deviation()).mean().alias("m3"),
+            (deviation() * deviation() * deviation() * deviation())
+                .mean()
+                .alias("m4"),
+        ];
+        if let [bin] = bins.as_mut_slice() {
+            bin.count = n;
+        } else {
+            let last = bins.len() as i64 - 1;
+            let width = bins[0].end - bins[0].start;
+            let index = || ((x() - lit(min)) / lit(width)).cast(PolarsType::Int64);
+            exprs.extend((0..=last).map(|i| {
+                let inside: Expr = if i == last {
+                    index().gt_eq(lit(i))
+                } else {
+                    index().eq(lit(i))
+                };
+                inside.sum().alias(&format!("bin{i}"))
+            }));
+        }
+        let second = values.select(exprs).collect()?;
+        if bins.len() > 1 {
+            for (i, bin) in bins.iter_mut().enumerate() {
+                bin.count = extract(&second, &format!("bin{i}"))?.unwrap_or_default();
+            }
+        }
+
+        let nf = n as f64;
+        let m2: f64 = extract(&second, "m2")?.unwrap_or_default();
+        let m3: f64 = extract(&second, "m3")?.unwrap_or_default();
+        let m4: f64 = extract(&second, "m4")?.unwrap_or_default();
+        let std = (m2 * nf / (nf - 1.0).max(1.0)).sqrt();
+        let (skew, kurtosis) = moments(m2, m3, m4);
+        let log = if min > 0.0 {
+            Some((get("mu")?, get("sigma")?))
+        } else {
+            None
+        };
+        let fit = Fit::closest(n, mean, std, min, max, log, |family| {
+            binned_distance(&bins, n, family)
+        });
+
+        Ok(Some(Self {
+            quantiles: Quantiles {
+                min,
+                p5: get("p5")?,
+                q1: get("q1")?,
+                median: get("median")?,
+                q3: get("q3")?,
+                p95: get("p95")?,
+                max,
+            },
+            bins,
+            mean,
+            std,
+            skew,
+            kurtosis,
+            fit,
+        }))
+    }
+}
+
+impl Categorical {
+    fn of(series: &Series, level: Level) -> PolarsResult<Self> {
+        let texts = series.cast(&PolarsType::String)?;
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        let mut values: Vec<&str> = texts.str()?.into_iter().flatten().collect();
+        values.sort_unstable();
+        for value in values {
+            match counts.last_mut() {
+                Some((last, count)) if last == value => *count += 1,
+                _ => counts.push((value.to_string(), 1)),
+            }
+        }
+        Ok(Self::from_counts(counts, series.dtype(), level))
+    }
+
+    /// Counts of the values of `column` in `frame`, grouped by the query
+    fn scan(
+        frame: LazyFrame,
+        column: &str,
+        dtype: &PolarsType,
+        level: Level,
+    ) -> PolarsResult<Self> {
+        let grouped = frame
+            .select([col(column).cast(PolarsType::String).alias(VALUE)])
+            .drop_nulls(None)
+            .group_by([col(VALUE)])
+            .agg([len().alias("count")])
+            .collect()?;
+        let values = grouped.column(VALUE)?.str()?;
+        let tallies = grouped.column("count")?.cast(&PolarsType::UInt64)?;
+        let mut counts: Vec<(String, usize)> = values
+            .into_iter()
+            .zip(tallies.u64()?)
+            .filter_map(|(value, count)| Some((value?.to_string(), count? as usize)))
+            .collect();
+        counts.sort_unstable();
+        Ok(Self::from_counts(counts, dtype, level))
+    }
+
+    /// From the counts of every value, in value order
+    fn from_counts(mut counts: Vec<(String, usize)>, dtype: &PolarsType, level: Level) -> Self {
+        let distinct = counts.len();
+        if level == Level::Ordinal {
+            // Numeric levels in numeric order, so 10 comes after 9
+            if dtype.is_numeric() {
+                let number = |value: &str| value.parse::<f64>().unwrap_or(f64::NAN);
+                counts.sort_by(|(a, _), (b, _)| number(a).total_cmp(&number(b)));
+            }
+        } else {
+            counts.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+        }
+        let other = counts.iter().skip(TOP_VALUES).map(|(_, count)| count).sum();
+        counts.truncate(TOP_VALUES);
+        Self {
+            values: counts,
+            other,
+            distinct,
+        }
+    }
+}
+
+impl Family {
+    /// Cumulative probability of the values up to `x`
+    pub fn cdf(&self, x: f64) -> f64 {
+        match *self {
+            Family::Normal { mean, std } => normal_cdf((x - mean) / std),
+            Family::LogNormal { mu, sigma } if x > 0.0 => normal_cdf((x.ln() - mu) / sigma),
+            Family::LogNormal { .. } => 0.0,
+            Family::Uniform { min, max } => ((x - min) / (max - min)).clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Fit {
+    /// The family closest to `sorted` values, fitted by their moments and range
+    fn best(sorted: &[f64], mean: f64, std: f64) -> Option<Self> {
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let log = (min > 0.0 && sorted.len() > 1).then(|| {
+            let logs: Vec<f64> = sorted.iter().map(|x| x.ln()).collect();
+            let n = logs.len() as f64;
+            let mu = logs.iter().sum::<f64>() / n;
+            let sigma = (logs.iter().map(|x| (x - mu).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+            (mu, sigma)
+        });
+        Self::closest(sorted.len(), mean, std, min, max, log, |family| {
+            ks_distance(sorted, family)
+        })
+    }
+
+    /// Of the families fitted to `n` values by their moments, range and the `mu` and
+    /// `sigma` of their logarithms when all are positive, the one `distance` finds closest
+    fn closest(
+        n: usize,
+        mean: f64,
+        std: f64,
+        min: f64,
+        max: f64,
+        log: Option<(f64, f64)>,
+        distance: impl Fn(&Family) -> f64,
+    ) -> Option<Self> {
+        if n < 3 || std <= 0.0 {
+            return None;
+        }
+        let mut candidates = vec![Family::Normal { mean, std }, Family::Uniform { min, max }];
+        if let Some((mu, sigma)) = log.filter(|(_, sigma)| *sigma > 0.0) {
+            candidates.push(Family::LogNormal { mu, sigma });
+        }
+        candidates
+            .into_iter()
+            .map(|family| Fit {
+                family,
+                distance: distance(&family),
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Family::Normal { mean, std } => write!(f, "normal(mean {mean:.4}, std {std:.4})"),
+            Family::LogNormal { mu, sigma } => {
+                write!(f, "lognormal(mu {mu:.4}, sigma {sigma:.4})")
+            }
+            Family::Uniform { min, max } => write!(f, "uniform({min:.4}, {max:.4})"),
+        }
+    }
+}
+
+/// Skewness and excess kurtosis from the second to fourth central moments
+fn moments(m2: f64, m3: f64, m4: f64) -> (f64, f64) {
+    if m2 > 0.0 {
+        (m3 / m2.powf(1.5), m4 / (m2 * m2) - 3.0)
+    } else {
+        (0.0, 0.0)
+    }
+}
+
+/// Equal-width bins of `sorted` values; one bin when they are all the same
+fn bins(sorted: &[f64], min: f64, max: f64) -> Vec<Bin> {
+    let mut bins = bin_edges(sorted.len(), min, max);
+    if let [bin] = bins.as_mut_slice() {
+        bin.count = sorted.len();
+        return bins;
+    }
+    let width = bins[0].end - bins[0].start;
+    for x in sorted {
+        let index = (((x - min) / width) as usize).min(bins.len() - 1);
+        bins[index].count += 1;
+    }
+    bins
+}
+
+/// Empty bins for `n` values from `min` to `max`, by Sturges' rule
+fn bin_edges(n: usize, min: f64, max: f64) -> Vec<Bin> {
+    if max <= min {
+        return vec![Bin {
+            start: min,
+            end: max,
+            count: 0,
+        }];
+    }
+    let count = ((n as f64).log2().ceil() as usize + 1).clamp(1, MAX_BINS);
+    let width = (max - min) / count as f64;
+    (0..count)
+        .map(|i| Bin {
+            start: min + width * i as f64,
+            end: if i + 1 == count {
+                max
+            } else {
+                min + width * (i + 1) as f64
+            },
+            count: 0,
+        })
+        .collect()
+}
+
+/// Value of the one-row aggregate `name`, `None` when it is missing or null
+fn extract<T: NumCast>(aggregates: &DataFrame, name: &str) -> PolarsResult<Option<T>> {
+    Ok(aggregates.column(name)?.get(0)?.extract())
+}
+
+/// Quantile `q` of `sorted` values, interpolating linearly between neighbours
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+fn ks_distance(sorted: &[f64], family: &Family) -> f64 {
+    let n = sorted.len() as f64;
+    sorted
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let p = family.cdf(x);
+            (p - i as f64 / n).abs().max(((i + 1) as f64 / n - p).abs())
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Kolmogorov–Smirnov distance between `family` and `n` values binned into `bins`, judged
+/// where the bins start and end
+fn binned_distance(bins: &[Bin], n: usize, family: &Family) -> f64 {
+    let mut below = 0;
+    let mut distance = family.cdf(bins[0].start);
+    for bin in bins {
+        below += bin.count;
+        distance = distance.max((below as f64 / n as f64 - family.cdf(bin.end)).abs());
+    }
+    distance
+}
+
+/// Standard normal cumulative distribution, by the Abramowitz and Stegun approximation of
+/// erf (7.1.26), accurate to 1.5e-7
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::NamedFrom;
+
+    fn numeric(distribution: &Distribution) -> &Numeric {
+        match &distribution.shape {
+            Shape::Numeric(numeric) => numeric,
+            shape => panic!("expected a numeric shape, got {shape:?}"),
+        }
+    }
+
+    #[test]
+    fn test_numeric_distribution_and_fit() {
+        // Evenly spread values are uniform
+        let uniform: Vec<Option<f64>> = (0..1000).map(|i| Some(i as f64)).chain([None]).collect();
+        let distribution = Distribution::of(&Series::new("x", uniform), Level::Ratio).unwrap();
+        assert_eq!((distribution.count, distribution.nulls), (1000, 1));
+        let shape = numeric(&distribution);
+        assert_eq!(shape.bins.len(), 11);
+        assert_eq!(shape.bins.iter().map(|bin| bin.count).sum::<usize>(), 1000);
+        assert_eq!((shape.bins[0].start, shape.bins[10].end), (0.0, 999.0));
+        assert_eq!(shape.quantiles.median, 499.5);
+        assert_eq!(shape.quantiles.q1, 249.75);
+        assert!(shape.skew.abs() < 1e-9);
+        assert!((shape.kurtosis + 1.2).abs() < 0.01);
+        let fit = shape.fit.unwrap();
+        assert!(matches!(fit.family, Family::Uniform { .. }));
+        assert!(fit.distance < 0.01);
+
+        // Exponentials of evenly spread normal quantiles are lognormal
+        let values: Vec<f64> = (1..1000)
+            .map(|i| (probit(i as f64 / 1000.0) * 0.8 + 1.0).exp())
+            .collect();
+        let distribution = Distribution::of(&Series::new("x", values), Level::Ratio).unwrap();
+        let shape = numeric(&distribution);
+        assert!(shape.skew > 1.0);
+        match shape.fit.unwrap().family {
+            Family::LogNormal { mu, sigma } => {
+                assert!((mu - 1.0).abs() < 0.01 && (sigma - 0.8).abs() < 0.05)
+            }
+            family => panic!("expected lognormal, got {family}"),
+        }
+
+        // The normal quantiles themselves are normal
+        let values: Vec<f64> = (1..1000).map(|i| probit(i as f64 / 1000.0)).collect();
+        let distribution = Distribution::of(&Series::new("x", values), Level::Interval).unwrap();
+        let fit = numeric(&distribution).fit.unwrap();
+        assert!(
+            matches!(fit.family, Family::Normal { .. }),
+            "{}",
+            fit.family
+        );
+
+        let constant = Distribution::of(&Series::new("x", [2.0, 2.0]), Level::Ratio).unwrap();
+        let shape = numeric(&constant);
+        assert_eq!((shape.bins.len(), shape.fit), (1, None));
+    }
+
+    #[test]
+    fn test_categorical_distribution() {
+        let region = Series::new("region", [Some(3i64), Some(10), Some(3), None, Some(9)]);
+        let nominal = Distribution::of(&region, Level::Nominal).unwrap();
+        let Shape::Categorical(counts) = &nominal.shape else {
+            panic!("expected value counts");
+        };
+        assert_eq!(
+            counts.values,
+            [
+                ("3".to_string(), 2),
+                ("10".to_string(), 1),
+                ("9".to_string(), 1)
+            ]
+        );
+        assert_eq!((counts.distinct, counts.other, nominal.nulls), (3, 0, 1));
+
+        // Ordinal levels keep their order
+        let ordinal = Distribution::of(&region, Level::Ordinal).unwrap();
+        let Shape::Categorical(counts) = &ordinal.shape else {
+            panic!("expected value counts");
+        };
+        let order: Vec<&str> = counts.values.iter().map(|(v, _)| v.as_str()).collect();
+        assert_eq!(order, ["3", "9", "10"]);
+
+        let ids: Vec<String> = (0..25).map(|i| format!("id{i:02}")).collect();
+        let ids = Distribution::of(&Series::new("id", ids), Level::Nominal).unwrap();
+        let Shape::Categorical(counts) = &ids.shape else {
+            panic!("expected value counts");
+        };
+        assert_eq!(
+            (counts.values.len(), counts.other, counts.distinct),
+            (20, 5, 25)
+        );
+
+        let json = serde_json::to_value(&ids).unwrap();
+        assert_eq!(json["shape"]["kind"], "categorical");
+        assert_eq!(json["level"], "nominal");
+    }
+
+    #[test]
+    fn test_scan_agrees_with_reading_the_column() {
+        use polars::prelude::{DataFrame, IntoLazy};
+
+        let normal: Vec<Option<f64>> = (1..1000)
+            .map(|i| Some(probit(i as f64 / 1000.0)))
+            .chain([None, Some(f64::NAN)])
+            .collect();
+        let lognormal: Vec<f64> = (1..1000)
+            .map(|i| (probit(i as f64 / 1000.0) * 0.8 + 1.0).exp())
+            .collect();
+        let region = [Some(3i64), Some(10), Some(3), None, Some(9)];
+        let cases = [
+            (Series::new("x", normal), Level::Interval),
+            (Series::new("x", lognormal), Level::Ratio),
+            (Series::new("x", [2.0, 2.0]), Level::Ratio),
+            (Series::new("x", region), Level::Nominal),
+            (Series::new("x", region), Level::Ordinal),
+            (Series::new("x", [None::<f64>, None]), Level::Ratio),
+        ];
+        for (series, level) in cases {
+            let read = Distribution::of(&series, level).unwrap();
+            let frame = DataFrame::new(vec![series.clone()]).unwrap().lazy();
+            let scanned = Distribution::scan(frame, "x", series.dtype(), level).unwrap();
+            assert_eq!((scanned.count, scanned.nulls), (read.count, read.nulls));
+            match (&scanned.shape, &read.shape) {
+                (Shape::Numeric(scanned), Shape::Numeric(read)) => {
+                    assert_eq!(scanned.bins, read.bins);
+                    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+                    let (s, r) = (&scanned.quantiles, &read.quantiles);
+                    assert!(close(s.p5, r.p5) && close(s.median, r.median), "{s:?}");
+                    assert!(close(s.q1, r.q1) && close(s.q3, r.q3) && close(s.p95, r.p95));
+                    assert!(close(scanned.mean, read.mean) && close(scanned.std, read.std));
+                    assert!(close(scanned.skew, read.skew));
+                    assert!(close(scanned.kurtosis, read.kurtosis));
+                    let family = |fit: Option<Fit>| fit.map(|fit| fit.family.to_string());
+                    assert_eq!(family(scanned.fit), family(read.fit));
+                }
+                (scanned, read) => assert_eq!(scanned, read),
+            }
+        }
+    }
+
+    /// Inverse of the standard normal distribution, by bisection
+    fn probit(p: f64) -> f64 {
+        let (mut low, mut high) = (-10.0, 10.0);
+        for _ in 0..100 {
+            let mid = (low + high) / 2.0;
+            if normal_cdf(mid) < p {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (low + high) / 2.0
+    }
+}
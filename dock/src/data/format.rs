@@ -0,0 +1,308 @@
+//! Reading and writing datasets in every table format of `settypes.md` (FR-DOCK-04)
+//!
+//! The format of a file to read is told by its first bytes where they are decisive
+//! (Parquet files start with a magic number, XLSX files are zip archives holding
+//! `xl/workbook.xml`), then by its extension. Only a file without an extension has its
+//! text sniffed: JSON starts with `[` or `{`, a first line with more tabs than commas is
+//! TSV and other text is CSV. Files to write take the format of their extension.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use calamine::{Data, Reader, Xlsx, open_workbook};
+use polars::prelude::*;
+use rust_xlsxwriter::Workbook;
+
+/// Bytes read to sniff the format of a file
+const SNIFF_LEN: u64 = 8 * 1024;
+
+/// File formats dock reads and writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Tsv,
+    /// A JSON array of row objects
+    Json,
+    /// One JSON row object per line
+    NdJson,
+    Parquet,
+    /// The first worksheet of an Excel workbook, with a header row
+    Xlsx,
+}
+
+impl Format {
+    /// The format named by the extension of `path`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Format::Csv),
+            "tsv" => Some(Format::Tsv),
+            "json" => Some(Format::Json),
+            "ndjson" | "jsonl" => Some(Format::NdJson),
+            "parquet" => Some(Format::Parquet),
+            "xlsx" => Some(Format::Xlsx),
+            _ => None,
+        }
+    }
+
+    /// The format of the existing file at `path`, `None` when it is not a table
+    pub fn detect(path: &Path) -> io::Result<Option<Self>> {
+        let mut head = Vec::new();
+        File::open(path)?.take(SNIFF_LEN).read_to_end(&mut head)?;
+        if head.starts_with(b"PAR1") {
+            return Ok(Some(Format::Parquet));
+        }
+        if head.starts_with(b"PK\x03\x04") {
+            // A zip archive; of the formats dock reads only XLSX is one
+            return Ok(is_workbook(path).then_some(Format::Xlsx));
+        }
+        Ok(match path.extension() {
+            Some(_) => Self::from_path(path),
+            None => Self::from_text(&head),
+        })
+    }
+
+    fn from_text(head: &[u8]) -> Option<Self> {
+        if head.contains(&0) {
+            return None;
+        }
+        let text = String::from_utf8_lossy(head);
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        match text.chars().next()? {
+            '[' => Some(Format::Json),
+            '{' => Some(Format::NdJson),
+            _ => {
+                let header = text.lines().next().unwrap_or_default();
+                let count = |c| header.matches(c).count();
+                if count('\t') > count(',') {
+                    Some(Format::Tsv)
+                } else {
+                    Some(Format::Csv)
+                }
+            }
+        }
+    }
+}
+
+/// Whether the zip archive at `path` is an Excel workbook
+fn is_workbook(path: &Path) -> bool {
+    File::open(path)
+        .ok()
+        .and_then(|file| ::zip::ZipArchive::new(file).ok())
+        .is_some_and(|archive| archive.index_for_name("xl/workbook.xml").is_some())
+}
+
+/// Read every row of a dataset
+pub fn read_data_frame(path: &Path, format: Format) -> PolarsResult<DataFrame> {
+    match format {
+        Format::Csv | Format::Tsv => CsvReadOptions::default()
+            .with_has_header(true)
+            .with_parse_options(CsvParseOptions::default().with_separator(separator(format)))
+            .try_into_reader_with_file_path(Some(path.to_path_buf()))?
+            .finish(),
+        Format::Json | Format::NdJson => JsonReader::new(File::open(path)?)
+            .with_json_format(json_format(format))
+            .finish(),
+        Format::Parquet => ParquetReader::new(File::open(path)?).finish(),
+        Format::Xlsx => read_xlsx(path),
+    }
+}
+
+/// Read every row of the dataset at `path`, whatever its format
+pub fn load_data_frame(path: &Path) -> PolarsResult<DataFrame> {
+    match Format::detect(path)? {
+        Some(format) => read_data_frame(path, format),
+        None => polars_bail!(ComputeError: "{} is not a table", path.display()),
+    }
+}
+
+/// Write `df` to `path`, replacing the file
+pub fn write_data_frame(df: &mut DataFrame, path: &Path, format: Format) -> PolarsResult<()> {
+    if format == Format::Xlsx {
+        return write_xlsx(df, path);
+    }
+    let mut file = File::create(path)?;
+    match format {
+        Format::Csv | Format::Tsv => CsvWriter::new(&mut file)
+            .include_header(true)
+            .with_separator(separator(format))
+            .finish(df),
+        Format::Json | Format::NdJson => JsonWriter::new(&mut file)
+            .with_json_format(json_format(format))
+            .finish(df),
+        Format::Parquet => ParquetWriter::new(&mut file).finish(df).map(|_| ()),
+        Format::Xlsx => unreachable!("written above"),
+    }
+}
+
+pub(super) fn separator(format: Format) -> u8 {
+    if format == Format::Tsv { b'\t' } else { b',' }
+}
+
+fn json_format(format: Format) -> JsonFormat {
+    if format == Format::NdJson {
+        JsonFormat::JsonLines
+    } else {
+        JsonFormat::Json
+    }
+}
+
+/// The first worksheet, typing each column by its cells: integers, floats, booleans or
+/// else text. Empty cells are missing values.
+fn read_xlsx(path: &Path) -> PolarsResult<DataFrame> {
+    let xlsx_error = |e: calamine::XlsxError| polars_err!(ComputeError: "{}: {e}", path.display());
+    let mut workbook: Xlsx<_> = open_workbook(path).map_err(xlsx_error)?;
+    let range = match workbook.worksheet_range_at(0) {
+        Some(range) => range.map_err(xlsx_error)?,
+        None => polars_bail!(ComputeError: "{} has no worksheet", path.display()),
+    };
+    let mut rows = range.rows();
+    let Some(header) = rows.next() else {
+        return Ok(DataFrame::empty());
+    };
+    let mut cells: Vec<Vec<&Data>> = vec![Vec::new(); header.len()];
+    for row in rows {
+        for (column, cell) in cells.iter_mut().zip(row) {
+            column.push(cell);
+        }
+    }
+
+    let columns = header
+        .iter()
+        .zip(&cells)
+        .map(|(name, cells)| xlsx_column(&name.to_string(), cells))
+        .collect();
+    DataFrame::new(columns)
+}
+
+fn xlsx_column(name: &str, cells: &[&Data]) -> Series {
+    let present = || cells.iter().filter(|cell| **cell != &Data::Empty);
+    let number = |cell: &Data| match cell {
+        Data::Int(n) => Some(*n as f64),
+        Data::Float(x) => Some(*x),
+        _ => None,
+    };
+
+    if present().all(|cell| number(cell).is_some_and(|x| x.fract() == 0.0)) {
+        let values: Vec<Option<i64>> = cells
+            .iter()
+            .map(|cell| number(cell).map(|x| x as i64))
+            .collect();
+        Series::new(name, values)
+    } else if present().all(|cell| number(cell).is_some()) {
+        let values: Vec<Option<f64>> = cells.iter().map(|cell| number(cell)).collect();
+        Series::new(name, values)
+    } else if present().all(|cell| matches!(cell, Data::Bool(_))) {
+        let values: Vec<Option<bool>> = cells
+            .iter()
+            .map(|cell| match cell {
+                Data::Bool(b) => Some(*b),
+                _ => None,
+            })
+            .collect();
+        Series::new(name, values)
+    } else {
+        let values: Vec<Option<String>> = cells
+            .iter()
+            .map(|cell| (**cell != Data::Empty).then(|| cell.to_string()))
+            .collect();
+        Series::new(name, values)
+    }
+}
+
+/// One worksheet with a header row; missing values are left empty
+fn write_xlsx(df: &DataFrame, path: &Path) -> PolarsResult<()> {
+    let xlsx_error =
+        |e: rust_xlsxwriter::XlsxError| polars_err!(ComputeError: "{}: {e}", path.display());
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    for (index, column) in df.get_columns().iter().enumerate() {
+        let col = u16::try_from(index)
+            .map_err(|_| polars_err!(ComputeError: "too many columns for XLSX"))?;
+        sheet
+            .write_string(0, col, column.name())
+            .map_err(xlsx_error)?;
+        for row in 0..column.len() {
+            let cell = u32::try_from(row + 1)
+                .map_err(|_| polars_err!(ComputeError: "too many rows for XLSX"))?;
+            match column.get(row)? {
+                AnyValue::Null => continue,
+                AnyValue::Boolean(value) => sheet.write_boolean(cell, col, value),
+                AnyValue::String(value) => sheet.write_string(cell, col, value),
+                value if value.is_numeric() => {
+                    sheet.write_number(cell, col, value.extract::<f64>().unwrap_or_default())
+                }
+                value => sheet.write_string(cell, col, value.to_string()),
+            }
+            .map_err(xlsx_error)?;
+        }
+    }
+    workbook.save(path).map_err(xlsx_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_format_round_trips_and_is_detected() {
+        let df = df![
+            "age" => [Some(23i64), None, Some(45)],
+            "income" => [54000.5, 61000.0, 72000.25],
+            "gender" => [Some("M"), Some("F"), None],
+        ]
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        for (name, format) in [
+            ("out.csv", Format::Csv),
+            ("out.tsv", Format::Tsv),
+            ("out.json", Format::Json),
+            ("out.ndjson", Format::NdJson),
+            ("out.parquet", Format::Parquet),
+            ("out.xlsx", Format::Xlsx),
+        ] {
+            let path = dir.path().join(name);
+            assert_eq!(Format::from_path(&path), Some(format));
+            write_data_frame(&mut df.clone(), &path, format).unwrap();
+            assert_eq!(read_data_frame(&path, format).unwrap(), df, "{name}");
+
+            // Without its extension the format is told by the contents
+            let bare = dir.path().join(format!("bare-{name}").replace('.', "-"));
+            std::fs::copy(&path, &bare).unwrap();
+            assert_eq!(Format::detect(&bare).unwrap(), Some(format), "{name}");
+            assert_eq!(load_data_frame(&bare).unwrap(), df, "{name}");
+        }
+
+        // Magic bytes win over a misleading extension
+        let parquet = dir.path().join("mislabelled.csv");
+        std::fs::copy(dir.path().join("out.parquet"), &parquet).unwrap();
+        assert_eq!(Format::detect(&parquet).unwrap(), Some(Format::Parquet));
+
+        let binary = dir.path().join("image");
+        std::fs::write(&binary, [0x89, b'P', b'N', b'G', 0, 0]).unwrap();
+        assert_eq!(Format::detect(&binary).unwrap(), None);
+        assert!(load_data_frame(&binary).is_err());
+        assert_eq!(Format::from_path(Path::new("notes.txt")), None);
+
+        // Text with an extension that is no table format is not sniffed
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "name,age\nada,36\n").unwrap();
+        assert_eq!(Format::detect(&notes).unwrap(), None);
+        let readme = dir.path().join("README.md");
+        std::fs::write(&readme, "# Readme\n").unwrap();
+        assert_eq!(Format::detect(&readme).unwrap(), None);
+
+        // A zip archive is a workbook only when it holds one
+        for name in ["archive.zip", "archive", "archive.xlsx"] {
+            let path = dir.path().join(name);
+            let mut zip = ::zip::ZipWriter::new(File::create(&path).unwrap());
+            let options = ::zip::write::SimpleFileOptions::default()
+                .compression_method(::zip::CompressionMethod::Stored);
+            zip.start_file("data.csv", options).unwrap();
+            std::io::Write::write_all(&mut zip, b"a,b\n1,2\n").unwrap();
+            zip.finish().unwrap();
+            assert_eq!(Format::detect(&path).unwrap(), None, "{name}");
+        }
+    }
+}
@@ -1,31 +1,71 @@
 ///
 /// This file defines common data management functions for csv files and polars
 ///
+use std::path::Path;
+
 use polars::prelude::*;
 
+pub mod catalog;
+pub mod distribution;
+pub mod format;
 pub mod pipeline;
+pub mod semantic;
+pub mod source;
+pub mod versions;
 
-pub enum DataType {
-    Ordinal,
-    Nominal,
-    Interval,
-    Ratio,
-}
+pub use distribution::Distribution;
+pub use format::{Format, load_data_frame, read_data_frame, write_data_frame};
+pub use semantic::{DataType, SemanticSchema};
+pub use source::{ColumnStats, DataSource};
 
+/// Every row of a dataset of any format. Open a [`DataSource`] instead to page through
+/// large files.
 pub fn get_data_frame(file_path: &str) -> PolarsResult<DataFrame> {
-    LazyCsvReader::new(file_path)
-        .with_n_rows(Some(1000))
-        .finish()?
-        .collect()
+    load_data_frame(Path::new(file_path))
+}
+
+/// How `clean` tidies a dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CleanOptions {
+    /// Drop rows with a missing value in any column
+    pub drop_nulls: bool,
+    /// Keep only the first of identical rows
+    pub dedup: bool,
+}
+
+/// Drop columns with no values at all, then apply `options`
+pub fn clean(df: &DataFrame, options: CleanOptions) -> PolarsResult<DataFrame> {
+    let empty: Vec<String> = df
+        .get_columns()
+        .iter()
+        .filter(|column| df.height() > 0 && column.null_count() == df.height())
+        .map(|column| column.name().to_string())
+        .collect();
+    let mut df = df.drop_many(&empty);
+    if options.drop_nulls {
+        df = df.drop_nulls::<String>(None)?;
+    }
+    if options.dedup {
+        df = df.unique_stable(None, UniqueKeepStrategy::First, None)?;
+    }
+    Ok(df)
 }
 
-trait ExtDataFrame {
-    fn dist(&self) -> ();
+pub trait ExtDataFrame {
+    /// The distribution of every column, at the measurement level inferred from its data
+    fn dist(&self) -> PolarsResult<Vec<Distribution>>;
 }
 
 impl ExtDataFrame for DataFrame {
-    fn dist(&self) -> () {
-        todo!()
+    fn dist(&self) -> PolarsResult<Vec<Distribution>> {
+        self.get_columns()
+            .iter()
+            .map(|series| {
+                let stats = ColumnStats::of(series)?;
+                let level = DataType::infer(series.name(), series.dtype(), &stats);
+                Distribution::of(series, level)
+            })
+            .collect()
     }
 }
 
@@ -51,4 +91,60 @@ mod tests {
         let df = get_data_frame("examples/data.csv").expect("Failed to load CSV file");
         assert_eq!(df, ex);
     }
+
+    #[test]
+    pub fn test_dist_follows_inferred_levels() {
+        let df = get_data_frame("examples/data.csv").expect("Failed to load CSV file");
+        let dists = df.dist().unwrap();
+        let levels: Vec<DataType> = dists.iter().map(|d| d.level).collect();
+        assert_eq!(
+            levels,
+            [
+                DataType::Ratio,
+                DataType::Nominal,
+                DataType::Ratio,
+                DataType::Nominal
+            ]
+        );
+        assert!(matches!(dists[0].shape, distribution::Shape::Numeric(_)));
+        // Region codes are counted, not binned
+        assert!(matches!(
+            dists[3].shape,
+            distribution::Shape::Categorical(_)
+        ));
+    }
+
+    #[test]
+    pub fn test_clean_and_round_trip_formats() {
+        let df = df![
+            "age" => &[Some(23i64), Some(23), None, Some(45)],
+            "gender" => &[Some("M"), Some("M"), Some("F"), Some("M")],
+            "notes" => &[None::<&str>, None, None, None]
+        ]
+        .expect("Cannot create test df");
+
+        let cleaned = clean(
+            &df,
+            CleanOptions {
+                drop_nulls: true,
+                dedup: true,
+            },
+        )
+        .unwrap();
+        let expected = df!["age" => &[23i64, 45], "gender" => &["M", "M"]].unwrap();
+        assert_eq!(cleaned, expected);
+        assert_eq!(clean(&df, CleanOptions::default()).unwrap().shape(), (4, 2));
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["out.csv", "out.tsv", "out.json", "out.ndjson"] {
+            let path = dir.path().join(name);
+            let format = Format::from_path(&path).unwrap();
+            write_data_frame(&mut cleaned.clone(), &path, format).unwrap();
+            assert_eq!(read_data_frame(&path, format).unwrap(), expected, "{name}");
+        }
+        assert_eq!(
+            Format::from_path(Path::new("data.parquet")),
+            Some(Format::Parquet)
+        );
+    }
 }
@@ -1,34 +1,690 @@
+//! Preprocessing pipelines, fitted on one dataset and applied to others (FR-DOCK-02)
+//!
+//! `fit` learns what each stage needs from the data it is given, running every stage on
+//! the output of the previous one; `transform` then applies exactly what was learned, so
+//! unseen data is imputed, scaled, encoded and projected like the training data was.
+//!
+//! | Stage     | Strategies                                     | Columns                                |
+//! |-----------|------------------------------------------------|----------------------------------------|
+//! | `Imputer` | `auto`, `mean`, `median`, `mode`, `constant:V` | numeric; text too for mode and constant |
+//! | `Scaler`  | `minmax`, `standard`, `robust`                 | numeric                                |
+//! | `Encoder` | `auto`, `onehot`, `ordinal`                    | text                                   |
+//! | `PCA(n)`  | `n` components named `pc1`, `pc2`, ...         | numeric, without missing values        |
+//!
+//! `constant:V` fills numeric columns only when `V` is a number, and text columns always.
+//!
+//! Given the measurement levels of the columns ([`Pipeline::with_levels`]), numeric
+//! columns that are categories count as text: a numeric region code is encoded, not
+//! scaled, imputed with its mode rather than its mean, and left out of PCA. Without
+//! levels, text is nominal and numbers are ratio. The `auto` strategies pick the default
+//! of each column's level, see [`Level::imputer`] and [`Level::encoder`]. Ordinal text is
+//! coded in the order given by [`Pipeline::with_orders`]; `auto` one-hot encodes it when
+//! no order is given, and `ordinal` then codes it alphabetically.
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
 use polars::prelude::*;
+use thiserror::Error;
+
+use super::DataType as Level;
+
 pub enum Stage<'a> {
     Imputer(&'a str),
     Scaler(&'a str),
-    Encoder,
+    Encoder(&'a str),
     PCA(i32),
 }
 
-pub struct Pipeline {
-    stages: Vec<Stage<'static>>,
+pub struct Pipeline<'a> {
+    stages: Vec<Stage<'a>>,
+    /// Measurement level of columns, by name
+    levels: BTreeMap<String, Level>,
+    /// Categories of ordinal columns from lowest to highest, by name
+    orders: BTreeMap<String, Vec<String>>,
+    /// What each stage learned, set by `fit`
+    fitted: Option<Vec<Fitted>>,
+}
+
+/// Errors
+#[derive(Debug, Error)]
+pub enum PipelineError {
+    #[error(
+        "Invalid stage `{0}`; expected e.g. imputer:mean, scaler:minmax, encoder:onehot or pca:2"
+    )]
+    InvalidStage(String),
+
+    #[error("Unknown {0} strategy `{1}`")]
+    UnknownStrategy(&'static str, String),
+
+    #[error("No column can be imputed with `{0}`, which is text, as there is no text column")]
+    InvalidConstant(String),
+
+    #[error("PCA needs at least 1 component and at most one per numeric column ({1}), not {0}")]
+    Components(i32, usize),
+
+    #[error("Column `{0}` has missing values; impute them before PCA")]
+    Nulls(String),
+
+    #[error("The pipeline was fitted with column `{0}`, which is missing")]
+    MissingColumn(String),
+
+    #[error("Column `{0}` has the value `{1}`, which the ordinal encoder was not fitted with")]
+    UnknownCategory(String, String),
+
+    #[error("The pipeline must be fitted before transforming")]
+    NotFitted,
+
+    #[error(transparent)]
+    Polars(#[from] PolarsError),
+}
+
+/// Value missing entries of a column are replaced with
+#[derive(Debug, Clone, PartialEq)]
+enum Fill {
+    Number(f64),
+    Text(String),
 }
 
-impl Pipeline {
-    pub fn new(stages: Vec<Stage<'static>>) -> Self {
-        Pipeline { stages }
+/// A stage with the state it learned from the data
+#[derive(Debug, Clone, PartialEq)]
+enum Fitted {
+    Imputer(Vec<(String, Fill)>),
+    /// `(column, center, scale)`: values become `(x - center) / scale`
+    Scaler(Vec<(String, f64, f64)>),
+    /// Categories of each column, sorted
+    OneHot(Vec<(String, Vec<String>)>),
+    /// Categories of each column in order: each value is coded by its position
+    Ordinal(Vec<(String, Vec<String>)>),
+    Pca {
+        columns: Vec<String>,
+        means: Vec<f64>,
+        /// Unit vectors, by decreasing explained variance
+        components: Vec<Vec<f64>>,
+    },
+    /// Several encoders fitted by one `auto` stage, applied in order
+    Many(Vec<Fitted>),
+}
+
+impl<'a> Stage<'a> {
+    /// Parse a stage written like its `Display`: `imputer:mean`, `imputer:constant:0`,
+    /// `scaler:robust`, `encoder:ordinal` or `pca:3`. Strategies are checked by `fit`.
+    pub fn parse(stage: &'a str) -> Result<Self, PipelineError> {
+        let invalid = || PipelineError::InvalidStage(stage.to_string());
+        match stage.split_once(':').ok_or_else(invalid)? {
+            ("imputer", strategy) => Ok(Stage::Imputer(strategy)),
+            ("scaler", strategy) => Ok(Stage::Scaler(strategy)),
+            ("encoder", strategy) => Ok(Stage::Encoder(strategy)),
+            ("pca", n) => n.parse().map(Stage::PCA).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Stage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Imputer(strategy) => write!(f, "imputer:{strategy}"),
+            Stage::Scaler(strategy) => write!(f, "scaler:{strategy}"),
+            Stage::Encoder(strategy) => write!(f, "encoder:{strategy}"),
+            Stage::PCA(n) => write!(f, "pca:{n}"),
+        }
+    }
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new(stages: Vec<Stage<'a>>) -> Self {
+        Pipeline {
+            stages,
+            levels: BTreeMap::new(),
+            orders: BTreeMap::new(),
+            fitted: None,
+        }
+    }
+
+    /// Treat columns according to their measurement `levels`, such as those of a
+    /// [`SemanticSchema`](super::SemanticSchema); unnamed columns get the level of their type
+    pub fn with_levels(mut self, levels: BTreeMap<String, Level>) -> Self {
+        self.levels = levels;
+        self
+    }
+
+    /// Code ordinal columns by the position of their values in `orders`, such as those of
+    /// a [`SemanticSchema`](super::SemanticSchema), rather than alphabetically
+    pub fn with_orders(mut self, orders: BTreeMap<String, Vec<String>>) -> Self {
+        self.orders = orders;
+        self
+    }
+
+    pub fn stages(&self) -> &[Stage<'a>] {
+        &self.stages
+    }
+
+    pub fn is_fitted(&self) -> bool {
+        self.fitted.is_some()
     }
 
-    pub fn fit(&self, df: &DataFrame) -> Result<(), Box<dyn std::error::Error>> {
-        todo!()
+    /// Learn the state of every stage from `df`, replacing what was learned before
+    pub fn fit(&mut self, df: &DataFrame) -> Result<(), PipelineError> {
+        let mut df = df.clone();
+        let mut fitted = Vec::with_capacity(self.stages.len());
+        for stage in &self.stages {
+            let stage = Fitted::fit(stage, &df, &self.levels, &self.orders)?;
+            df = stage.apply(&df)?;
+            fitted.push(stage);
+        }
+        self.fitted = Some(fitted);
+        Ok(())
     }
 
-    pub fn transform(&self, df: &DataFrame) -> Result<DataFrame, Box<dyn std::error::Error>> {
-        todo!()
+    /// Apply the fitted stages to `df`
+    pub fn transform(&self, df: &DataFrame) -> Result<DataFrame, PipelineError> {
+        let fitted = self.fitted.as_ref().ok_or(PipelineError::NotFitted)?;
+        let mut df = df.clone();
+        for stage in fitted {
+            df = stage.apply(&df)?;
+        }
+        Ok(df)
     }
 
-    pub fn fit_transform(&self, df: &DataFrame) -> Result<DataFrame, Box<dyn std::error::Error>> {
+    pub fn fit_transform(&mut self, df: &DataFrame) -> Result<DataFrame, PipelineError> {
         self.fit(df)?;
         self.transform(df)
     }
 }
 
+impl Fitted {
+    fn fit(
+        stage: &Stage,
+        df: &DataFrame,
+        levels: &BTreeMap<String, Level>,
+        orders: &BTreeMap<String, Vec<String>>,
+    ) -> Result<Self, PipelineError> {
+        match *stage {
+            Stage::Imputer(strategy) => fit_imputer(strategy, df, levels),
+            Stage::Scaler(strategy) => fit_scaler(strategy, df, levels),
+            Stage::Encoder(strategy) => fit_encoder(strategy, df, levels, orders),
+            Stage::PCA(n) => fit_pca(n, df, levels),
+        }
+    }
+
+    fn apply(&self, df: &DataFrame) -> Result<DataFrame, PipelineError> {
+        let mut df = df.clone();
+        match self {
+            Fitted::Imputer(fills) => {
+                for (name, fill) in fills {
+                    let column = column(&df, name)?;
+                    if column.null_count() == 0 {
+                        continue;
+                    }
+                    let filled = match fill {
+                        Fill::Number(value) => {
+                            let values = numbers(column)?
+                                .into_iter()
+                                .map(|v| Some(v.unwrap_or(*value)));
+                            let filled = Series::new(name, values.collect::<Vec<_>>());
+                            // Integers, such as codes, stay integers when filled with one
+                            if column.dtype().is_integer() && value.fract() == 0.0 {
+                                filled.cast(column.dtype())?
+                            } else {
+                                filled
+                            }
+                        }
+                        Fill::Text(value) => {
+                            let values = texts(column)?
+                                .into_iter()
+                                .map(|v| Some(v.unwrap_or_else(|| value.clone())));
+                            Series::new(name, values.collect::<Vec<_>>())
+                        }
+                    };
+                    df.with_column(filled)?;
+                }
+            }
+            Fitted::Scaler(scales) => {
+                for (name, center, scale) in scales {
+                    let values = numbers(column(&df, name)?)?
+                        .into_iter()
+                        .map(|v| v.map(|x| (x - center) / scale));
+                    df.with_column(Series::new(name, values.collect::<Vec<_>>()))?;
+                }
+            }
+            Fitted::OneHot(categories) => {
+                let mut columns = Vec::new();
+                for series in df.get_columns() {
+                    let Some((name, seen)) = categories.iter().find(|(n, _)| n == series.name())
+                    else {
+                        columns.push(series.clone());
+                        continue;
+                    };
+                    // A value not seen by fit sets none of the indicators
+                    let values = texts(series)?;
+                    for category in seen {
+                        let indicator: Vec<f64> = values
+                            .iter()
+                            .map(|v| f64::from(v.as_deref() == Some(category.as_str())))
+                            .collect();
+                        columns.push(Series::new(&format!("{name}_{category}"), indicator));
+                    }
+                }
+                check_columns(&df, categories.iter().map(|(name, _)| name))?;
+                df = DataFrame::new(columns)?;
+            }
+            Fitted::Ordinal(categories) => {
+                for (name, order) in categories {
+                    let positions: HashMap<&str, usize> = order
+                        .iter()
+                        .enumerate()
+                        .map(|(index, category)| (category.as_str(), index))
+                        .collect();
+                    let mut codes = Vec::with_capacity(df.height());
+                    for value in texts(column(&df, name)?)? {
+                        let code = match value {
+                            Some(value) => match positions.get(value.as_str()) {
+                                Some(&index) => Some(index as f64),
+                                None => {
+                                    return Err(PipelineError::UnknownCategory(
+                                        name.clone(),
+                                        value,
+                                    ));
+                                }
+                            },
+                            None => None,
+                        };
+                        codes.push(code);
+                    }
+                    df.with_column(Series::new(name, codes))?;
+                }
+            }
+            Fitted::Pca {
+                columns,
+                means,
+                components,
+            } => {
+                let rows = matrix(&df, columns)?;
+                df = df.drop_many(columns);
+                for (index, component) in components.iter().enumerate() {
+                    let scores: Vec<f64> = rows
+                        .iter()
+                        .map(|row| {
+                            row.iter()
+                                .zip(means)
+                                .zip(component)
+                                .map(|((x, mean), weight)| (x - mean) * weight)
+                                .sum()
+                        })
+                        .collect();
+                    df.with_column(Series::new(&format!("pc{}", index + 1), scores))?;
+                }
+            }
+            Fitted::Many(stages) => {
+                for stage in stages {
+                    df = stage.apply(&df)?;
+                }
+            }
+        }
+        Ok(df)
+    }
+}
+
+fn fit_imputer(
+    strategy: &str,
+    df: &DataFrame,
+    levels: &BTreeMap<String, Level>,
+) -> Result<Fitted, PipelineError> {
+    let (strategy, constant) = match strategy.split_once(':') {
+        Some(("constant", value)) => ("constant", Some(value)),
+        _ => (strategy, None),
+    };
+    if !["auto", "mean", "median", "mode", "constant"].contains(&strategy) {
+        return Err(PipelineError::UnknownStrategy("imputer", strategy.into()));
+    }
+    // A constant that is not a number fills text columns only
+    let number = constant.and_then(|value| value.parse::<f64>().ok());
+    if let Some(value) = constant
+        && number.is_none()
+        && !df.get_columns().iter().any(is_text)
+    {
+        return Err(PipelineError::InvalidConstant(value.into()));
+    }
+
+    let mut fills = Vec::new();
+    for column in df.get_columns() {
+        let name = column.name().to_string();
+        let level = level_of(column, levels);
+        let strategy = match (strategy, level) {
+            ("auto", _) if is_text(column) => "mode",
+            ("auto", Some(level)) => level.imputer(),
+            ("auto", None) => continue,
+            _ => strategy,
+        };
+        let fill = if column.dtype().is_numeric() {
+            let mut values: Vec<f64> = numbers(column)?.into_iter().flatten().collect();
+            values.sort_by(f64::total_cmp);
+            let fill = match (strategy, constant) {
+                ("constant", _) => number,
+                // Means and medians of codes are undefined, leave their gaps
+                ("mean" | "median", _) if level == Some(Level::Nominal) => None,
+                ("mean", _) => mean(&values),
+                ("median", _) => quantile(&values, 0.5),
+                _ => mode(&values, |a, b| a == b).copied(),
+            };
+            fill.map(Fill::Number)
+        } else if is_text(column) {
+            let mut values: Vec<String> = texts(column)?.into_iter().flatten().collect();
+            values.sort();
+            match (strategy, constant) {
+                ("constant", Some(value)) => Some(Fill::Text(value.to_string())),
+                ("mode", _) => mode(&values, |a, b| a == b).cloned().map(Fill::Text),
+                // Means and medians of text are undefined, leave its gaps
+                _ => None,
+            }
+        } else {
+            None
+        };
+        // A column without any value has nothing to learn from
+        if let Some(fill) = fill {
+            fills.push((name, fill));
+        }
+    }
+    Ok(Fitted::Imputer(fills))
+}
+
+fn fit_scaler(
+    strategy: &str,
+    df: &DataFrame,
+    levels: &BTreeMap<String, Level>,
+) -> Result<Fitted, PipelineError> {
+    let mut scales = Vec::new();
+    for column in df.get_columns().iter().filter(|c| is_quantity(c, levels)) {
+        let mut values: Vec<f64> = numbers(column)?.into_iter().flatten().collect();
+        values.sort_by(f64::total_cmp);
+        let (center, spread) = match strategy {
+            "minmax" => (
+                values.first().copied(),
+                values
+                    .first()
+                    .zip(values.last())
+                    .map(|(min, max)| max - min),
+            ),
+            "standard" => (mean(&values), std(&values)),
+            "robust" => (
+                quantile(&values, 0.5),
+                quantile(&values, 0.25)
+                    .zip(quantile(&values, 0.75))
+                    .map(|(q1, q3)| q3 - q1),
+            ),
+            _ => return Err(PipelineError::UnknownStrategy("scaler", strategy.into())),
+        };
+        let (Some(center), Some(spread)) = (center, spread) else {
+            continue;
+        };
+        // A constant column is only centered
+        let scale = if spread > 0.0 { spread } else { 1.0 };
+        scales.push((column.name().to_string(), center, scale));
+    }
+    Ok(Fitted::Scaler(scales))
+}
+
+/// Categories of the columns to encode, each sorted
+fn categories<'a>(
+    columns: impl Iterator<Item = &'a Series>,
+) -> Result<Vec<(String, Vec<String>)>, PipelineError> {
+    let mut categories = Vec::new();
+    for column in columns {
+        let mut seen: Vec<String> = texts(column)?.into_iter().flatten().collect();
+        seen.sort();
+        seen.dedup();
+        categories.push((column.name().to_string(), seen));
+    }
+    Ok(categories)
+}
+
+/// Categories of the columns to code, in their given order or else sorted
+fn ordered<'a>(
+    columns: impl Iterator<Item = &'a Series>,
+    orders: &BTreeMap<String, Vec<String>>,
+) -> Result<Vec<(String, Vec<String>)>, PipelineError> {
+    let mut categories = categories(columns)?;
+    for (name, seen) in &mut categories {
+        if let Some(order) = orders.get(name.as_str()) {
+            *seen = order.clone();
+        }
+    }
+    Ok(categories)
+}
+
+fn fit_encoder(
+    strategy: &str,
+    df: &DataFrame,
+    levels: &BTreeMap<String, Level>,
+    orders: &BTreeMap<String, Vec<String>>,
+) -> Result<Fitted, PipelineError> {
+    let columns = df.get_columns().iter();
+    match strategy {
+        "auto" => {
+            // Numeric ordinal columns are already codes in order, and text without an
+            // order is only categories
+            let encoder = |c: &Series| {
+                let level = level_of(c, levels)?;
+                match level.encoder()? {
+                    _ if level == Level::Ordinal && !is_text(c) => None,
+                    "ordinal" if !orders.contains_key(c.name()) => Some("onehot"),
+                    encoder => Some(encoder),
+                }
+            };
+            let onehot = categories(columns.clone().filter(|c| encoder(c) == Some("onehot")))?;
+            let ordinal = ordered(columns.filter(|c| encoder(c) == Some("ordinal")), orders)?;
+            Ok(Fitted::Many(vec![
+                Fitted::OneHot(onehot),
+                Fitted::Ordinal(ordinal),
+            ]))
+        }
+        "onehot" | "ordinal" => {
+            let encoded = columns.filter(|c| {
+                is_text(c)
+                    || (c.dtype().is_numeric() && level_of(c, levels) == Some(Level::Nominal))
+            });
+            if strategy == "onehot" {
+                Ok(Fitted::OneHot(categories(encoded)?))
+            } else {
+                Ok(Fitted::Ordinal(ordered(encoded, orders)?))
+            }
+        }
+        _ => Err(PipelineError::UnknownStrategy("encoder", strategy.into())),
+    }
+}
+
+fn fit_pca(
+    n: i32,
+    df: &DataFrame,
+    levels: &BTreeMap<String, Level>,
+) -> Result<Fitted, PipelineError> {
+    let columns: Vec<String> = df
+        .get_columns()
+        .iter()
+        .filter(|c| is_quantity(c, levels))
+        .map(|c| c.name().to_string())
+        .collect();
+    let count = usize::try_from(n).unwrap_or(0);
+    if count == 0 || count > columns.len() {
+        return Err(PipelineError::Components(n, columns.len()));
+    }
+
+    let rows = matrix(df, &columns)?;
+    let width = columns.len();
+    let means: Vec<f64> = (0..width)
+        .map(|j| rows.iter().map(|row| row[j]).sum::<f64>() / rows.len().max(1) as f64)
+        .collect();
+    let mut covariance = vec![vec![0.0; width]; width];
+    for row in &rows {
+        for i in 0..width {
+            for j in 0..width {
+                covariance[i][j] += (row[i] - means[i]) * (row[j] - means[j]);
+            }
+        }
+    }
+    let degrees = rows.len().saturating_sub(1).max(1) as f64;
+    covariance
+        .iter_mut()
+        .flatten()
+        .for_each(|value| *value /= degrees);
+
+    let (values, vectors) = symmetric_eigen(covariance);
+    let mut order: Vec<usize> = (0..width).collect();
+    order.sort_by(|&a, &b| values[b].total_cmp(&values[a]));
+    let components = order
+        .into_iter()
+        .take(count)
+        .map(|k| {
+            let mut component: Vec<f64> = vectors.iter().map(|row| row[k]).collect();
+            // Eigenvectors are only defined up to sign; make the largest weight positive
+            let largest = component
+                .iter()
+                .copied()
+                .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+                .unwrap_or_default();
+            if largest < 0.0 {
+                component.iter_mut().for_each(|weight| *weight = -*weight);
+            }
+            component
+        })
+        .collect();
+    Ok(Fitted::Pca {
+        columns,
+        means,
+        components,
+    })
+}
+
+/// Eigenvalues and eigenvectors (as columns) of a symmetric matrix, by Jacobi rotations
+fn symmetric_eigen(mut a: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut v: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| f64::from(i == j)).collect())
+        .collect();
+    for _ in 0..100 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off < 1e-24 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() < f64::MIN_POSITIVE {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut().chain(v.iter_mut()) {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+                let (head, tail) = a.split_at_mut(q);
+                for (pk, qk) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    (*pk, *qk) = (c * *pk - s * *qk, s * *pk + c * *qk);
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+fn column<'a>(df: &'a DataFrame, name: &str) -> Result<&'a Series, PipelineError> {
+    df.column(name)
+        .map_err(|_| PipelineError::MissingColumn(name.to_string()))
+}
+
+fn check_columns<'a>(
+    df: &DataFrame,
+    names: impl IntoIterator<Item = &'a String>,
+) -> Result<(), PipelineError> {
+    names
+        .into_iter()
+        .try_for_each(|name| column(df, name).map(|_| ()))
+}
+
+/// Rows of the numeric `columns`, which must not have missing values
+fn matrix(df: &DataFrame, columns: &[String]) -> Result<Vec<Vec<f64>>, PipelineError> {
+    let mut rows = vec![Vec::with_capacity(columns.len()); df.height()];
+    for name in columns {
+        for (row, value) in rows.iter_mut().zip(numbers(column(df, name)?)?) {
+            row.push(value.ok_or_else(|| PipelineError::Nulls(name.clone()))?);
+        }
+    }
+    Ok(rows)
+}
+
+fn is_text(series: &Series) -> bool {
+    series.dtype() == &DataType::String || series.dtype().is_categorical()
+}
+
+/// The level of a column given to the pipeline, or else the level of its type
+fn level_of(series: &Series, levels: &BTreeMap<String, Level>) -> Option<Level> {
+    levels
+        .get(series.name())
+        .copied()
+        .or_else(|| Level::of_dtype(series.dtype()))
+}
+
+/// A numeric column of measurements rather than category codes
+fn is_quantity(series: &Series, levels: &BTreeMap<String, Level>) -> bool {
+    series.dtype().is_numeric() && !level_of(series, levels).is_some_and(Level::is_categorical)
+}
+
+fn numbers(series: &Series) -> PolarsResult<Vec<Option<f64>>> {
+    Ok(series
+        .cast(&DataType::Float64)?
+        .f64()?
+        .into_iter()
+        .collect())
+}
+
+fn texts(series: &Series) -> PolarsResult<Vec<Option<String>>> {
+    let strings = series.cast(&DataType::String)?;
+    Ok(strings
+        .str()?
+        .into_iter()
+        .map(|v| v.map(str::to_string))
+        .collect())
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Population standard deviation
+fn std(values: &[f64]) -> Option<f64> {
+    let mean = mean(values)?;
+    let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    Some(variance.sqrt())
+}
+
+/// Quantile `q` of sorted `values`, interpolating between the nearest two
+fn quantile(values: &[f64], q: f64) -> Option<f64> {
+    let last = values.len().checked_sub(1)?;
+    let position = q * last as f64;
+    let (low, high) = (position.floor() as usize, position.ceil() as usize);
+    Some(values[low] + (values[high] - values[low]) * (position - low as f64))
+}
+
+/// Most frequent of sorted `values`, the smallest on a tie
+fn mode<T>(values: &[T], same: impl Fn(&T, &T) -> bool) -> Option<&T> {
+    let mut runs: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut start = 0;
+    for end in 1..=values.len() {
+        if end == values.len() || !same(&values[start], &values[end]) {
+            // Keep the first start per length, so the smallest value wins ties
+            runs.entry(end - start).or_insert(start);
+            start = end;
+        }
+    }
+    runs.last_key_value().map(|(_, &start)| &values[start])
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -44,7 +700,7 @@ pub mod tests {
         .expect("Could not construct dataframe");
 
         // Create transformers
-        let pipeline = Pipeline::new(vec![
+        let mut pipeline = Pipeline::new(vec![
             Stage::Imputer("mean"),
             Stage::Scaler("minmax"),
             Stage::PCA(2),
@@ -55,5 +711,227 @@ pub mod tests {
             .expect("Could not transform dataframe");
 
         println!("{}", transformed_df);
+        assert_eq!(transformed_df.get_column_names(), ["pc1", "pc2"]);
+    }
+
+    fn values(df: &DataFrame, name: &str) -> Vec<Option<f64>> {
+        numbers(df.column(name).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_fitted_state_applies_to_unseen_data() {
+        let train = df![
+            "age" => [Some(20.0), None, Some(40.0), Some(30.0)],
+            "city" => [Some("Oslo"), Some("Rome"), None, Some("Oslo")],
+        ]
+        .unwrap();
+        let test = df![
+            "age" => [None, Some(50.0)],
+            "city" => [Some("Lima"), None],
+        ]
+        .unwrap();
+
+        let mut pipeline = Pipeline::new(vec![
+            Stage::Imputer("mean"),
+            Stage::Imputer("mode"),
+            Stage::Scaler("minmax"),
+            Stage::Encoder("onehot"),
+        ]);
+        assert!(matches!(
+            pipeline.transform(&test),
+            Err(PipelineError::NotFitted)
+        ));
+        let fitted = pipeline.fit_transform(&train).unwrap();
+        assert_eq!(fitted.get_column_names(), ["age", "city_Oslo", "city_Rome"]);
+        assert_eq!(
+            values(&fitted, "age"),
+            [Some(0.0), Some(0.5), Some(1.0), Some(0.5)]
+        );
+        assert_eq!(
+            values(&fitted, "city_Oslo"),
+            [Some(1.0), Some(0.0), Some(1.0), Some(1.0)]
+        );
+
+        // Imputed with the training mean and mode, scaled by the training range, and an
+        // unseen city sets no indicator
+        let transformed = pipeline.transform(&test).unwrap();
+        assert_eq!(values(&transformed, "age"), [Some(0.5), Some(1.5)]);
+        assert_eq!(values(&transformed, "city_Oslo"), [Some(0.0), Some(1.0)]);
+        assert_eq!(values(&transformed, "city_Rome"), [Some(0.0), Some(0.0)]);
+
+        let mut ordinal = Pipeline::new(vec![
+            Stage::Imputer("constant:Bergen"),
+            Stage::Encoder("ordinal"),
+            Stage::Scaler("robust"),
+        ]);
+        let encoded = ordinal.fit_transform(&train).unwrap();
+        // Bergen=0, Oslo=1, Rome=2 centered on the median 1 over the IQR 0.25 * 2
+        assert_eq!(
+            values(&encoded, "city"),
+            [Some(0.0), Some(2.0), Some(-2.0), Some(0.0)]
+        );
+        // A text constant leaves the gaps of numbers
+        assert_eq!(
+            values(&encoded, "age"),
+            [Some(-1.0), None, Some(1.0), Some(0.0)]
+        );
+        assert!(matches!(
+            Pipeline::new(vec![Stage::Imputer("constant:Bergen")])
+                .fit_transform(&train.drop("city").unwrap()),
+            Err(PipelineError::InvalidConstant(value)) if value == "Bergen"
+        ));
+        assert!(matches!(
+            ordinal.transform(&test),
+            Err(PipelineError::UnknownCategory(column, value)) if column == "city" && value == "Lima"
+        ));
+        assert!(matches!(
+            ordinal.transform(&test.drop("city").unwrap()),
+            Err(PipelineError::MissingColumn(column)) if column == "city"
+        ));
+
+        let mut standard = Pipeline::new(vec![Stage::Scaler("standard")]);
+        let scaled = standard
+            .fit_transform(&df!["x" => [1.0, 3.0]].unwrap())
+            .unwrap();
+        assert_eq!(values(&scaled, "x"), [Some(-1.0), Some(1.0)]);
+
+        for (stage, message) in [
+            (Stage::Imputer("max"), "Unknown imputer strategy `max`"),
+            (Stage::Scaler("log"), "Unknown scaler strategy `log`"),
+            (Stage::Encoder("hash"), "Unknown encoder strategy `hash`"),
+            (
+                Stage::PCA(3),
+                "PCA needs at least 1 component and at most one per numeric column (1), not 3",
+            ),
+            (
+                Stage::PCA(1),
+                "Column `age` has missing values; impute them before PCA",
+            ),
+        ] {
+            let error = Pipeline::new(vec![stage]).fit(&train).unwrap_err();
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn test_stages_parse_from_their_display() {
+        for stage in [
+            "imputer:constant:0",
+            "scaler:robust",
+            "encoder:onehot",
+            "pca:3",
+        ] {
+            assert_eq!(Stage::parse(stage).unwrap().to_string(), stage);
+        }
+        for stage in ["imputer", "pca:two", "sampler:10"] {
+            assert!(matches!(
+                Stage::parse(stage),
+                Err(PipelineError::InvalidStage(s)) if s == stage
+            ));
+        }
+    }
+
+    #[test]
+    fn test_pca_finds_the_direction_of_most_variance() {
+        // x2 = 2 * x1, so one component explains everything
+        let df = df![
+            "x1" => [1.0, 2.0, 3.0, 4.0],
+            "x2" => [2.0, 4.0, 6.0, 8.0],
+        ]
+        .unwrap();
+        let mut pipeline = Pipeline::new(vec![Stage::PCA(2)]);
+        let projected = pipeline.fit_transform(&df).unwrap();
+        assert_eq!(projected.get_column_names(), ["pc1", "pc2"]);
+
+        let norm = 5f64.sqrt();
+        for (score, expected) in values(&projected, "pc1").into_iter().zip([
+            -1.5 * norm,
+            -0.5 * norm,
+            0.5 * norm,
+            1.5 * norm,
+        ]) {
+            assert!((score.unwrap() - expected).abs() < 1e-9);
+        }
+        for score in values(&projected, "pc2") {
+            assert!(score.unwrap().abs() < 1e-9);
+        }
+
+        // Unseen rows are projected onto the same axes
+        let unseen = pipeline
+            .transform(&df!["x1" => [2.5], "x2" => [5.0]].unwrap())
+            .unwrap();
+        assert!(values(&unseen, "pc1")[0].unwrap().abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_levels_decide_how_columns_are_treated() {
+        let df = df![
+            "income" => [Some(54000.0), None, Some(58000.0), Some(72000.0)],
+            "region" => [Some(3i64), Some(2), Some(3), None],
+            "rating" => [1i64, 3, 2, 3],
+            "size" => [Some("S"), Some("L"), None, Some("S")],
+        ]
+        .unwrap();
+        let levels = BTreeMap::from([
+            ("region".to_string(), Level::Nominal),
+            ("rating".to_string(), Level::Ordinal),
+            ("size".to_string(), Level::Ordinal),
+        ]);
+
+        let stages = || {
+            vec![
+                Stage::Imputer("auto"),
+                Stage::Encoder("auto"),
+                Stage::Scaler("minmax"),
+            ]
+        };
+        let orders = BTreeMap::from([(
+            "size".to_string(),
+            vec!["S".to_string(), "M".to_string(), "L".to_string()],
+        )]);
+        let mut pipeline = Pipeline::new(stages())
+            .with_levels(levels.clone())
+            .with_orders(orders);
+        let out = pipeline.fit_transform(&df).unwrap();
+        assert_eq!(
+            out.get_column_names(),
+            ["income", "region_2", "region_3", "rating", "size"]
+        );
+        // Ratio income imputed with its median, then scaled
+        let median = Some(2.0 / 9.0);
+        assert_eq!(
+            values(&out, "income"),
+            [Some(0.0), median, median, Some(1.0)]
+        );
+        // Nominal region imputed with its mode and one-hot encoded, not scaled
+        assert_eq!(
+            values(&out, "region_3"),
+            [Some(1.0), Some(0.0), Some(1.0), Some(1.0)]
+        );
+        // Ordinal rating kept as it is; ordinal text coded in its given order, S < M < L
+        assert_eq!(
+            values(&out, "rating"),
+            [Some(1.0), Some(3.0), Some(2.0), Some(3.0)]
+        );
+        assert_eq!(
+            values(&out, "size"),
+            [Some(0.0), Some(2.0), Some(0.0), Some(0.0)]
+        );
+
+        // Without an order, ordinal text is one-hot encoded rather than coded A to Z
+        let mut unordered = Pipeline::new(stages()).with_levels(levels.clone());
+        let out = unordered.fit_transform(&df).unwrap();
+        assert_eq!(
+            out.get_column_names(),
+            [
+                "income", "region_2", "region_3", "rating", "size_L", "size_S"
+            ]
+        );
+
+        // Explicit strategies still respect levels: codes have no mean
+        let mut mean = Pipeline::new(vec![Stage::Imputer("mean")]).with_levels(levels);
+        let out = mean.fit_transform(&df).unwrap();
+        assert_eq!(out.column("region").unwrap().null_count(), 1);
+        assert_eq!(out.column("income").unwrap().null_count(), 0);
     }
 }
@@ -0,0 +1,596 @@
+//! Measurement levels of columns
+//!
+//! Whether a column is nominal, ordinal, interval or ratio decides what can be done with
+//! it: a numeric region code is nominal, so it is one-hot encoded rather than scaled, and
+//! its mean is meaningless. Levels are inferred from the type, name and values of each
+//! column; where the inference is wrong, `dock schema --set` records an override in a
+//! sidecar file next to the dataset. The order of ordinal text cannot be inferred, so
+//! `dock schema --order` records it there too:
+//!
+//! ```toml
+//! # data.csv.schema.toml
+//! [columns]
+//! region = "nominal"
+//! size = "ordinal"
+//!
+//! [order]
+//! size = ["S", "M", "L"]
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use polars::prelude::{DataFrame, DataType as PolarsType, PolarsError, PolarsResult};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{ColumnStats, DataSource};
+
+/// Integer columns with at most this many different values, each repeated several times,
+/// are taken for ordinal scales such as ratings
+const ORDINAL_LEVELS: usize = 10;
+
+/// Words in column names that mark numbers as codes rather than quantities
+const NOMINAL_HINTS: [&str; 13] = [
+    "id", "code", "zip", "postcode", "postal", "region", "category", "class", "type", "group",
+    "cluster", "label", "kind",
+];
+
+/// Words in column names that mark quantities without a true zero
+const INTERVAL_HINTS: [&str; 5] = ["year", "temp", "temperature", "celsius", "fahrenheit"];
+
+/// Measurement level of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataType {
+    /// Ordered categories, such as ratings; differences between them are not defined
+    Ordinal,
+    /// Unordered categories, such as names or codes
+    Nominal,
+    /// Quantities without a true zero, such as temperatures in Celsius or dates
+    Interval,
+    /// Quantities with a true zero, such as incomes or durations
+    Ratio,
+}
+
+/// A statistic shown for a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Statistic {
+    Count,
+    Nulls,
+    Distinct,
+    Min,
+    Median,
+    Mean,
+    Std,
+    Max,
+    /// Standard deviation over the mean, only meaningful with a true zero
+    Variation,
+}
+
+/// How a column is charted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chart {
+    /// The count of each value
+    Bar,
+    /// The count of values in ranges of equal width
+    Histogram,
+}
+
+/// A column with its inferred or overridden level
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticColumn {
+    pub name: String,
+    pub dtype: PolarsType,
+    pub level: DataType,
+    /// Whether `level` comes from the sidecar file rather than inference
+    pub overridden: bool,
+    /// Categories from lowest to highest, for ordinal text given an order
+    pub order: Option<Vec<String>>,
+    /// Statistics over every row, which the level was inferred from
+    pub stats: ColumnStats,
+}
+
+/// The levels of every column of a dataset
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticSchema {
+    columns: Vec<SemanticColumn>,
+}
+
+/// Levels and category orders set by the user, stored in the sidecar file of a dataset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Overrides {
+    #[serde(default)]
+    columns: BTreeMap<String, DataType>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    order: BTreeMap<String, Vec<String>>,
+}
+
+/// Errors
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    #[error("Unknown measurement level `{0}`; expected nominal, ordinal, interval or ratio")]
+    UnknownLevel(String),
+
+    #[error("No column `{0}`")]
+    UnknownColumn(String),
+
+    #[error("Cannot read or write {0}: {1}")]
+    Io(PathBuf, io::Error),
+
+    #[error("Invalid schema file {0}: {1}")]
+    Parse(PathBuf, Box<toml::de::Error>),
+
+    #[error(transparent)]
+    Polars(#[from] PolarsError),
+}
+
+impl DataType {
+    pub const ALL: [DataType; 4] = [
+        DataType::Nominal,
+        DataType::Ordinal,
+        DataType::Interval,
+        DataType::Ratio,
+    ];
+
+    /// The level of a column from its type alone: text is nominal, dates are interval and
+    /// other numbers ratio. `None` for types without a level, such as lists.
+    pub fn of_dtype(dtype: &PolarsType) -> Option<Self> {
+        match dtype {
+            PolarsType::String | PolarsType::Boolean => Some(DataType::Nominal),
+            PolarsType::Duration(_) => Some(DataType::Ratio),
+            dtype if dtype.is_temporal() => Some(DataType::Interval),
+            dtype if dtype.is_categorical() => Some(DataType::Nominal),
+            dtype if dtype.is_numeric() => Some(DataType::Ratio),
+            _ => None,
+        }
+    }
+
+    /// The level of a column from its type, name and values. Numbers are nominal when
+    /// their name marks them as codes, interval when it marks a scale without a true zero
+    /// or when some are negative, ordinal when they are few integers each seen often, and
+    /// ratio otherwise.
+    pub fn infer(name: &str, dtype: &PolarsType, stats: &ColumnStats) -> Self {
+        let level = Self::of_dtype(dtype).unwrap_or(DataType::Nominal);
+        if level != DataType::Ratio || !dtype.is_numeric() {
+            return level;
+        }
+        let words = words(name);
+        let hinted = |hints: &[&str]| words.iter().any(|word| hints.contains(&word.as_str()));
+        let integer = dtype.is_integer();
+        if integer && hinted(&NOMINAL_HINTS) {
+            DataType::Nominal
+        } else if hinted(&INTERVAL_HINTS) {
+            DataType::Interval
+        } else if integer
+            && stats.distinct <= ORDINAL_LEVELS
+            && stats.count >= ORDINAL_LEVELS * stats.distinct.max(1)
+        {
+            DataType::Ordinal
+        } else if stats.min.is_some_and(|min| min < 0.0) {
+            DataType::Interval
+        } else {
+            DataType::Ratio
+        }
+    }
+
+    /// Whether values are categories rather than quantities
+    pub fn is_categorical(self) -> bool {
+        matches!(self, DataType::Nominal | DataType::Ordinal)
+    }
+
+    /// The `imputer` strategy for missing values: the most common category, the median of
+    /// ordered and skewed values, or the mean
+    pub fn imputer(self) -> &'static str {
+        match self {
+            DataType::Nominal => "mode",
+            DataType::Ordinal | DataType::Ratio => "median",
+            DataType::Interval => "mean",
+        }
+    }
+
+    /// The `encoder` strategy, `None` for quantities
+    pub fn encoder(self) -> Option<&'static str> {
+        match self {
+            DataType::Nominal => Some("onehot"),
+            DataType::Ordinal => Some("ordinal"),
+            DataType::Interval | DataType::Ratio => None,
+        }
+    }
+
+    /// The statistics that are meaningful at this level
+    pub fn statistics(self) -> &'static [Statistic] {
+        use Statistic::*;
+        match self {
+            DataType::Nominal => &[Count, Nulls, Distinct],
+            DataType::Ordinal => &[Count, Nulls, Distinct, Min, Median, Max],
+            DataType::Interval => &[Count, Nulls, Min, Median, Mean, Std, Max],
+            DataType::Ratio => &[Count, Nulls, Min, Median, Mean, Std, Max, Variation],
+        }
+    }
+
+    pub fn chart(self) -> Chart {
+        if self.is_categorical() {
+            Chart::Bar
+        } else {
+            Chart::Histogram
+        }
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DataType::Ordinal => "ordinal",
+            DataType::Nominal => "nominal",
+            DataType::Interval => "interval",
+            DataType::Ratio => "ratio",
+        })
+    }
+}
+
+impl FromStr for DataType {
+    type Err = SchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DataType::ALL
+            .into_iter()
+            .find(|level| level.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| SchemaError::UnknownLevel(s.to_string()))
+    }
+}
+
+impl Statistic {
+    pub fn label(self) -> &'static str {
+        match self {
+            Statistic::Count => "Count",
+            Statistic::Nulls => "Nulls",
+            Statistic::Distinct => "Distinct",
+            Statistic::Min => "Min",
+            Statistic::Median => "Median",
+            Statistic::Mean => "Mean",
+            Statistic::Std => "Std Dev",
+            Statistic::Max => "Max",
+            Statistic::Variation => "Coeff. of Variation",
+        }
+    }
+
+    /// The value of this statistic in `stats`, `None` where it is undefined
+    pub fn of(self, stats: &ColumnStats) -> Option<f64> {
+        match self {
+            Statistic::Count => Some(stats.count as f64),
+            Statistic::Nulls => Some(stats.nulls as f64),
+            Statistic::Distinct => Some(stats.distinct as f64),
+            Statistic::Min => stats.min,
+            Statistic::Median => stats.median,
+            Statistic::Mean => stats.mean,
+            Statistic::Std => stats.std,
+            Statistic::Max => stats.max,
+            Statistic::Variation => stats
+                .std
+                .zip(stats.mean)
+                .filter(|(_, mean)| *mean != 0.0)
+                .map(|(std, mean)| std / mean),
+        }
+    }
+}
+
+impl fmt::Display for Chart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Chart::Bar => "bar",
+            Chart::Histogram => "histogram",
+        })
+    }
+}
+
+impl SemanticSchema {
+    /// Infer the level of every column of `source` in one pass over its rows
+    pub fn infer(source: &DataSource) -> PolarsResult<Self> {
+        let columns = source
+            .schema()
+            .iter()
+            .zip(source.column_stats()?)
+            .map(|((name, dtype), stats)| SemanticColumn::infer(name, dtype, stats))
+            .collect();
+        Ok(Self { columns })
+    }
+
+    /// Infer the level of every column of `df`, already read
+    pub fn of(df: &DataFrame) -> PolarsResult<Self> {
+        let columns = df
+            .get_columns()
+            .iter()
+            .map(|series| {
+                let stats = ColumnStats::of(series)?;
+                Ok(SemanticColumn::infer(series.name(), series.dtype(), stats))
+            })
+            .collect::<PolarsResult<_>>()?;
+        Ok(Self { columns })
+    }
+
+    /// Infer the levels of `source`, then apply the overrides in its sidecar file
+    pub fn load(source: &DataSource) -> Result<Self, SchemaError> {
+        let mut schema = Self::infer(source)?;
+        schema.apply(&Overrides::load(source.path())?);
+        Ok(schema)
+    }
+
+    /// Set the levels and orders of the columns named in `overrides`; others are left as
+    /// they are
+    pub fn apply(&mut self, overrides: &Overrides) {
+        for column in &mut self.columns {
+            if let Some(level) = overrides.get(&column.name) {
+                column.level = level;
+                column.overridden = true;
+            }
+            if let Some(order) = overrides.order(&column.name) {
+                column.order = Some(order.to_vec());
+            }
+        }
+    }
+
+    pub fn columns(&self) -> &[SemanticColumn] {
+        &self.columns
+    }
+
+    pub fn level(&self, name: &str) -> Option<DataType> {
+        self.columns
+            .iter()
+            .find(|column| column.name == name)
+            .map(|column| column.level)
+    }
+
+    /// Level of every column, by name
+    pub fn levels(&self) -> BTreeMap<String, DataType> {
+        self.columns
+            .iter()
+            .map(|column| (column.name.clone(), column.level))
+            .collect()
+    }
+
+    /// Order of the categories of every column given one, by name
+    pub fn orders(&self) -> BTreeMap<String, Vec<String>> {
+        self.columns
+            .iter()
+            .filter_map(|column| Some((column.name.clone(), column.order.clone()?)))
+            .collect()
+    }
+}
+
+impl SemanticColumn {
+    fn infer(name: &str, dtype: &PolarsType, stats: ColumnStats) -> Self {
+        Self {
+            name: name.to_string(),
+            dtype: dtype.clone(),
+            level: DataType::infer(name, dtype, &stats),
+            overridden: false,
+            order: None,
+            stats,
+        }
+    }
+
+    /// The strategy `encoder:auto` applies: ordinal text without an order is one-hot
+    /// encoded, as coding it in alphabetical order would invent an order
+    pub fn encoder(&self) -> Option<&'static str> {
+        let text = self.dtype == PolarsType::String || self.dtype.is_categorical();
+        match self.level.encoder() {
+            Some("ordinal") if text && self.order.is_none() => Some("onehot"),
+            encoder => encoder,
+        }
+    }
+}
+
+impl Overrides {
+    /// The sidecar file of `dataset`: `data.csv` has `data.csv.schema.toml`
+    pub fn path(dataset: &Path) -> PathBuf {
+        let mut name = dataset.file_name().unwrap_or_default().to_os_string();
+        name.push(".schema.toml");
+        dataset.with_file_name(name)
+    }
+
+    /// The overrides of `dataset`, none when it has no sidecar file
+    pub fn load(dataset: &Path) -> Result<Self, SchemaError> {
+        let path = Self::path(dataset);
+        match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| SchemaError::Parse(path, Box::new(e))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(SchemaError::Io(path, e)),
+        }
+    }
+
+    /// Write the sidecar file of `dataset`, removing it when nothing is overridden
+    pub fn save(&self, dataset: &Path) -> Result<(), SchemaError> {
+        let path = Self::path(dataset);
+        if self.is_empty() {
+            return match fs::remove_file(&path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(SchemaError::Io(path, e)),
+                _ => Ok(()),
+            };
+        }
+        let text = toml::to_string(self)
+            .map_err(|e| SchemaError::Io(path.clone(), io::Error::other(e)))?;
+        let name = dataset.file_name().unwrap_or_default().to_string_lossy();
+        let text = format!(
+            "# Measurement levels and orders of columns of {name}, set with `dock schema`\n{text}"
+        );
+        fs::write(&path, text).map_err(|e| SchemaError::Io(path, e))
+    }
+
+    pub fn get(&self, column: &str) -> Option<DataType> {
+        self.columns.get(column).copied()
+    }
+
+    pub fn set(&mut self, column: &str, level: DataType) {
+        self.columns.insert(column.to_string(), level);
+    }
+
+    /// Categories of `column` from lowest to highest
+    pub fn order(&self, column: &str) -> Option<&[String]> {
+        self.order.get(column).map(Vec::as_slice)
+    }
+
+    /// Order the categories of `column`, which makes it ordinal
+    pub fn set_order(&mut self, column: &str, order: Vec<String>) {
+        self.set(column, DataType::Ordinal);
+        self.order.insert(column.to_string(), order);
+    }
+
+    /// Forget the level and order of `column`
+    pub fn remove(&mut self, column: &str) -> Option<DataType> {
+        self.order.remove(column);
+        self.columns.remove(column)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty() && self.order.is_empty()
+    }
+}
+
+/// The lowercase words of a column name, split at punctuation and camel case humps
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        let boundary = !c.is_alphanumeric() || (c.is_uppercase() && previous_lower);
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        }
+        previous_lower = c.is_lowercase();
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_levels_and_override_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let dataset = dir.path().join("people.csv");
+        let rows = 60;
+        let mut csv = String::from("age,gender,income,region,rating,temp,customerId,balance\n");
+        for i in 0..rows {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                20 + i % 40,
+                ["M", "F"][i % 2],
+                30000 + i * 1000,
+                i % 5,
+                1 + i % 5,
+                15.5 + i as f64,
+                1000 + i,
+                i as i64 - 10,
+            ));
+        }
+        fs::write(&dataset, csv).unwrap();
+        let source = DataSource::open(&dataset).unwrap();
+
+        let schema = SemanticSchema::load(&source).unwrap();
+        let levels: Vec<DataType> = schema.columns().iter().map(|c| c.level).collect();
+        use DataType::*;
+        assert_eq!(
+            levels,
+            [
+                Ratio, Nominal, Ratio, Nominal, Ordinal, Interval, Nominal, Interval
+            ]
+        );
+        assert_eq!(schema.columns()[4].stats.distinct, 5);
+        // Read whole, the same columns get the same levels and statistics
+        let read = SemanticSchema::of(&source.collect().unwrap()).unwrap();
+        assert_eq!(read.levels(), schema.levels());
+        assert_eq!(read.columns()[4].stats, schema.columns()[4].stats);
+
+        let mut overrides = Overrides::load(&dataset).unwrap();
+        assert!(overrides.is_empty());
+        overrides.set("rating", Interval);
+        overrides.set("age", "ORDINAL".parse().unwrap());
+        overrides.save(&dataset).unwrap();
+        let sidecar = dir.path().join("people.csv.schema.toml");
+        assert!(
+            fs::read_to_string(&sidecar)
+                .unwrap()
+                .contains("rating = \"interval\"")
+        );
+
+        let schema = SemanticSchema::load(&source).unwrap();
+        assert_eq!(schema.level("rating"), Some(Interval));
+        assert_eq!(schema.level("age"), Some(Ordinal));
+        assert!(schema.columns()[0].overridden && !schema.columns()[1].overridden);
+        assert_eq!(schema.levels()["region"], Nominal);
+
+        // Ordinal text is one-hot encoded until its order is known
+        overrides.set("gender", Ordinal);
+        overrides.save(&dataset).unwrap();
+        let schema = SemanticSchema::load(&source).unwrap();
+        assert_eq!(schema.columns()[1].encoder(), Some("onehot"));
+        assert!(schema.orders().is_empty());
+        overrides.set_order("gender", vec!["M".into(), "F".into()]);
+        overrides.save(&dataset).unwrap();
+        assert!(
+            fs::read_to_string(&sidecar)
+                .unwrap()
+                .contains("gender = [\"M\", \"F\"]")
+        );
+        let schema = SemanticSchema::load(&source).unwrap();
+        assert_eq!(schema.columns()[1].encoder(), Some("ordinal"));
+        assert_eq!(schema.orders()["gender"], ["M", "F"]);
+
+        overrides.remove("gender");
+        overrides.remove("rating");
+        overrides.remove("age");
+        overrides.save(&dataset).unwrap();
+        assert!(!sidecar.exists());
+        assert!(matches!(
+            "count".parse::<DataType>(),
+            Err(SchemaError::UnknownLevel(_))
+        ));
+
+        fs::write(&sidecar, "[columns]\nage = \"huge\"\n").unwrap();
+        assert!(matches!(
+            Overrides::load(&dataset),
+            Err(SchemaError::Parse(..))
+        ));
+    }
+
+    #[test]
+    fn test_defaults_follow_the_level() {
+        assert_eq!(DataType::Nominal.imputer(), "mode");
+        assert_eq!(DataType::Nominal.encoder(), Some("onehot"));
+        assert_eq!(DataType::Ratio.encoder(), None);
+        assert_eq!(DataType::Ordinal.chart(), Chart::Bar);
+        assert_eq!(DataType::Interval.chart(), Chart::Histogram);
+        assert!(!DataType::Nominal.statistics().contains(&Statistic::Mean));
+        assert!(
+            !DataType::Interval
+                .statistics()
+                .contains(&Statistic::Variation)
+        );
+
+        let stats = ColumnStats {
+            count: 4,
+            nulls: 0,
+            distinct: 4,
+            mean: Some(10.0),
+            median: Some(9.0),
+            std: Some(2.0),
+            min: Some(7.0),
+            max: Some(14.0),
+        };
+        assert_eq!(Statistic::Variation.of(&stats), Some(0.2));
+        assert_eq!(words("customerId"), ["customer", "id"]);
+        assert_eq!(words("ZIP_code"), ["zip", "code"]);
+    }
+}
@@ -0,0 +1,269 @@
+//! Lazy, paginated access to datasets of any size
+//!
+//! A [`DataSource`] scans its file instead of reading it: opening one only reads the
+//! schema, [`DataSource::window`] reads just the rows asked for, and statistics are
+//! computed over the whole file without holding it in memory. CSV, TSV, NDJSON and
+//! Parquet files are scanned; JSON arrays and XLSX workbooks cannot be, and are read into
+//! memory when opened.
+
+use std::path::{Path, PathBuf};
+
+use polars::prelude::*;
+
+use super::format::separator;
+use super::{DataType as Level, Distribution, Format, read_data_frame};
+
+/// A dataset read on demand
+#[derive(Clone)]
+pub struct DataSource {
+    path: PathBuf,
+    format: Format,
+    frame: LazyFrame,
+    schema: SchemaRef,
+}
+
+/// Statistics of one column over every row
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    /// Values present
+    pub count: usize,
+    pub nulls: usize,
+    /// Different values present
+    pub distinct: usize,
+    /// The rest are `None` for columns that are not numeric or have no values
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    /// Sample standard deviation
+    pub std: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl ColumnStats {
+    /// Statistics of the values of `series`
+    pub fn of(series: &Series) -> PolarsResult<Self> {
+        let nulls = series.null_count();
+        let mut stats = Self {
+            count: series.len() - nulls,
+            nulls,
+            distinct: series.drop_nulls().n_unique()?,
+            mean: None,
+            median: None,
+            std: None,
+            min: None,
+            max: None,
+        };
+        if series.dtype().is_numeric() {
+            stats.mean = series.mean();
+            stats.median = series.median();
+            stats.std = series.std(1);
+            stats.min = series.min()?;
+            stats.max = series.max()?;
+        }
+        Ok(stats)
+    }
+}
+
+impl DataSource {
+    /// Open the dataset at `path`, telling its format by its contents and extension
+    pub fn open(path: &Path) -> PolarsResult<Self> {
+        match Format::detect(path)? {
+            Some(format) => Self::open_as(path, format),
+            None => polars_bail!(ComputeError: "{} is not a table", path.display()),
+        }
+    }
+
+    pub fn open_as(path: &Path, format: Format) -> PolarsResult<Self> {
+        let mut frame = match format {
+            Format::Csv | Format::Tsv => LazyCsvReader::new(path)
+                .with_has_header(true)
+                .with_separator(separator(format))
+                .finish()?,
+            Format::NdJson => LazyJsonLineReader::new(path).finish()?,
+            Format::Parquet => LazyFrame::scan_parquet(path, ScanArgsParquet::default())?,
+            Format::Json | Format::Xlsx => read_data_frame(path, format)?.lazy(),
+        };
+        let schema = frame.schema()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            frame,
+            schema,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn column_names(&self) -> Vec<String> {
+        self.schema
+            .iter_names()
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /// A query over every row, to build on
+    pub fn lazy(&self) -> LazyFrame {
+        self.frame.clone()
+    }
+
+    /// Number of rows, counted without loading them
+    pub fn count_rows(&self) -> PolarsResult<usize> {
+        let counted = self.lazy().select([len()]).collect()?;
+        let count = counted.get_columns()[0].get(0)?.extract::<usize>();
+        count.ok_or_else(|| polars_err!(ComputeError: "cannot count the rows"))
+    }
+
+    /// Up to `rows` rows starting at row `offset`; fewer at the end of the data. The rows
+    /// before `offset` are scanned to find it, so the first window of a multi-GB file takes
+    /// milliseconds but one millions of rows in takes seconds.
+    pub fn window(&self, offset: usize, rows: usize) -> PolarsResult<DataFrame> {
+        let rows = IdxSize::try_from(rows).unwrap_or(IdxSize::MAX);
+        self.lazy().slice(offset as i64, rows).collect()
+    }
+
+    /// Every value of `column`
+    pub fn column(&self, column: &str) -> PolarsResult<Series> {
+        let values = self.lazy().select([col(column)]).collect()?;
+        Ok(values.get_columns()[0].clone())
+    }
+
+    /// Every row
+    pub fn collect(&self) -> PolarsResult<DataFrame> {
+        self.lazy().collect()
+    }
+
+    /// Statistics of `column` over every row
+    pub fn stats(&self, column: &str) -> PolarsResult<ColumnStats> {
+        Ok(self.collect_stats(&[column])?.remove(0))
+    }
+
+    /// Distribution of `column` at `level` over every row, aggregated without collecting
+    /// the column
+    pub fn distribution(&self, column: &str, level: Level) -> PolarsResult<Distribution> {
+        Distribution::scan(self.lazy(), column, self.schema.try_get(column)?, level)
+    }
+
+    /// Statistics of every column, in one pass over the rows
+    pub fn column_stats(&self) -> PolarsResult<Vec<ColumnStats>> {
+        let names = self.column_names();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        self.collect_stats(&names)
+    }
+
+    fn collect_stats(&self, columns: &[&str]) -> PolarsResult<Vec<ColumnStats>> {
+        let mut exprs = Vec::new();
+        for (index, &column) in columns.iter().enumerate() {
+            let alias = |stat: &str| format!("{index}.{stat}");
+            exprs.extend([
+                col(column).count().alias(&alias("count")),
+                col(column).null_count().alias(&alias("nulls")),
+                col(column)
+                    .drop_nulls()
+                    .n_unique()
+                    .alias(&alias("distinct")),
+            ]);
+            if self.schema.try_get(column)?.is_numeric() {
+                let value = || col(column).cast(DataType::Float64);
+                exprs.extend([
+                    value().mean().alias(&alias("mean")),
+                    value().median().alias(&alias("median")),
+                    value().std(1).alias(&alias("std")),
+                    value().min().alias(&alias("min")),
+                    value().max().alias(&alias("max")),
+                ]);
+            }
+        }
+        let stats = self.lazy().select(exprs).collect()?;
+
+        let mut all = Vec::with_capacity(columns.len());
+        for index in 0..columns.len() {
+            let get = |stat: &str| -> PolarsResult<AnyValue> {
+                match stats.column(&format!("{index}.{stat}")) {
+                    Ok(column) => column.get(0),
+                    Err(_) => Ok(AnyValue::Null),
+                }
+            };
+            all.push(ColumnStats {
+                count: get("count")?.extract().unwrap_or_default(),
+                nulls: get("nulls")?.extract().unwrap_or_default(),
+                distinct: get("distinct")?.extract().unwrap_or_default(),
+                mean: get("mean")?.extract(),
+                median: get("median")?.extract(),
+                std: get("std")?.extract(),
+                min: get("min")?.extract(),
+                max: get("max")?.extract(),
+            });
+        }
+        Ok(all)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::write_data_frame;
+
+    #[test]
+    fn test_windows_and_stats_cover_the_whole_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let n = 2500i64;
+        let mut df = df![
+            "id" => (0..n).collect::<Vec<_>>(),
+            "score" => (0..n).map(|i| (i % 2 == 0).then_some(i as f64)).collect::<Vec<_>>(),
+            "label" => (0..n).map(|i| format!("row{i}")).collect::<Vec<_>>(),
+        ]
+        .unwrap();
+
+        for name in ["big.csv", "big.parquet", "big.ndjson", "big.xlsx"] {
+            let path = dir.path().join(name);
+            write_data_frame(&mut df, &path, Format::from_path(&path).unwrap()).unwrap();
+            let source = DataSource::open(&path).unwrap();
+            assert_eq!(source.column_names(), ["id", "score", "label"], "{name}");
+            assert_eq!(source.count_rows().unwrap(), 2500, "{name}");
+
+            let window = source.window(1990, 20).unwrap();
+            assert_eq!(window.height(), 20);
+            assert_eq!(
+                window.column("id").unwrap().get(0).unwrap(),
+                AnyValue::Int64(1990)
+            );
+            assert_eq!(source.window(2490, 100).unwrap().height(), 10, "{name}");
+
+            let score = source.stats("score").unwrap();
+            assert_eq!((score.count, score.nulls), (1250, 1250), "{name}");
+            assert_eq!(score.mean, Some(1249.0));
+            assert_eq!((score.min, score.max), (Some(0.0), Some(2498.0)));
+            let label = source.stats("label").unwrap();
+            assert_eq!(
+                (label.count, label.distinct, label.mean),
+                (2500, 2500, None)
+            );
+            let all = source.column_stats().unwrap();
+            assert_eq!((all.len(), all[1].distinct), (3, 1250));
+            assert_eq!(all[1], score, "{name}");
+            let values = source.column("score").unwrap();
+            assert_eq!(ColumnStats::of(&values).unwrap(), score, "{name}");
+            let dist = source.distribution("score", Level::Ratio).unwrap();
+            assert_eq!((dist.count, dist.nulls), (1250, 1250), "{name}");
+        }
+
+        let path = dir.path().join("big.csv");
+        assert_eq!(
+            crate::data::get_data_frame(path.to_str().unwrap())
+                .unwrap()
+                .height(),
+            2500
+        );
+        assert!(DataSource::open(&path).unwrap().stats("missing").is_err());
+    }
+}
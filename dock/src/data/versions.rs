@@ -0,0 +1,467 @@
+//! Content-addressed versions of datasets (FR-DOCK-03)
+//!
+//! A snapshot stores the bytes of a dataset in `.dock/objects/` under the data root, keyed
+//! by a hash of its contents and schema, and appends a [`Version`] to `.dock/index.jsonl`.
+//! A dataset is named by its path relative to the data root, so `raw/churn.csv` and
+//! `clean/churn.csv` have separate histories.
+//! Each version links to its parent: the dataset it was derived from, with the pipeline
+//! stages that produced it, or else the previous version of the same dataset. Snapshotting
+//! unchanged data again records nothing new.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use polars::prelude::DataFrame;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::catalog::{Field, schema_of};
+
+/// Hex digits shown of a version hash
+pub const SHORT_HASH: usize = 12;
+
+/// One snapshot of a dataset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Version {
+    /// Path relative to the data root, e.g. `raw/churn.csv`; absolute for files outside it
+    pub dataset: String,
+    /// SHA-256 of the contents and the schema
+    pub hash: String,
+    /// File the snapshot was taken of, relative to the data root
+    pub path: PathBuf,
+    /// Hash of the version this one was derived from
+    pub parent: Option<String>,
+    /// Pipeline stages that produced it from its parent, e.g. `scaler:minmax`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<String>,
+    pub schema: Vec<Field>,
+    pub rows: usize,
+    pub columns: usize,
+    pub created: DateTime<Utc>,
+}
+
+/// How a dataset was produced from a stored version
+#[derive(Debug, Clone, Copy)]
+pub struct Derivation<'a> {
+    pub parent: &'a Version,
+    pub stages: &'a [String],
+}
+
+/// The object store and version index under a data root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Store {
+    root: PathBuf,
+    dir: PathBuf,
+}
+
+/// What changed between two versions of a dataset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionDiff {
+    pub before: Version,
+    pub after: Version,
+    pub added: Vec<Field>,
+    pub removed: Vec<Field>,
+    /// `(column, type before, type after)`
+    pub retyped: Vec<(String, String, String)>,
+}
+
+/// A version named on the command line: `name@hash`, with any prefix of the hash, or just
+/// `name` for the latest. The name is resolved by [`Store::datasets_named`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRef {
+    pub dataset: String,
+    pub hash: Option<String>,
+}
+
+impl VersionRef {
+    pub fn parse(reference: &str) -> Self {
+        let (dataset, hash) = match reference.rsplit_once('@') {
+            Some((dataset, hash)) => (dataset, Some(hash).filter(|h| !h.is_empty())),
+            None => (reference, None),
+        };
+        Self {
+            dataset: dataset.to_string(),
+            hash: hash.map(str::to_string),
+        }
+    }
+}
+
+impl fmt::Display for VersionRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.hash {
+            Some(hash) => write!(f, "{}@{hash}", self.dataset),
+            None => write!(f, "{}", self.dataset),
+        }
+    }
+}
+
+impl Version {
+    /// `name@hash`, with the hash shortened
+    pub fn id(&self) -> String {
+        format!("{}@{}", self.dataset, self.short_hash())
+    }
+
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..SHORT_HASH.min(self.hash.len())]
+    }
+}
+
+impl Store {
+    /// The store of the data root `root`
+    pub fn open(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            dir: root.join(".dock"),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn index(&self) -> PathBuf {
+        self.dir.join("index.jsonl")
+    }
+
+    /// Where the contents of a version are kept
+    pub fn object(&self, version: &Version) -> PathBuf {
+        self.dir.join("objects").join(&version.hash)
+    }
+
+    /// The file a version was taken of
+    pub fn path(&self, version: &Version) -> PathBuf {
+        self.root.join(&version.path)
+    }
+
+    /// Name the file at `path` is versioned under: its path relative to the data root, or
+    /// its absolute path when it lies outside the root
+    pub fn dataset(&self, path: &Path) -> String {
+        let path = resolved(path);
+        let root = resolved(&self.root);
+        path.strip_prefix(&root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Stored datasets `name` may mean: the file at that path, a dataset of that name, or
+    /// else every dataset with that file name or file stem, so `churn` finds
+    /// `raw/churn.csv`
+    pub fn datasets_named(&self, name: &str) -> io::Result<Vec<String>> {
+        let mut datasets = Vec::new();
+        for version in self.all_versions()? {
+            if !datasets.contains(&version.dataset) {
+                datasets.push(version.dataset);
+            }
+        }
+        let file = self.dataset(Path::new(name));
+        for exact in [file.as_str(), name] {
+            if datasets.iter().any(|dataset| dataset == exact) {
+                return Ok(vec![exact.to_string()]);
+            }
+        }
+        datasets.retain(|dataset| {
+            let path = Path::new(dataset);
+            [path.file_name(), path.file_stem()].contains(&Some(name.as_ref()))
+        });
+        Ok(datasets)
+    }
+
+    /// Store the file at `path`, whose contents were read into `df`, as a version of its
+    /// dataset. The latest version is returned unchanged when the contents match it.
+    pub fn snapshot(
+        &self,
+        path: &Path,
+        df: &DataFrame,
+        derivation: Option<Derivation>,
+    ) -> io::Result<Version> {
+        let schema = schema_of(df);
+        let mut hasher = Sha256::new();
+        io::copy(&mut File::open(path)?, &mut hasher)?;
+        hasher.update(serde_json::to_vec(&schema)?);
+        let hash = format!("{:x}", hasher.finalize());
+
+        let dataset = self.dataset(path);
+        let latest = self.versions(&dataset)?.pop();
+        if let Some(latest) = latest.as_ref().filter(|v| v.hash == hash) {
+            return Ok(latest.clone());
+        }
+
+        let (parent, stages) = match derivation {
+            Some(derivation) => (
+                Some(derivation.parent.hash.clone()),
+                derivation.stages.to_vec(),
+            ),
+            None => (latest.map(|v| v.hash), Vec::new()),
+        };
+        let version = Version {
+            path: PathBuf::from(&dataset),
+            dataset,
+            hash,
+            parent,
+            stages,
+            schema,
+            rows: df.height(),
+            columns: df.width(),
+            created: Utc::now(),
+        };
+
+        let object = self.object(&version);
+        if !object.exists() {
+            fs::create_dir_all(self.dir.join("objects"))?;
+            // Written aside and renamed so a crash never leaves a truncated object
+            let partial = object.with_extension("partial");
+            fs::copy(path, &partial)?;
+            fs::rename(&partial, &object)?;
+        }
+        let mut index = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.index())?;
+        writeln!(index, "{}", serde_json::to_string(&version)?)?;
+        Ok(version)
+    }
+
+    /// Versions of `dataset`, oldest first
+    pub fn versions(&self, dataset: &str) -> io::Result<Vec<Version>> {
+        let mut versions = self.all_versions()?;
+        versions.retain(|version| version.dataset == dataset);
+        Ok(versions)
+    }
+
+    /// Versions of every dataset, oldest first
+    fn all_versions(&self) -> io::Result<Vec<Version>> {
+        let file = match File::open(self.index()) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut versions = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            versions.push(serde_json::from_str(&line)?);
+        }
+        Ok(versions)
+    }
+
+    /// Versions of `dataset` whose hash starts with `prefix`, or its latest without one
+    pub fn find(&self, dataset: &str, prefix: Option<&str>) -> io::Result<Vec<Version>> {
+        let mut versions = self.versions(dataset)?;
+        match prefix {
+            Some(prefix) => {
+                // Contents snapshotted again after a change are one version, the latest entry
+                let mut seen = HashSet::new();
+                versions.reverse();
+                versions.retain(|v| v.hash.starts_with(prefix) && seen.insert(v.hash.clone()));
+                versions.reverse();
+                Ok(versions)
+            }
+            None => Ok(versions.pop().into_iter().collect()),
+        }
+    }
+
+    /// Copy the stored contents of `version` to `out`
+    pub fn restore(&self, version: &Version, out: &Path) -> io::Result<()> {
+        if let Some(dir) = out.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        fs::copy(self.object(version), out).map(|_| ())
+    }
+}
+
+/// `path` made absolute, with symlinks resolved when it exists
+fn resolved(path: &Path) -> PathBuf {
+    fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl VersionDiff {
+    pub fn between(before: &Version, after: &Version) -> Self {
+        let find = |schema: &[Field], name: &str| schema.iter().find(|f| f.name == name).cloned();
+        let added = after
+            .schema
+            .iter()
+            .filter(|f| find(&before.schema, &f.name).is_none())
+            .cloned()
+            .collect();
+        let mut removed = Vec::new();
+        let mut retyped = Vec::new();
+        for field in &before.schema {
+            match find(&after.schema, &field.name) {
+                None => removed.push(field.clone()),
+                Some(now) if now.dtype != field.dtype => {
+                    retyped.push((field.name.clone(), field.dtype.clone(), now.dtype));
+                }
+                Some(_) => {}
+            }
+        }
+        Self {
+            before: before.clone(),
+            after: after.clone(),
+            added,
+            removed,
+            retyped,
+        }
+    }
+
+    /// Whether the schema and the row count are the same
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.retyped.is_empty()
+            && self.before.rows == self.after.rows
+    }
+}
+
+impl fmt::Display for VersionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (before, after) = (&self.before, &self.after);
+        writeln!(f, "{} → {}", before.id(), after.id())?;
+        let rows = after.rows as i64 - before.rows as i64;
+        writeln!(
+            f,
+            "{:<10} {} → {} ({rows:+})",
+            "rows", before.rows, after.rows
+        )?;
+        writeln!(
+            f,
+            "{:<10} {} → {}",
+            "columns", before.columns, after.columns
+        )?;
+        for field in &self.added {
+            writeln!(f, "+ {} ({})", field.name, field.dtype)?;
+        }
+        for field in &self.removed {
+            writeln!(f, "- {} ({})", field.name, field.dtype)?;
+        }
+        for (name, was, now) in &self.retyped {
+            writeln!(f, "~ {name} ({was} → {now})")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::df;
+
+    #[test]
+    fn test_snapshots_are_content_addressed() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path());
+        let path = dir.path().join("churn.csv");
+        assert!(store.versions("churn.csv").unwrap().is_empty());
+
+        fs::write(&path, "a,b\n1,x\n").unwrap();
+        let df = df!["a" => [1i64], "b" => ["x"]].unwrap();
+        let first = store.snapshot(&path, &df, None).unwrap();
+        assert_eq!(first.parent, None);
+        assert_eq!(store.snapshot(&path, &df, None).unwrap(), first);
+
+        fs::write(&path, "a,b\n1,x\n2,y\n").unwrap();
+        let df = df!["a" => [1i64, 2], "b" => ["x", "y"]].unwrap();
+        let second = store.snapshot(&path, &df, None).unwrap();
+        assert_eq!(second.parent.as_deref(), Some(first.hash.as_str()));
+        assert_eq!(
+            store.versions("churn.csv").unwrap(),
+            [first.clone(), second.clone()]
+        );
+
+        // A derived dataset links to its source and the stages that made it
+        let scaled = dir.path().join("scaled.csv");
+        fs::write(&scaled, "a\n0.0\n1.0\n").unwrap();
+        let stages = vec!["scaler:minmax".to_string()];
+        let derived = store
+            .snapshot(
+                &scaled,
+                &df!["a" => [0.0, 1.0]].unwrap(),
+                Some(Derivation {
+                    parent: &second,
+                    stages: &stages,
+                }),
+            )
+            .unwrap();
+        assert_eq!(derived.parent.as_deref(), Some(second.hash.as_str()));
+        assert_eq!(derived.stages, stages);
+
+        assert_eq!(
+            store.find("churn.csv", None).unwrap(),
+            std::slice::from_ref(&second)
+        );
+        assert_eq!(
+            store.find("churn.csv", Some(first.short_hash())).unwrap(),
+            std::slice::from_ref(&first)
+        );
+        assert!(store.find("churn.csv", Some("zz")).unwrap().is_empty());
+
+        let restored = dir.path().join("old/churn.csv");
+        store.restore(&first, &restored).unwrap();
+        assert_eq!(fs::read_to_string(restored).unwrap(), "a,b\n1,x\n");
+
+        let diff = VersionDiff::between(&second, &derived);
+        assert_eq!(diff.removed, [second.schema[1].clone()]);
+        assert_eq!(
+            diff.retyped,
+            [("a".to_string(), "i64".to_string(), "f64".to_string())]
+        );
+        assert!(diff.to_string().contains("rows       2 → 2 (+0)"));
+        assert!(diff.to_string().contains("~ a (i64 → f64)"));
+        assert!(!diff.is_empty());
+        assert!(VersionDiff::between(&first, &first).is_empty());
+
+        assert_eq!(
+            VersionRef::parse("data/churn.csv@ab12"),
+            VersionRef {
+                dataset: "data/churn.csv".into(),
+                hash: Some("ab12".into())
+            }
+        );
+        assert_eq!(VersionRef::parse("churn").to_string(), "churn");
+        assert_eq!(VersionRef::parse("raw.clean@").to_string(), "raw.clean");
+    }
+
+    #[test]
+    fn test_datasets_are_named_by_their_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open(dir.path());
+        let df = df!["a" => [1i64]].unwrap();
+        let mut versions = Vec::new();
+        for (name, contents) in [
+            ("raw/churn.csv", "a\n1\n"),
+            ("clean/churn.csv", "a\n2\n"),
+            ("churn.parquet", "PAR1"),
+            ("raw/sales.csv", "a\n3\n"),
+        ] {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, contents).unwrap();
+            versions.push(store.snapshot(&path, &df, None).unwrap());
+        }
+
+        assert_eq!(versions[0].dataset, "raw/churn.csv");
+        assert_eq!(versions[1].parent, None);
+        assert_eq!(store.versions("raw/churn.csv").unwrap(), versions[..1]);
+        assert_eq!(store.path(&versions[1]), dir.path().join("clean/churn.csv"));
+
+        let named = |name: &str| store.datasets_named(name).unwrap();
+        assert_eq!(
+            named("churn"),
+            ["raw/churn.csv", "clean/churn.csv", "churn.parquet"]
+        );
+        assert_eq!(named("churn.csv"), ["raw/churn.csv", "clean/churn.csv"]);
+        assert_eq!(named("sales"), ["raw/sales.csv"]);
+        assert_eq!(named("clean/churn.csv"), ["clean/churn.csv"]);
+        let file = dir.path().join("raw/churn.csv");
+        assert_eq!(named(file.to_str().unwrap()), ["raw/churn.csv"]);
+        assert!(named("missing").is_empty());
+    }
+}
@@ -0,0 +1,6 @@
+//! Data Organization and Cleaning Kit: catalog, inspect, clean, transform, convert and
+//! version tabular datasets from the command line (FR-DOCK-01..04)
+
+pub mod cli;
+pub mod commands;
+pub mod data;
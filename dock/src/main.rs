@@ -1,3 +1,18 @@
-fn main() {
-    println!("Hello, world!");
+use std::process::ExitCode;
+
+use dock::cli::{self, DockError};
+
+fn main() -> ExitCode {
+    match cli::run(std::env::args_os()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(DockError::Usage(e)) => {
+            let _ = e.print();
+            // Help and version requests are not errors
+            ExitCode::from(if e.use_stderr() { 2 } else { 0 })
+        }
+        Err(e) => {
+            toollog::error(&e.to_string(), &[]);
+            ExitCode::from(e.exit_code())
+        }
+    }
 }
@@ -0,0 +1,360 @@
+//! Key bindings loaded from TOML, shared by riptide's dashboard and sail's viewer
+//!
+//! An application declares its actions with [`actions!`], lists them through [`Bindable`]
+//! and ships its default bindings in the same format users write:
+//!
+//! ```toml
+//! [global]
+//! quit = ["q", "esc"]
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use thiserror::Error;
+
+/// Declares a per-view action enum together with its TOML names and help descriptions
+#[macro_export]
+macro_rules! actions {
+    ($(#[$meta:meta])* $name:ident, $scope:literal, { $($variant:ident => $key:literal, $help:literal;)+ }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($variant,)+
+        }
+
+        impl $name {
+            pub const SCOPE: &'static str = $scope;
+            pub const ALL: &'static [$name] = &[$($name::$variant,)+];
+
+            /// Name of the action in the keymap file
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $key,)+
+                }
+            }
+
+            /// One-line description for the help panel
+            pub fn description(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $help,)+
+                }
+            }
+        }
+    };
+}
+
+/// An action keys can be bound to
+pub trait Bindable: Copy + Eq + 'static {
+    /// Where an action can be limited to, such as a view of the dashboard
+    type View: Copy + Eq;
+
+    /// Bindings used when the user has no keymap file, in the same format users write
+    const DEFAULTS: &'static str;
+
+    /// Every action, in help order
+    fn all() -> Vec<Self>;
+
+    /// Section of the keymap file the action is listed in
+    fn scope(&self) -> &'static str;
+
+    /// Name of the action in the keymap file
+    fn name(&self) -> &'static str;
+
+    /// One-line description for the help panel
+    fn description(&self) -> &'static str;
+
+    /// The view the action is limited to, `None` when it is available everywhere
+    fn view(&self) -> Option<Self::View>;
+}
+
+/// A key plus modifiers, written like `q`, `esc`, `shift+tab` or `ctrl+w`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Normalize a terminal key event so it compares equal to its parsed binding
+    pub fn from_event(key: KeyEvent) -> Self {
+        let mut modifiers = key.modifiers;
+        // The case of a character or BackTab already carries the shift
+        if matches!(key.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self {
+            code: key.code,
+            modifiers,
+        }
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = KeymapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || KeymapError::InvalidKey(s.to_string());
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = s.split('+').collect();
+
+        // A lone "+" or a binding ending in "++" means the plus key itself
+        let key = if s.ends_with("++") || s == "+" {
+            parts.truncate(parts.len().saturating_sub(2));
+            "+".to_string()
+        } else {
+            parts.pop().ok_or_else(invalid)?.to_string()
+        };
+
+        for modifier in parts {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let mut code = match key.to_ascii_lowercase().as_str() {
+            "esc" => KeyCode::Esc,
+            "enter" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(invalid()),
+                }
+            }
+        };
+
+        // Terminals report shifted keys as their shifted form
+        if modifiers.contains(KeyModifiers::SHIFT) {
+            match code {
+                KeyCode::Tab => code = KeyCode::BackTab,
+                KeyCode::Char(c) => code = KeyCode::Char(c.to_ascii_uppercase()),
+                _ => {}
+            }
+        }
+
+        Ok(KeyBinding::from_event(KeyEvent::new(code, modifiers)))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::BackTab => write!(f, "shift+tab"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            code => write!(f, "{}", code.to_string().to_lowercase()),
+        }
+    }
+}
+
+/// Errors
+#[derive(Debug, Error)]
+pub enum KeymapError {
+    #[error("Cannot read keymap: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid keymap file: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("Unknown keymap action `{scope}.{action}`")]
+    UnknownAction { scope: String, action: String },
+
+    #[error("Invalid key `{0}`")]
+    InvalidKey(String),
+
+    #[error("Key `{key}` is bound to both `{first}` and `{second}`")]
+    Conflict {
+        key: String,
+        first: String,
+        second: String,
+    },
+}
+
+/// Key bindings of every action, loaded from TOML on top of the defaults of `A`
+#[derive(Debug, Clone)]
+pub struct Keymap<A> {
+    bindings: Vec<(A, Vec<KeyBinding>)>,
+}
+
+impl<A: Bindable> Keymap<A> {
+    /// Parse a keymap on top of the defaults; actions it lists replace their default keys
+    pub fn from_toml(source: &str) -> Result<Self, KeymapError> {
+        let mut keymap = Self::parse(A::DEFAULTS)?;
+        for (action, keys) in Self::parse(source)?.bindings {
+            if let Some(entry) = keymap.bindings.iter_mut().find(|(a, _)| *a == action) {
+                entry.1 = keys;
+            }
+        }
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    /// Load the user's keymap file, falling back to the defaults when it does not exist
+    pub fn load(path: &Path) -> Result<Self, KeymapError> {
+        match fs::read_to_string(path) {
+            Ok(source) => Self::from_toml(&source),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn parse(source: &str) -> Result<Self, KeymapError> {
+        let sections: BTreeMap<String, BTreeMap<String, Vec<String>>> = toml::from_str(source)?;
+        let all = A::all();
+        let mut bindings = Vec::new();
+
+        for (scope, section) in &sections {
+            if !all.iter().any(|a| a.scope() == scope) {
+                let action = section.keys().next().cloned().unwrap_or_default();
+                return Err(KeymapError::UnknownAction {
+                    scope: scope.clone(),
+                    action,
+                });
+            }
+            for (name, keys) in section {
+                let action = all
+                    .iter()
+                    .find(|a| a.scope() == scope && a.name() == name)
+                    .ok_or_else(|| KeymapError::UnknownAction {
+                        scope: scope.clone(),
+                        action: name.clone(),
+                    })?;
+                let keys = keys
+                    .iter()
+                    .map(|key| key.parse())
+                    .collect::<Result<Vec<KeyBinding>, _>>()?;
+                bindings.push((*action, keys));
+            }
+        }
+
+        // Keep help output in declaration order regardless of the file's order
+        bindings.sort_by_key(|(action, _)| all.iter().position(|a| a == action));
+        Ok(Self { bindings })
+    }
+
+    /// Reject keys that would trigger two actions in the same view
+    fn check_conflicts(&self) -> Result<(), KeymapError> {
+        let all = self
+            .bindings
+            .iter()
+            .flat_map(|(action, keys)| keys.iter().map(move |key| (*action, *key)));
+
+        let mut seen: Vec<(A, KeyBinding)> = Vec::new();
+        for (action, key) in all {
+            let clash = seen.iter().find(|(other, other_key)| {
+                *other_key == key
+                    && *other != action
+                    && (other.view().is_none()
+                        || action.view().is_none()
+                        || other.view() == action.view())
+            });
+            if let Some((other, _)) = clash {
+                return Err(KeymapError::Conflict {
+                    key: key.to_string(),
+                    first: format!("{}.{}", other.scope(), other.name()),
+                    second: format!("{}.{}", action.scope(), action.name()),
+                });
+            }
+            seen.push((action, key));
+        }
+        Ok(())
+    }
+
+    /// Every action with its keys, in help order
+    pub fn bindings(&self) -> &[(A, Vec<KeyBinding>)] {
+        &self.bindings
+    }
+
+    /// The action a key press triggers in `view`
+    pub fn action(&self, view: A::View, key: KeyEvent) -> Option<A> {
+        let key = KeyBinding::from_event(key);
+        self.bindings
+            .iter()
+            .find(|(action, keys)| {
+                (action.view().is_none() || action.view() == Some(view)) && keys.contains(&key)
+            })
+            .map(|(action, _)| *action)
+    }
+
+    /// `(keys, description)` for each action limited to `view`, or available everywhere
+    /// when `view` is `None`
+    pub fn help(&self, view: Option<A::View>) -> Vec<(String, &'static str)> {
+        self.bindings
+            .iter()
+            .filter(|(action, keys)| !keys.is_empty() && action.view() == view)
+            .map(|(action, keys)| {
+                let keys: Vec<String> = keys.iter().map(KeyBinding::to_string).collect();
+                (keys.join("/"), action.description())
+            })
+            .collect()
+    }
+}
+
+impl<A: Bindable> Default for Keymap<A> {
+    fn default() -> Self {
+        Self::from_toml("").expect("the default keymap is valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bindings() {
+        let binding: KeyBinding = "ctrl+w".parse().unwrap();
+        assert_eq!(binding.code, KeyCode::Char('w'));
+        assert_eq!(binding.modifiers, KeyModifiers::CONTROL);
+        assert_eq!(binding.to_string(), "ctrl+w");
+
+        let binding: KeyBinding = "shift+tab".parse().unwrap();
+        assert_eq!(binding.code, KeyCode::BackTab);
+        assert_eq!(binding.to_string(), "shift+tab");
+
+        let binding: KeyBinding = "shift+w".parse().unwrap();
+        assert_eq!(
+            binding,
+            KeyBinding::from_event(KeyEvent::new(KeyCode::Char('W'), KeyModifiers::SHIFT))
+        );
+
+        assert_eq!("+".parse::<KeyBinding>().unwrap().code, KeyCode::Char('+'));
+        assert!(matches!(
+            "hyper+x".parse::<KeyBinding>(),
+            Err(KeymapError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            "xyz".parse::<KeyBinding>(),
+            Err(KeymapError::InvalidKey(_))
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+use keymap::Bindable;
+
+pub use keymap::{KeyBinding, KeymapError};
+
+/// Bindings used when the user gives no keymap file, in the same format users write
+pub const DEFAULT_KEYMAP: &str = include_str!("keymap.toml");
+
+/// Key bindings of the viewer, loaded from TOML
+pub type Keymap = keymap::Keymap<ViewerAction>;
+
+keymap::actions!(
+    /// Actions of the dataset viewer
+    ViewerAction, "viewer", {
+        Quit => "quit", "quit";
+        Up => "up", "move up";
+        Down => "down", "move down";
+        Left => "left", "move left";
+        Right => "right", "move right";
+        ToggleSummary => "toggle_summary", "toggle column summary";
+    }
+);
+
+impl Bindable for ViewerAction {
+    /// The viewer has a single view, so every action is available everywhere
+    type View = ();
+
+    const DEFAULTS: &'static str = DEFAULT_KEYMAP;
+
+    fn all() -> Vec<ViewerAction> {
+        ViewerAction::ALL.to_vec()
+    }
+
+    fn scope(&self) -> &'static str {
+        ViewerAction::SCOPE
+    }
+
+    fn name(&self) -> &'static str {
+        ViewerAction::name(self)
+    }
+
+    fn description(&self) -> &'static str {
+        ViewerAction::description(self)
+    }
+
+    fn view(&self) -> Option<()> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_defaults_cover_every_action() {
+        let keymap = Keymap::default();
+        for (action, keys) in keymap.bindings() {
+            assert!(!keys.is_empty(), "{} has no default key", action.name());
+        }
+        assert_eq!(keymap.bindings().len(), ViewerAction::ALL.len());
+        assert_eq!(
+            keymap.action((), key(KeyCode::Char(' '))),
+            Some(ViewerAction::ToggleSummary)
+        );
+        assert_eq!(keymap.action((), key(KeyCode::Char('x'))), None);
+    }
+
+    #[test]
+    fn test_user_overrides_replace_defaults() {
+        let keymap = Keymap::from_toml("[viewer]\ndown = [\"n\"]\n").unwrap();
+        assert_eq!(keymap.action((), key(KeyCode::Char('j'))), None);
+        assert_eq!(
+            keymap.action((), key(KeyCode::Char('n'))),
+            Some(ViewerAction::Down)
+        );
+        assert!(keymap.help(None).contains(&("n".to_string(), "move down")));
+    }
+
+    #[test]
+    fn test_conflicts_are_rejected() {
+        let err = Keymap::from_toml("[viewer]\nquit = [\"j\"]\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Key `j` is bound to both `viewer.quit` and `viewer.down`"
+        );
+    }
+
+    #[test]
+    fn test_unknown_actions_are_rejected() {
+        assert!(matches!(
+            Keymap::from_toml("[viewer]\nfly = [\"f\"]\n"),
+            Err(KeymapError::UnknownAction { .. })
+        ));
+        // Sections of riptide's own keymap mean nothing to sail
+        assert!(matches!(
+            Keymap::from_toml("[logs]\nup = [\"k\"]\n"),
+            Err(KeymapError::UnknownAction { .. })
+        ));
+    }
+}
@@ -1,94 +1,189 @@
+pub mod keymap;
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
 // Polars imports
 use polars::prelude::*;
 
 // Ratatui imports
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    crossterm::event::{self, Event, KeyEvent, KeyEventKind},
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     prelude::*,
     style::{Modifier, Style},
     text::Text,
     widgets::{
-        Block, BorderType, Borders, Cell, Clear, HighlightSpacing, Paragraph, Row, Scrollbar,
-        ScrollbarOrientation, ScrollbarState, Table, TableState, Wrap,
+        BarChart, Block, BorderType, Borders, Cell, Clear, HighlightSpacing, Paragraph, Row,
+        Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState, Wrap,
     },
     DefaultTerminal, Frame,
 };
 
 // Your internal module imports
-use crate::data::get_data_frame;
-use crate::util::colors::TableColors;
-
-const INFO_TEXT: [&str; 2] = [
-    "(Esc) quit | (k) move up | (j) move down | (h) move left | (l) move right",
-    "(Shift + →) next color | (Shift + ←) previous color",
-];
+use self::keymap::{Keymap, ViewerAction};
+use dock::data::distribution::Shape;
+use dock::data::semantic::{Chart, Overrides};
+use dock::data::{ColumnStats, DataSource, DataType as Level, Distribution};
+use theme::TableColors;
 
 const ITEM_HEIGHT: usize = 4;
 
+/// How long to wait for a key before checking on work done in the background
+const TICK: Duration = Duration::from_millis(100);
+
+/// What the summary popup shows of a column, over every row of the dataset
+struct Summary {
+    stats: ColumnStats,
+    level: Level,
+    /// `None` when the column could not be scanned
+    dist: Option<Distribution>,
+}
+
+/// Rows fetched from the dataset at a time; only the page holding the selected row is in
+/// memory
+const PAGE_ROWS: usize = 500;
+
 pub struct App {
     state: TableState,
-    df: DataFrame,
+    source: DataSource,
+    /// The rows from `page_offset` on
+    page: DataFrame,
+    page_offset: usize,
+    /// Index of the selected row in the whole dataset
+    selected_row: usize,
+    /// Number of rows, `None` until counted in the background
+    rows: Option<usize>,
+    row_count: Option<Receiver<usize>>,
+    /// Measurement levels the user set with `dock schema`
+    overrides: Overrides,
+    /// Summaries of columns, computed when each is first shown
+    summaries: HashMap<String, Summary>,
+    /// Columns whose summary is being computed in the background, and where it arrives;
+    /// `None` when it could not be computed
+    computing: HashSet<String>,
+    summary_sender: Sender<(String, Option<Summary>)>,
+    summary_receiver: Receiver<(String, Option<Summary>)>,
     column_widths: Vec<u16>,
     scroll_state: ScrollbarState,
     colors: TableColors,
+    keymap: Keymap,
     showing_summary: bool,
 }
 
 impl App {
     pub fn new(file_path: &str) -> Self {
-        // Create DataFrame instead of vector of Data structs
-        let df = get_data_frame(&file_path).expect("Failed to load CSV file");
-
-        // Calculate constraints based on DataFrame
-        let column_widths = App::constraint_len_calculator(&df);
-
-        let height = df.height();
+        // Scan the dataset rather than reading it, so any size opens at once
+        let source = DataSource::open(Path::new(file_path)).expect("Failed to open the dataset");
+        let page = source
+            .window(0, PAGE_ROWS)
+            .expect("Failed to load the dataset");
+
+        // Calculate constraints based on the first page
+        let column_widths = App::constraint_len_calculator(&page);
+
+        // Counting the rows of a large file takes a while; do it off the UI thread
+        let (sender, receiver) = mpsc::channel();
+        let counted = source.clone();
+        thread::spawn(move || {
+            if let Ok(rows) = counted.count_rows() {
+                let _ = sender.send(rows);
+            }
+        });
 
         // Start at col 0
         let mut state = TableState::default().with_selected(0);
         state.select_next_column();
+        let (summary_sender, summary_receiver) = mpsc::channel();
 
         Self {
             state,
-            df,
+            scroll_state: ScrollbarState::new(page.height().saturating_sub(1) * ITEM_HEIGHT),
+            source,
+            page,
+            page_offset: 0,
+            selected_row: 0,
+            rows: None,
+            row_count: Some(receiver),
+            // A broken sidecar file only loses the overrides
+            overrides: Overrides::load(Path::new(file_path)).unwrap_or_default(),
+            summaries: HashMap::new(),
+            computing: HashSet::new(),
+            summary_sender,
+            summary_receiver,
             column_widths,
-            scroll_state: ScrollbarState::new((height - 1) * ITEM_HEIGHT),
             colors: TableColors::new_from_pywal(),
+            keymap: Keymap::default(),
             showing_summary: false,
         }
     }
 
-    fn next_row(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i >= self.df.height() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
+    /// Use `keymap` instead of the default key bindings
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Pick up the row count once the background count is done
+    fn poll_row_count(&mut self) {
+        if let Some(rows) = self.row_count.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.rows = Some(rows);
+            self.row_count = None;
+            self.scroll_state = self
+                .scroll_state
+                .content_length(rows.saturating_sub(1) * ITEM_HEIGHT);
+        }
+    }
+
+    /// Pick up the summaries computed in the background since the last call
+    fn poll_summaries(&mut self) {
+        while let Ok((column, summary)) = self.summary_receiver.try_recv() {
+            self.computing.remove(&column);
+            if let Some(summary) = summary {
+                self.summaries.insert(column, summary);
             }
-            None => 0,
-        };
-        self.state.select(Some(i));
-        self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
+        }
     }
 
-    pub fn previous_row(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.df.height() - 1
-                } else {
-                    i - 1
+    /// Select row `i` of the dataset, fetching its page when it is not loaded. Rows past
+    /// the end are ignored.
+    fn select_row(&mut self, i: usize) {
+        let loaded = self.page_offset..self.page_offset + self.page.height();
+        if !loaded.contains(&i) {
+            let offset = i / PAGE_ROWS * PAGE_ROWS;
+            match self.source.window(offset, PAGE_ROWS) {
+                Ok(page) if i - offset < page.height() => {
+                    self.page = page;
+                    self.page_offset = offset;
                 }
+                _ => return,
             }
-            None => 0,
-        };
-        self.state.select(Some(i));
+        }
+        self.selected_row = i;
+        self.state.select(Some(i - self.page_offset));
         self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
     }
 
+    fn next_row(&mut self) {
+        let i = self.selected_row + 1;
+        match self.rows {
+            // Wrap around only once it is known where the data ends
+            Some(rows) if i >= rows => self.select_row(0),
+            _ => self.select_row(i),
+        }
+    }
+
+    pub fn previous_row(&mut self) {
+        match (self.selected_row, self.rows) {
+            (0, Some(rows)) => self.select_row(rows.saturating_sub(1)),
+            (0, None) => {}
+            (i, _) => self.select_row(i - 1),
+        }
+    }
+
     pub fn next_column(&mut self) {
         self.state.select_next_column();
     }
@@ -99,30 +194,44 @@ impl App {
 
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<(), Box<dyn std::error::Error>> {
         loop {
+            self.poll_row_count();
+            self.poll_summaries();
             terminal.draw(|frame| self.draw(frame))?;
 
+            // Redraw now and then so results of background work show without a keypress
+            if !event::poll(TICK)? {
+                continue;
+            }
             if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    let shift_pressed = key.modifiers.contains(KeyModifiers::SHIFT);
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
-                        KeyCode::Char('j') | KeyCode::Down => self.next_row(),
-                        KeyCode::Char('k') | KeyCode::Up => self.previous_row(),
-                        KeyCode::Char('l') | KeyCode::Right => self.next_column(),
-                        KeyCode::Char('h') | KeyCode::Left => self.previous_column(),
-                        KeyCode::Char(' ') => {
-                            self.showing_summary = !self.showing_summary;
-                        }
-                        _ => {}
-                    }
+                if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                    return Ok(());
                 }
             }
         }
     }
 
+    /// Act on a key press through the keymap; false once the viewer should quit
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match self.keymap.action((), key) {
+            Some(ViewerAction::Quit) => return false,
+            Some(ViewerAction::Down) => self.next_row(),
+            Some(ViewerAction::Up) => self.previous_row(),
+            Some(ViewerAction::Right) => self.next_column(),
+            Some(ViewerAction::Left) => self.previous_column(),
+            Some(ViewerAction::ToggleSummary) => {
+                self.showing_summary = !self.showing_summary;
+                if self.showing_summary {
+                    self.load_summary();
+                }
+            }
+            None => {}
+        }
+        true
+    }
+
     fn draw(&mut self, frame: &mut Frame) {
-        let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(4)]);
-        let rects = vertical.split(frame.size());
+        let vertical = &Layout::vertical([Constraint::Min(5), Constraint::Length(5)]);
+        let rects = vertical.split(frame.area());
         self.render_table(frame, rects[0]);
         self.render_scrollbar(frame, rects[0]);
         self.render_footer(frame, rects[1]);
@@ -131,6 +240,35 @@ impl App {
             self.render_summary_popup(frame);
         }
     }
+    /// Start computing the summary of the selected column over the whole dataset, once.
+    /// Scanning a large file takes a while, so it is done off the UI thread.
+    fn load_summary(&mut self) {
+        let Some(selected_col) = self.state.selected_column() else {
+            return;
+        };
+        let col_name = self.page.get_column_names()[selected_col].to_string();
+        if self.summaries.contains_key(&col_name) || !self.computing.insert(col_name.clone()) {
+            return;
+        }
+        let source = self.source.clone();
+        let level = self.overrides.get(&col_name);
+        let sender = self.summary_sender.clone();
+        thread::spawn(move || {
+            let summary = Self::summarize(&source, &col_name, level);
+            let _ = sender.send((col_name, summary));
+        });
+    }
+
+    /// The summary of `column`, at `level` unless it is to be inferred
+    fn summarize(source: &DataSource, column: &str, level: Option<Level>) -> Option<Summary> {
+        let stats = source.stats(column).ok()?;
+        let dtype = source.schema().get(column)?;
+        let level = level.unwrap_or_else(|| Level::infer(column, dtype, &stats));
+        // Aggregated by the scan, so the column is never collected
+        let dist = source.distribution(column, level).ok();
+        Some(Summary { stats, level, dist })
+    }
+
     fn render_summary_popup(&self, frame: &mut Frame) {
         // Create a rect for the right half of the screen
         let area = Layout::default()
@@ -143,8 +281,9 @@ impl App {
 
         // Get the selected column
         let selected_col = self.state.selected_column().unwrap();
-        let col_name = self.df.get_column_names()[selected_col];
-        let col = self.df.column(col_name).unwrap();
+        let col_name = self.page.get_column_names()[selected_col];
+        let summary = self.summaries.get(col_name);
+        let computing = self.computing.contains(col_name);
 
         // Split the popup area into sections
         let popup_layout = Layout::default()
@@ -157,67 +296,89 @@ impl App {
             .split(area);
 
         // Title
-        let title = Paragraph::new(format!("Column: {}", col_name))
+        let heading = match summary {
+            Some(summary) => format!("Column: {} ({})", col_name, summary.level),
+            None => format!("Column: {}", col_name),
+        };
+        let title = Paragraph::new(heading)
             .block(Block::default().borders(Borders::ALL).title("Summary"))
             .alignment(Alignment::Center);
         frame.render_widget(title, popup_layout[0]);
 
-        // Stats section
-        // Generate a distribution of the current column
-
-        let stats_text = format!(
-            "Mean: {}\nMedian: {}\nStd Dev: {}\nMin: {:?}\nMax: {:?}",
-            match col.mean() {
-                Some(val) => val.to_string(),
-                None => "N/A".to_string(),
-            },
-            match col.median() {
-                Some(val) => val.to_string(),
-                None => "N/A".to_string(),
-            },
-            match col.std(1) {
-                Some(val) => val.to_string(),
-                None => "N/A".to_string(),
-            },
-            match col.min::<f64>() {
-                Ok(val) => match val {
-                        Some(val) => val.to_string(),
-                        None => "N/A".to_string(),
+        // Stats section, over every row of the dataset; only those meaningful at the
+        // column's level, so codes get no mean
+        let stats_text = match summary {
+            Some(summary) => {
+                let mut lines: Vec<String> = summary
+                    .level
+                    .statistics()
+                    .iter()
+                    .map(|statistic| {
+                        let value = match statistic.of(&summary.stats) {
+                            Some(val) => val.to_string(),
+                            None => "N/A".to_string(),
+                        };
+                        format!("{}: {}", statistic.label(), value)
+                    })
+                    .collect();
+                // The shape of quantities, with the family they follow best
+                if let Some(Shape::Numeric(numeric)) = summary.dist.as_ref().map(|d| &d.shape) {
+                    lines.push(format!("Skew: {:.4}", numeric.skew));
+                    lines.push(format!("Kurtosis: {:.4}", numeric.kurtosis));
+                    if let Some(fit) = &numeric.fit {
+                        lines.push(format!("Best Fit: {}", fit.family));
                     }
-                },
-                Err(_) => "N/A".to_string(),
-            },
-            match col.max::<f64>() {
-                Ok(val) => val.unwrap().to_string(),
-                Err(_) => "N/A".to_string(),
+                }
+                lines.join("\n")
             }
-        );
+            None if computing => "computing…".to_string(),
+            None => "Statistics unavailable".to_string(),
+        };
 
-        let stats = Paragraph::new(stats_text)
+        let stats_block = Paragraph::new(stats_text)
             .block(Block::default().borders(Borders::ALL).title("Statistics"))
             .wrap(Wrap { trim: true });
-        frame.render_widget(stats, popup_layout[1]);
-    }
+        frame.render_widget(stats_block, popup_layout[1]);
 
-    fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-        let vertical_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage((100 - percent_y) / 2),
-                Constraint::Percentage(percent_y),
-                Constraint::Percentage((100 - percent_y) / 2),
-            ])
-            .split(r);
-
-        Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage((100 - percent_x) / 2),
-                Constraint::Percentage(percent_x),
-                Constraint::Percentage((100 - percent_x) / 2),
-            ])
-            .split(vertical_chunks[1])[1]
+        // Graph section, charted by level: bars of categories or a histogram of quantities
+        let chart_title = match summary.map(|summary| summary.level.chart()) {
+            Some(Chart::Bar) => "Bar Chart",
+            Some(Chart::Histogram) => "Histogram",
+            None => "Graph",
+        };
+        let block = Block::default().borders(Borders::ALL).title(chart_title);
+        let bars: Vec<(String, u64)> = match summary.and_then(|summary| summary.dist.as_ref()) {
+            Some(dist) => match &dist.shape {
+                Shape::Numeric(numeric) => numeric
+                    .bins
+                    .iter()
+                    .map(|bin| (format!("{:.1}", bin.start), bin.count as u64))
+                    .collect(),
+                Shape::Categorical(categorical) => categorical
+                    .values
+                    .iter()
+                    .map(|(value, count)| (value.clone(), *count as u64))
+                    .collect(),
+            },
+            None => Vec::new(),
+        };
+        let data: Vec<(&str, u64)> = bars
+            .iter()
+            .map(|(label, count)| (label.as_str(), *count))
+            .collect();
+        // Bars share the width of the popup, with a column between them
+        let inner_width = popup_layout[2].width.saturating_sub(2) as usize;
+        let bar_width = (inner_width / data.len().max(1)).saturating_sub(1).max(1) as u16;
+        let chart = BarChart::default()
+            .block(block)
+            .data(&data)
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::new().fg(self.colors.selected_column_style_fg))
+            .value_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_widget(chart, popup_layout[2]);
     }
+
     fn render_table(&mut self, frame: &mut Frame, area: Rect) {
         let header_style = Style::default()
             .fg(self.colors.header_fg)
@@ -232,9 +393,9 @@ impl App {
             .add_modifier(Modifier::REVERSED)
             .fg(self.colors.selected_cell_style_fg);
 
-        // Get column names from DataFrame
+        // Get column names from the loaded page
         let header = self
-            .df
+            .page
             .get_column_names()
             .iter()
             .map(|name| Cell::from(*name))
@@ -258,8 +419,8 @@ impl App {
             })
             .collect();
 
-        let rows = (0..self.df.height()).map(|i| {
-            let color = if i % 2 == 0 {
+        let rows = (0..self.page.height()).map(|i| {
+            let color = if (self.page_offset + i).is_multiple_of(2) {
                 self.colors.normal_row_color
             } else {
                 self.colors.alt_row_color
@@ -307,14 +468,31 @@ impl App {
         );
     }
 
+    /// Footer with the position of the selected row and the bindings of the active keymap
+    fn footer_text(&self) -> Text<'static> {
+        let position = match self.rows {
+            Some(rows) => format!("row {} of {rows}", self.selected_row + 1),
+            None => format!("row {} (counting rows...)", self.selected_row + 1),
+        };
+        let bindings = self
+            .keymap
+            .help(None)
+            .into_iter()
+            .map(|(keys, description)| format!("({keys}) {description}"))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        Text::from_iter([position, bindings])
+    }
+
     fn render_footer(&self, frame: &mut Frame, area: Rect) {
-        let info_footer = Paragraph::new(Text::from_iter(INFO_TEXT))
+        let info_footer = Paragraph::new(self.footer_text())
             .style(
                 Style::new()
                     .fg(self.colors.row_fg)
                     .bg(self.colors.buffer_bg),
             )
             .centered()
+            .wrap(Wrap { trim: true })
             .block(
                 Block::bordered()
                     .border_type(BorderType::Double)
@@ -322,8 +500,9 @@ impl App {
             );
         frame.render_widget(info_footer, area);
     }
+    /// Row `i` of the loaded page
     fn get_row_as_strings(&self, i: usize) -> Vec<String> {
-        self.df
+        self.page
             .get_columns()
             .iter()
             .map(|col| {
@@ -350,7 +529,7 @@ impl App {
                     .str()
                     .unwrap()
                     .into_iter()
-                    .filter_map(|opt_s| opt_s.map(|s| unicode_width::UnicodeWidthStr::width(s)))
+                    .filter_map(|opt_s| opt_s.map(unicode_width::UnicodeWidthStr::width))
                     .max()
                     .unwrap_or(10);
 
@@ -363,3 +542,112 @@ impl App {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dock::data::{write_data_frame, Format};
+
+    #[test]
+    fn test_opens_every_format_dock_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut df = df![
+            "id" => [1i64, 2, 3],
+            "city" => ["Oslo", "Rome", "Lima"],
+        ]
+        .unwrap();
+        let files = [
+            ("data.csv", Format::Csv),
+            ("data.tsv", Format::Tsv),
+            ("data.json", Format::Json),
+            ("data.ndjson", Format::NdJson),
+            ("data.parquet", Format::Parquet),
+            ("data.xlsx", Format::Xlsx),
+            // Told apart by its magic bytes
+            ("data", Format::Parquet),
+        ];
+        for (name, format) in files {
+            let path = dir.path().join(name);
+            write_data_frame(&mut df, &path, format).unwrap();
+            let app = App::new(path.to_str().unwrap());
+            assert_eq!(app.page.get_column_names(), ["id", "city"], "{name}");
+            assert_eq!(app.page.height(), 3, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_keys_and_footer_follow_keymap() {
+        use ratatui::crossterm::event::{KeyCode, KeyModifiers};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        let mut df = df!["id" => [1i64, 2, 3]].unwrap();
+        write_data_frame(&mut df, &path, Format::Csv).unwrap();
+        let keymap = Keymap::from_toml("[viewer]\ndown = [\"n\"]\nquit = [\"ctrl+c\"]\n").unwrap();
+        let mut app = App::new(path.to_str().unwrap()).with_keymap(keymap);
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        assert!(app.handle_key(key(KeyCode::Char('n'))));
+        assert_eq!(app.selected_row, 1);
+        assert!(app.handle_key(key(KeyCode::Char('j'))));
+        assert_eq!(app.selected_row, 1);
+        assert!(app.handle_key(key(KeyCode::Char('q'))));
+        assert!(!app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+
+        let footer = app.footer_text().to_string();
+        assert!(footer.contains("(n) move down"), "{footer}");
+        assert!(footer.contains("(ctrl+c) quit"), "{footer}");
+        assert!(!footer.contains("(j/down)"), "{footer}");
+    }
+
+    #[test]
+    fn test_pages_through_every_row() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        let rows = 2 * PAGE_ROWS + 234;
+        let ids: Vec<i64> = (0..rows as i64).collect();
+        let mut df = df!["id" => ids].unwrap();
+        write_data_frame(&mut df, &path, Format::Csv).unwrap();
+
+        let mut app = App::new(path.to_str().unwrap());
+        assert_eq!((app.page_offset, app.page.height()), (0, PAGE_ROWS));
+        for _ in 0..500 {
+            app.poll_row_count();
+            if app.rows.is_some() {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(app.rows, Some(rows));
+
+        // Only the page of the selected row is loaded
+        app.select_row(2 * PAGE_ROWS + 100);
+        assert_eq!((app.page_offset, app.page.height()), (2 * PAGE_ROWS, 234));
+        assert_eq!(app.state.selected(), Some(100));
+        app.select_row(rows + 10);
+        assert_eq!(app.selected_row, 2 * PAGE_ROWS + 100);
+
+        app.select_row(PAGE_ROWS);
+        app.previous_row();
+        assert_eq!((app.page_offset, app.selected_row), (0, PAGE_ROWS - 1));
+        app.select_row(rows - 1);
+        app.next_row();
+        assert_eq!((app.page_offset, app.selected_row), (0, 0));
+        app.previous_row();
+        assert_eq!(app.selected_row, rows - 1);
+
+        // Summaries cover every row, not the first page, and arrive in the background
+        app.load_summary();
+        assert!(app.computing.contains("id"));
+        for _ in 0..500 {
+            app.poll_summaries();
+            if app.computing.is_empty() {
+                break;
+            }
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(app.summaries["id"].stats.count, rows);
+        assert_eq!(app.summaries["id"].stats.max, Some((rows - 1) as f64));
+        assert_eq!(app.summaries["id"].dist.as_ref().unwrap().count, rows);
+    }
+}
@@ -1,3 +1 @@
-mod actors;
-
-use actors::segmentation_engine::SegmentationEngine;
+pub mod app;
@@ -0,0 +1,100 @@
+use clap::{Arg, Command};
+use dock::data::Format;
+use polars_ex::app;
+use polars_ex::app::keymap::Keymap;
+use std::fs;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Exit codes follow riptide's table: 2 for an invalid command line, 3 for an unknown
+/// input and 1 for any other error
+fn main() -> ExitCode {
+    if let Err(e) = color_eyre::install() {
+        toollog::error(&e.to_string(), &[]);
+        return ExitCode::FAILURE;
+    }
+    let matches = match Command::new("csv-viewer")
+        .version("1.0")
+        .author("Ryan Kunkel <ryankunkel21@gmail.com>")
+        .about("A terminal-based dataset viewer built with Rust and Ratatui")
+        .arg(
+            Arg::new("file")
+                .help("The CSV, TSV, JSON, NDJSON, Parquet or XLSX file to view")
+                .required(true)
+                .value_name("FILE")
+                .index(1),
+        )
+        .arg(
+            Arg::new("keymap")
+                .long("keymap")
+                .help("A TOML file of key bindings to use instead of the defaults")
+                .value_name("FILE"),
+        )
+        .try_get_matches()
+    {
+        Ok(matches) => matches,
+        Err(e) => {
+            let _ = e.print();
+            // Help and version requests are not errors
+            return ExitCode::from(if e.use_stderr() { 2 } else { 0 });
+        }
+    };
+
+    let file_path = matches.get_one::<String>("file").unwrap();
+
+    // Validate that the file exists
+    if !Path::new(file_path).exists() {
+        toollog::error(
+            "File does not exist",
+            &[("file", file_path.as_str().into())],
+        );
+        return ExitCode::from(3);
+    }
+
+    // Check that it's a table dock can read, by its contents and extension
+    match Format::detect(Path::new(file_path)) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            toollog::error(
+                "File is not a table sail can read",
+                &[("file", file_path.as_str().into())],
+            );
+            return ExitCode::from(3);
+        }
+        Err(e) => {
+            toollog::error(
+                &format!("Cannot read the file: {e}"),
+                &[("file", file_path.as_str().into())],
+            );
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let keymap = match matches.get_one::<String>("keymap") {
+        Some(path) => match fs::read_to_string(path)
+            .map_err(Into::into)
+            .and_then(|source| Keymap::from_toml(&source))
+        {
+            Ok(keymap) => keymap,
+            Err(e) => {
+                toollog::error(
+                    &format!("Cannot use the keymap: {e}"),
+                    &[("keymap", path.as_str().into())],
+                );
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Keymap::default(),
+    };
+
+    let terminal = ratatui::init();
+    let app_result = app::App::new(file_path).with_keymap(keymap).run(terminal);
+    ratatui::restore();
+    match app_result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            toollog::error(&e.to_string(), &[]);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,5 +1,3 @@
-pub mod colors;
-
 #[cfg(test)]
 mod test {
     use burn::{backend::Wgpu, data::dataset::Dataset, tensor::Tensor};
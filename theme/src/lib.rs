@@ -0,0 +1,161 @@
+//! Table colours shared by riptide's dashboards and sail's viewer
+//!
+//! Colours come from pywal's `~/.cache/wal/colors.json` when it exists, else from a
+//! tailwind palette.
+
+use ratatui::style::{self, Color};
+use serde_json::Value;
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use style::palette::tailwind;
+
+const PALETTES: [tailwind::Palette; 4] = [
+    tailwind::BLUE,
+    tailwind::EMERALD,
+    tailwind::INDIGO,
+    tailwind::RED,
+];
+
+pub struct TableColors {
+    pub buffer_bg: Color,
+    pub header_bg: Color,
+    pub header_fg: Color,
+    pub row_fg: Color,
+    pub selected_row_style_fg: Color,
+    pub selected_column_style_fg: Color,
+    pub selected_cell_style_fg: Color,
+    pub normal_row_color: Color,
+    pub alt_row_color: Color,
+    pub footer_border_color: Color,
+}
+
+impl TableColors {
+    const fn new(color: &tailwind::Palette) -> Self {
+        Self {
+            buffer_bg: tailwind::SLATE.c950,
+            header_bg: color.c900,
+            header_fg: tailwind::SLATE.c200,
+            row_fg: tailwind::SLATE.c200,
+            selected_row_style_fg: color.c400,
+            selected_column_style_fg: color.c400,
+            selected_cell_style_fg: color.c600,
+            normal_row_color: tailwind::SLATE.c950,
+            alt_row_color: tailwind::SLATE.c900,
+            footer_border_color: color.c400,
+        }
+    }
+    pub fn default_terminal() -> Self {
+        Self {
+            header_fg: Color::Reset,
+            header_bg: Color::Reset,
+            normal_row_color: Color::Reset,
+            alt_row_color: Color::Reset,
+            row_fg: Color::Reset,
+            selected_row_style_fg: Color::Reset,
+            selected_column_style_fg: Color::Reset,
+            selected_cell_style_fg: Color::Reset,
+            buffer_bg: Color::Reset,
+            footer_border_color: Color::Reset,
+        }
+    }
+
+    pub fn new_from_pywal() -> Self {
+        match get_pywal_colors() {
+            Some(colors) => Self::from_pywal(&colors),
+            None => Self::new(&PALETTES[0]),
+        }
+    }
+
+    /// Colours from pywal's `color0` to `color15`; those missing keep the default
+    fn from_pywal(colors: &[Option<Color>]) -> Self {
+        let default = Self::new(&PALETTES[0]);
+        let color =
+            |index: usize, default: Color| colors.get(index).copied().flatten().unwrap_or(default);
+        Self {
+            buffer_bg: color(0, default.buffer_bg),
+            header_bg: color(1, default.header_bg),
+            header_fg: color(7, default.header_fg),
+            row_fg: color(7, default.row_fg),
+            selected_row_style_fg: color(5, default.selected_row_style_fg),
+            selected_column_style_fg: color(5, default.selected_column_style_fg),
+            selected_cell_style_fg: color(6, default.selected_cell_style_fg),
+            normal_row_color: color(0, default.normal_row_color),
+            alt_row_color: color(8, default.alt_row_color),
+            footer_border_color: color(4, default.footer_border_color),
+        }
+    }
+}
+
+/// pywal's colours by position, `None` where one is missing or not a hex colour
+fn get_pywal_colors() -> Option<Vec<Option<Color>>> {
+    // Get user's home directory using environment variables
+    let home = env::var("HOME").ok()?;
+    let cache_path = PathBuf::from(home).join(".cache/wal/colors.json");
+
+    // Read the pywal colors.json file
+    let mut file = match File::open(cache_path) {
+        Ok(file) => file,
+        Err(_) => return None,
+    };
+
+    let mut contents = String::new();
+    if file.read_to_string(&mut contents).is_err() {
+        return None;
+    }
+
+    parse_pywal_colors(&contents)
+}
+
+fn parse_pywal_colors(contents: &str) -> Option<Vec<Option<Color>>> {
+    // Parse the JSON
+    let json: Value = match serde_json::from_str(contents) {
+        Ok(json) => json,
+        Err(_) => return None,
+    };
+
+    // Extract colors array
+    let colors = json.get("colors")?;
+
+    // Convert hex colors to ratatui Color objects
+    let mut result = Vec::new();
+    for i in 0..16 {
+        let color_key = format!("color{}", i);
+        let hex = colors.get(&color_key).and_then(|v| v.as_str());
+        // Remove the leading # if present, then parse the hex color
+        let rgb = hex.and_then(|hex| u32::from_str_radix(hex.trim_start_matches('#'), 16).ok());
+        result.push(rgb.map(|rgb| {
+            let r = ((rgb >> 16) & 0xFF) as u8;
+            let g = ((rgb >> 8) & 0xFF) as u8;
+            let b = (rgb & 0xFF) as u8;
+            Color::Rgb(r, g, b)
+        }));
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_pywal_colors_keep_the_default() {
+        let colors = parse_pywal_colors(
+            r##"{"colors": {"color0": "#000000", "color1": "#102030", "color4": "oops"}}"##,
+        )
+        .unwrap();
+        assert_eq!(colors.len(), 16);
+        assert_eq!(colors[1], Some(Color::Rgb(0x10, 0x20, 0x30)));
+        assert_eq!(colors[4], None);
+
+        let table = TableColors::from_pywal(&colors);
+        let default = TableColors::new(&PALETTES[0]);
+        assert_eq!(table.header_bg, Color::Rgb(0x10, 0x20, 0x30));
+        assert_eq!(table.alt_row_color, default.alt_row_color);
+        assert_eq!(table.footer_border_color, default.footer_border_color);
+        assert_eq!(TableColors::from_pywal(&[]).row_fg, default.row_fg);
+        assert!(parse_pywal_colors("{}").is_none());
+    }
+}
@@ -0,0 +1,139 @@
+//! Log records of tools, for riptide's log store (FR-MON-01)
+//!
+//! Under a riptide run, where `$RIPTIDE_RUN_ID` is set, each record is printed to stderr as
+//! a JSON line, which riptide keeps with its level and fields:
+//!
+//! ```json
+//! {"level":"info","message":"Found 2 point clouds","clouds":2}
+//! ```
+//!
+//! Run by hand, the same record is printed as plain text, `Found 2 point clouds clouds=2`.
+//! riptide reads the records back with the same [`Level`].
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Set by riptide for the stages it runs
+pub const RUN_ENV: &str = "RIPTIDE_RUN_ID";
+
+/// Severity of a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "debug" => Ok(Level::Debug),
+            "info" => Ok(Level::Info),
+            "warn" | "warning" => Ok(Level::Warn),
+            "error" => Ok(Level::Error),
+            _ => Err(format!("unknown log level `{s}`")),
+        }
+    }
+}
+
+/// The record as riptide reads it: a JSON object with the level, message and fields
+pub fn json_line(level: Level, message: &str, fields: &[(&str, Value)]) -> String {
+    let mut object = Map::new();
+    object.insert("level".into(), level.to_string().into());
+    object.insert("message".into(), message.into());
+    for (key, value) in fields {
+        object.insert(key.to_string(), value.clone());
+    }
+    Value::Object(object).to_string()
+}
+
+/// The record for a person: the message, then fields as ` key=value` pairs, prefixed with
+/// the level unless it is `info`
+pub fn plain_line(level: Level, message: &str, fields: &[(&str, Value)]) -> String {
+    let mut line = match level {
+        Level::Info => message.to_string(),
+        level => format!("{level}: {message}"),
+    };
+    for (key, value) in fields {
+        match value {
+            Value::String(s) => line.push_str(&format!(" {key}={s}")),
+            value => line.push_str(&format!(" {key}={value}")),
+        }
+    }
+    line
+}
+
+/// Print a record to stderr, structured when running under riptide
+pub fn log(level: Level, message: &str, fields: &[(&str, Value)]) {
+    if env::var_os(RUN_ENV).is_some() {
+        eprintln!("{}", json_line(level, message, fields));
+    } else {
+        eprintln!("{}", plain_line(level, message, fields));
+    }
+}
+
+pub fn debug(message: &str, fields: &[(&str, Value)]) {
+    log(Level::Debug, message, fields);
+}
+
+pub fn info(message: &str, fields: &[(&str, Value)]) {
+    log(Level::Info, message, fields);
+}
+
+pub fn warn(message: &str, fields: &[(&str, Value)]) {
+    log(Level::Warn, message, fields);
+}
+
+pub fn error(message: &str, fields: &[(&str, Value)]) {
+    log(Level::Error, message, fields);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_are_json_for_riptide_and_text_for_people() {
+        let fields = [
+            ("clouds", Value::from(2)),
+            ("file", Value::from("scan.e57")),
+        ];
+        let line = json_line(Level::Info, "Found 2 point clouds", &fields);
+        let record: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(record["level"], "info");
+        assert_eq!(record["message"], "Found 2 point clouds");
+        assert_eq!(record["clouds"], 2);
+        assert_eq!(record["file"], "scan.e57");
+
+        assert_eq!(
+            plain_line(Level::Info, "Found 2 point clouds", &fields),
+            "Found 2 point clouds clouds=2 file=scan.e57"
+        );
+        assert_eq!(plain_line(Level::Warn, "no points", &[]), "warn: no points");
+    }
+}